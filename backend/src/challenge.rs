@@ -0,0 +1,195 @@
+use super::UserError;
+use chrono::{DateTime, Duration, Utc};
+use log::error;
+use rand_core::{OsRng, RngCore};
+use rocksdb::{ColumnFamily, IteratorMode, DB};
+use serde::{Deserialize, Serialize};
+use vc_core::URL;
+
+// How long a holder has to answer a challenge.
+pub const CHALLENGE_LIFETIME_SECONDS: i64 = 300;
+
+// A challenge issued for presentations to one audience, the verifier or issuer that checks them.
+#[derive(Serialize, Deserialize)]
+struct ChallengeRecord {
+    audience: URL,
+    expires: DateTime<Utc>,
+}
+
+fn get_challenge_cf<'a>(db: &'a DB, cf_path: &str) -> Result<&'a ColumnFamily, UserError> {
+    db.cf_handle(cf_path).ok_or_else(|| {
+        error!("Could not get challenge cf {}.", cf_path);
+        UserError::InternalServerError
+    })
+}
+
+// Issues a random single-use challenge for the audience, valid until the returned time. Expired
+// challenges are removed first, so unanswered ones do not accumulate.
+pub fn new_challenge(
+    db: &DB,
+    cf_path: &str,
+    audience: &URL,
+) -> Result<(String, DateTime<Utc>), UserError> {
+    let challenge_cf = get_challenge_cf(db, cf_path)?;
+    let now = Utc::now();
+    for result in db.iterator_cf(challenge_cf, IteratorMode::Start) {
+        let (key, value) = result.map_err(|e| {
+            error!("Error reading challenges from db: {:?}", e);
+            UserError::InternalServerError
+        })?;
+        let is_expired = serde_json::from_slice::<ChallengeRecord>(&value)
+            .map(|record| record.expires <= now)
+            .unwrap_or(true);
+        if is_expired {
+            db.delete_cf(challenge_cf, key).map_err(|e| {
+                error!("Error removing expired challenge from db: {:?}", e);
+                UserError::InternalServerError
+            })?;
+        }
+    }
+
+    let mut challenge_bytes = [0u8; 16];
+    OsRng.fill_bytes(&mut challenge_bytes);
+    let challenge = hex::encode(challenge_bytes);
+    let record = ChallengeRecord {
+        audience: audience.clone(),
+        expires: now + Duration::seconds(CHALLENGE_LIFETIME_SECONDS),
+    };
+    let record_bytes = serde_json::to_vec(&record).map_err(|e| {
+        error!("Error serializing challenge: {:?}", e);
+        UserError::InternalServerError
+    })?;
+    db.put_cf(challenge_cf, challenge.as_bytes(), record_bytes)
+        .map_err(|e| {
+            error!("Error adding challenge to db: {:?}", e);
+            UserError::InternalServerError
+        })?;
+    Ok((challenge, record.expires))
+}
+
+// Returns why the challenge cannot be answered for the audience, if it cannot.
+pub fn get_challenge_rejection(
+    db: &DB,
+    cf_path: &str,
+    challenge: &str,
+    audience: &URL,
+) -> Result<Option<String>, UserError> {
+    let record_bytes = db
+        .get_cf(get_challenge_cf(db, cf_path)?, challenge.as_bytes())
+        .map_err(|e| {
+            error!("Error getting challenge from db: {:?}", e);
+            UserError::InternalServerError
+        })?;
+    let record =
+        record_bytes.and_then(|bytes| serde_json::from_slice::<ChallengeRecord>(&bytes).ok());
+    Ok(match record {
+        Some(record) if record.audience != *audience => {
+            Some("Challenge was issued for another audience.".to_string())
+        }
+        Some(record) if record.expires <= Utc::now() => Some("Challenge has expired.".to_string()),
+        Some(_) => None,
+        None => Some("Unknown or reused challenge.".to_string()),
+    })
+}
+
+// Checks the challenge again and marks it used. Call this only once the presentation answering it
+// has verified, so a failed attempt does not burn the holder's challenge.
+pub fn use_challenge(
+    db: &DB,
+    cf_path: &str,
+    challenge: &str,
+    audience: &URL,
+) -> Result<Option<String>, UserError> {
+    let rejection = get_challenge_rejection(db, cf_path, challenge, audience)?;
+    if rejection.is_none() {
+        db.delete_cf(get_challenge_cf(db, cf_path)?, challenge.as_bytes())
+            .map_err(|e| {
+                error!("Error removing challenge from db: {:?}", e);
+                UserError::InternalServerError
+            })?;
+    }
+    Ok(rejection)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::new_test_app_state;
+    use crate::VERIFIER_CHALLENGE_CF_PATH;
+
+    #[test]
+    fn challenges_are_single_use_and_bound_to_their_audience() {
+        let app_state = new_test_app_state("challenge");
+        let verifier_db = app_state.verifier_db.lock().unwrap();
+        let audience = URL::new("did:example:verifier").unwrap();
+        let other_audience = URL::new("did:example:other").unwrap();
+        let (challenge, expires) =
+            new_challenge(&verifier_db, VERIFIER_CHALLENGE_CF_PATH, &audience).unwrap();
+        assert!(expires > Utc::now());
+
+        let rejection = |challenge: &str, audience: &URL| {
+            get_challenge_rejection(
+                &verifier_db,
+                VERIFIER_CHALLENGE_CF_PATH,
+                challenge,
+                audience,
+            )
+            .unwrap()
+        };
+        assert!(rejection("unknown", &audience).is_some());
+        assert!(rejection(&challenge, &other_audience).is_some());
+        assert_eq!(rejection(&challenge, &audience), None);
+        // Checking a challenge does not use it up.
+        assert_eq!(rejection(&challenge, &audience), None);
+
+        assert_eq!(
+            use_challenge(
+                &verifier_db,
+                VERIFIER_CHALLENGE_CF_PATH,
+                &challenge,
+                &audience
+            )
+            .unwrap(),
+            None
+        );
+        assert!(rejection(&challenge, &audience).is_some());
+        assert!(use_challenge(
+            &verifier_db,
+            VERIFIER_CHALLENGE_CF_PATH,
+            &challenge,
+            &audience
+        )
+        .unwrap()
+        .is_some());
+    }
+
+    #[test]
+    fn rejects_and_removes_expired_challenges() {
+        let app_state = new_test_app_state("expired-challenge");
+        let verifier_db = app_state.verifier_db.lock().unwrap();
+        let challenge_cf = verifier_db.cf_handle(VERIFIER_CHALLENGE_CF_PATH).unwrap();
+        let audience = URL::new("did:example:verifier").unwrap();
+        let record = ChallengeRecord {
+            audience: audience.clone(),
+            expires: Utc::now() - Duration::seconds(1),
+        };
+        verifier_db
+            .put_cf(
+                challenge_cf,
+                "expired",
+                serde_json::to_vec(&record).unwrap(),
+            )
+            .unwrap();
+        assert!(get_challenge_rejection(
+            &verifier_db,
+            VERIFIER_CHALLENGE_CF_PATH,
+            "expired",
+            &audience
+        )
+        .unwrap()
+        .is_some());
+
+        new_challenge(&verifier_db, VERIFIER_CHALLENGE_CF_PATH, &audience).unwrap();
+        assert_eq!(verifier_db.get_cf(challenge_cf, "expired").unwrap(), None);
+    }
+}
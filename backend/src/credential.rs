@@ -1,13 +1,27 @@
 use super::UserError;
-use crate::AppState;
-use crate::ISSUER_SIGNING_KEY_CF_PATH;
+use crate::challenge;
+use crate::keystore::{get_issuer_keystore, get_signing_key_handle};
+use crate::registry::RegistryResolver;
+use crate::status;
+use crate::verifier::{
+    get_holder_binding_rejection, get_presentation_rejection, get_proof_set_rejection, run_blocking,
+};
+use crate::{AppState, BASE_URL, ISSUER_CHALLENGE_CF_PATH};
 use actix_web::{post, web, HttpResponse, Scope};
 use chrono::{DateTime, Utc};
 use log::{error, info};
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-use vc_core::proof::{CryptographicSuite, MyEcdsaSecp256k1, ProofOptions};
-use vc_core::{ClaimProperty, Credential, VerifiableCredential, URL};
+use uuid::Uuid;
+use vc_core::cryptosuite::CryptosuiteRegistry;
+use vc_core::keystore::KeyStore;
+use vc_core::proof::ProofOptions;
+use vc_core::status_list::{REVOCATION_STATUS_PURPOSE, SUSPENSION_STATUS_PURPOSE};
+use vc_core::{
+    ClaimProperty, Credential, Evidence, Issuer, Proof, RefreshService, TermsOfUse,
+    VerifiableCredential, VerifiablePresentation, VerificationMethod, VerificationRelationship,
+    ASSERTION_METHOD_PROOF_PURPOSE, URL,
+};
 
 #[derive(Deserialize)]
 struct NewCredentialRequest {
@@ -15,10 +29,15 @@ struct NewCredentialRequest {
     credential_id: String,
     type_: Vec<String>,
     issuer_id: String,
+    verification_method_id: String,
     valid_from: String,  // Expects a RFC3339 formatted DateTime string
     valid_until: String, // Expects a RFC3339 formatted DateTime string
     credential_subject: HashMap<String, ClaimProperty>,
     credential_schema_ids: Vec<String>,
+    #[serde(default)]
+    terms_of_use: Vec<TermsOfUse>,
+    #[serde(default)]
+    evidence: Vec<Evidence>,
 }
 
 #[post("/")]
@@ -34,12 +53,6 @@ async fn new_credential(
         error!("Could not lock issuer db.");
         UserError::InternalServerError
     })?;
-    let signing_key_cf = issuer_db
-        .cf_handle(ISSUER_SIGNING_KEY_CF_PATH)
-        .ok_or_else(|| {
-            error!("Could not get issuer signing key cf.");
-            UserError::InternalServerError
-        })?;
     let mut context = Vec::new();
     for context_url in &req.context.clone() {
         let context_url = URL::new(context_url).map_err(|_e| {
@@ -52,14 +65,8 @@ async fn new_credential(
         error!("Invalid credential id.");
         UserError::BadRequest
     })?;
-    let mut type_ = Vec::new();
-    for type_url in &req.type_.clone() {
-        let type_url = URL::new(type_url).map_err(|_e| {
-            error!("Invalid type url.");
-            UserError::BadRequest
-        })?;
-        type_.push(type_url);
-    }
+    status::check_new_credential_id(&issuer_db, &credential_id)?;
+    let type_ = req.type_.clone();
     let issuer_id = URL::new(&req.issuer_id).map_err(|_e| {
         error!("Invalid issuer id.");
         UserError::BadRequest
@@ -74,6 +81,11 @@ async fn new_credential(
             error!("Could not find issuer {} in registry.", issuer_id);
             UserError::BadRequest
         })?;
+    let verification_method_id = URL::new(&req.verification_method_id).map_err(|_e| {
+        error!("Invalid verification method id.");
+        UserError::BadRequest
+    })?;
+    let verification_method = get_issuer_verification_method(&issuer, &verification_method_id)?;
     let valid_from = DateTime::parse_from_rfc3339(&req.valid_from)
         .map_err(|_e| {
             error!("Invalid valid_from date.");
@@ -87,6 +99,16 @@ async fn new_credential(
         })?
         .with_timezone(&Utc);
     let credential_subject = req.credential_subject.clone();
+    for evidence in &req.evidence {
+        if !evidence
+            .get_document_digests()
+            .iter()
+            .all(|document_digest| document_digest.is_valid_digest())
+        {
+            error!("Invalid evidence document digest.");
+            return Err(UserError::BadRequest);
+        }
+    }
     let mut credential_schema = Vec::new();
     for credential_schema_id in &req.credential_schema_ids {
         let credential_schema_id = URL::new(credential_schema_id).map_err(|_e| {
@@ -111,7 +133,7 @@ async fn new_credential(
             })?;
         credential_schema.push(schema.get_link());
     }
-    let credential = Credential::new(
+    let mut credential = Credential::new(
         context,
         credential_id.clone(),
         type_,
@@ -121,52 +143,600 @@ async fn new_credential(
         credential_subject,
         credential_schema,
     );
-    let cryptographic_suite = MyEcdsaSecp256k1::new();
-    let issuer_verification_methods = issuer.get_verification_methods();
-    if issuer_verification_methods.is_empty() {
-        error!("Issuer {} has no verification methods.", issuer_id);
-        return Err(UserError::InternalServerError);
-    }
-    let verification_method = issuer_verification_methods[0].clone();
-    let issuer_signing_key = issuer_db
-        .get_cf(
-            &signing_key_cf,
-            verification_method.get_id().get_str().as_bytes(),
-        )
+    for terms_of_use in &req.terms_of_use {
+        credential.add_terms_of_use(terms_of_use.clone());
+    }
+    for evidence in &req.evidence {
+        credential.add_evidence(evidence.clone());
+    }
+    let refresh_service_url =
+        URL::new(&format!("{}/credential/refresh", BASE_URL)).map_err(|e| {
+            error!("Invalid refresh service url: {:?}", e);
+            UserError::InternalServerError
+        })?;
+    credential.add_refresh_service(RefreshService::new(refresh_service_url));
+    // Status list entries cannot be given back, so they are only allocated once the request has
+    // been checked.
+    for status_purpose in [REVOCATION_STATUS_PURPOSE, SUSPENSION_STATUS_PURPOSE] {
+        let credential_status =
+            status::allocate_credential_status(&issuer_db, &issuer_id, status_purpose)?;
+        credential.add_credential_status(credential_status);
+    }
+    status::put_credential_status(
+        &issuer_db,
+        &credential_id,
+        credential.get_credential_status(),
+    )?;
+    let verifiable_credential = sign_credential(
+        &app_state.cryptosuites,
+        get_issuer_keystore(&app_state, &issuer_db).as_ref(),
+        verification_method,
+        credential,
+    )?;
+
+    info!("Generated new credential for user: {}", credential_id);
+    Ok(HttpResponse::Ok().json(verifiable_credential))
+}
+
+#[derive(Deserialize)]
+struct RefreshChallengeRequest {
+    issuer_id: String,
+}
+
+#[derive(Serialize)]
+struct RefreshChallengeResponse {
+    challenge: String,
+    domain: String,
+    expires: DateTime<Utc>,
+}
+
+// Issues a single-use challenge that a holder must sign into the presentation of the credential
+// they want refreshed.
+#[post("/refresh/challenge")]
+async fn new_refresh_challenge(
+    req: web::Json<RefreshChallengeRequest>,
+    app_state: web::Data<AppState>,
+) -> Result<HttpResponse, UserError> {
+    let registry = app_state.registry.lock().map_err(|_e| {
+        error!("Could not lock registry.");
+        UserError::InternalServerError
+    })?;
+    let issuer_db = app_state.issuer_db.lock().map_err(|_e| {
+        error!("Could not lock issuer db.");
+        UserError::InternalServerError
+    })?;
+    let issuer_id = URL::new(&req.issuer_id).map_err(|_e| {
+        error!("Invalid issuer id.");
+        UserError::BadRequest
+    })?;
+    registry
+        .get_issuer(&issuer_id)
         .map_err(|e| {
+            error!("Error getting issuer {} from registry: {:?}", issuer_id, e);
+            UserError::InternalServerError
+        })?
+        .ok_or_else(|| {
+            error!("Could not find issuer {} in registry.", issuer_id);
+            UserError::BadRequest
+        })?;
+    let (challenge, expires) =
+        challenge::new_challenge(&issuer_db, ISSUER_CHALLENGE_CF_PATH, &issuer_id)?;
+
+    info!("Issued refresh challenge for issuer: {}", issuer_id);
+    Ok(HttpResponse::Ok().json(RefreshChallengeResponse {
+        challenge,
+        domain: issuer_id.get_str().to_string(),
+        expires,
+    }))
+}
+
+#[derive(Deserialize)]
+struct RefreshCredentialRequest {
+    verifiable_presentation: String,
+}
+
+#[post("/refresh")]
+async fn refresh_credential(
+    req: web::Json<RefreshCredentialRequest>,
+    app_state: web::Data<AppState>,
+) -> Result<HttpResponse, UserError> {
+    let verifiable_presentation = serde_json::from_str::<VerifiablePresentation>(
+        &req.verifiable_presentation,
+    )
+    .map_err(|_| {
+        error!("Could not deserialize verifiable presentation.");
+        UserError::BadRequest
+    })?;
+    // The holder's and earlier signers' keys may be on other hosts, so the whole refresh runs off
+    // the async runtime.
+    let verifiable_credential =
+        run_blocking(move || refresh_presented_credential(&verifiable_presentation, &app_state))
+            .await?;
+    Ok(HttpResponse::Ok().json(verifiable_credential))
+}
+
+// Re-signs the presented credential with a new validity period. The holder proves they are its
+// subject by presenting it for a challenge from the issuer, and the credential must still verify
+// and be neither revoked nor suspended.
+fn refresh_presented_credential(
+    verifiable_presentation: &VerifiablePresentation,
+    app_state: &AppState,
+) -> Result<VerifiableCredential, UserError> {
+    let verifiable_credential = match verifiable_presentation
+        .get_verifiable_credential()
+        .as_slice()
+    {
+        [verifiable_credential] => verifiable_credential,
+        _ => {
+            error!("Refresh requests must present exactly one credential.");
+            return Err(UserError::BadRequest);
+        }
+    };
+    let mut credential = verifiable_credential.get_credential().clone();
+    let credential_id = credential.get_id().clone();
+    if credential.get_refresh_service().is_empty() {
+        error!(
+            "Credential {} does not offer a refresh service.",
+            credential_id
+        );
+        return Err(UserError::BadRequest);
+    }
+    let issuer_id = credential.get_issuer().clone();
+    if let Some(reason) = get_presentation_rejection(
+        verifiable_presentation,
+        &issuer_id,
+        &app_state.issuer_db,
+        ISSUER_CHALLENGE_CF_PATH,
+        &app_state.registry,
+        &app_state.cryptosuites,
+    )? {
+        error!("Cannot refresh credential {}: {}", credential_id, reason);
+        return Err(UserError::BadRequest);
+    }
+    let holder = verifiable_presentation
+        .get_holder()
+        .as_ref()
+        .ok_or_else(|| {
+            error!("No holder found in verifiable presentation.");
+            UserError::BadRequest
+        })?;
+    let rejection = match get_holder_binding_rejection(&credential, holder, false) {
+        Some(reason) => Some(reason),
+        None => get_proof_set_rejection(
+            &app_state.cryptosuites,
+            &RegistryResolver::new(&app_state.registry),
+            verifiable_credential,
+        )?,
+    };
+    if let Some(reason) = rejection {
+        error!("Cannot refresh credential {}: {}", credential_id, reason);
+        return Err(UserError::BadRequest);
+    }
+
+    let registry = app_state.registry.lock().map_err(|_e| {
+        error!("Could not lock registry.");
+        UserError::InternalServerError
+    })?;
+    let issuer_db = app_state.issuer_db.lock().map_err(|_e| {
+        error!("Could not lock issuer db.");
+        UserError::InternalServerError
+    })?;
+    let issuer = registry
+        .get_issuer(&issuer_id)
+        .map_err(|e| {
+            error!("Error getting issuer {} from registry: {:?}", issuer_id, e);
+            UserError::InternalServerError
+        })?
+        .ok_or_else(|| {
+            error!("Could not find issuer {} in registry.", issuer_id);
+            UserError::BadRequest
+        })?;
+    for status_purpose in [REVOCATION_STATUS_PURPOSE, SUSPENSION_STATUS_PURPOSE] {
+        if status::is_credential_status_set(&issuer_db, &credential_id, status_purpose)? {
             error!(
-                "Error getting signing key for issuer {} from db: {:?}",
-                issuer_id, e
+                "Credential {} has status {} set.",
+                credential_id, status_purpose
             );
+            return Err(UserError::BadRequest);
+        }
+    }
+
+    let (Some(original_valid_from), Some(original_valid_until)) =
+        (credential.get_valid_from(), credential.get_valid_until())
+    else {
+        error!(
+            "Credential {} has no validity period to renew.",
+            credential_id
+        );
+        return Err(UserError::BadRequest);
+    };
+    let valid_from = Utc::now();
+    let valid_until = valid_from + (*original_valid_until - *original_valid_from);
+    credential.set_validity_period(valid_from, valid_until);
+    // Refreshed credentials are always signed with the issuer's current key, so rotated out keys
+    // stop being used.
+    let verification_method = issuer
+        .get_newest_active_verification_method(VerificationRelationship::AssertionMethod)
+        .ok_or_else(|| {
+            error!(
+                "Issuer {} has no active verification methods.",
+                issuer.get_id()
+            );
+            UserError::BadRequest
+        })?;
+    let keystore = get_issuer_keystore(app_state, &issuer_db);
+    let verifiable_credential = sign_credential(
+        &app_state.cryptosuites,
+        keystore.as_ref(),
+        verification_method,
+        credential,
+    )?;
+
+    info!("Refreshed credential: {}", credential_id);
+    Ok(verifiable_credential)
+}
+
+#[derive(Deserialize)]
+struct EndorseCredentialRequest {
+    verifiable_credential: String,
+    issuer_id: String,
+    verification_method_id: String,
+    #[serde(default)]
+    chain: bool,
+}
+
+// Adds an endorsing issuer's proof to an already signed credential. Chained endorsements also
+// sign the credential's last proof, so the endorsement cannot be moved to another proof set.
+#[post("/endorse")]
+async fn endorse_credential(
+    req: web::Json<EndorseCredentialRequest>,
+    app_state: web::Data<AppState>,
+) -> Result<HttpResponse, UserError> {
+    let mut verifiable_credential = VerifiableCredential::from_json(&req.verifiable_credential)
+        .map_err(|_e| {
+            error!("Could not deserialize verifiable credential.");
+            UserError::BadRequest
+        })?;
+    let credential_id = verifiable_credential.get_credential().get_id().clone();
+    let issuer_id = URL::new(&req.issuer_id).map_err(|_e| {
+        error!("Invalid issuer id.");
+        UserError::BadRequest
+    })?;
+    // The existing proofs may name keys on other hosts, so they are checked before taking locks.
+    let endorsed_credential = verifiable_credential.clone();
+    let verifier_app_state = app_state.clone();
+    if let Some(reason) = run_blocking(move || {
+        get_proof_set_rejection(
+            &verifier_app_state.cryptosuites,
+            &RegistryResolver::new(&verifier_app_state.registry),
+            &endorsed_credential,
+        )
+    })
+    .await?
+    {
+        error!("Cannot endorse credential {}: {}", credential_id, reason);
+        return Err(UserError::BadRequest);
+    }
+
+    let registry = app_state.registry.lock().map_err(|_e| {
+        error!("Could not lock registry.");
+        UserError::InternalServerError
+    })?;
+    let issuer_db = app_state.issuer_db.lock().map_err(|_e| {
+        error!("Could not lock issuer db.");
+        UserError::InternalServerError
+    })?;
+    let issuer = registry
+        .get_issuer(&issuer_id)
+        .map_err(|e| {
+            error!("Error getting issuer {} from registry: {:?}", issuer_id, e);
             UserError::InternalServerError
         })?
         .ok_or_else(|| {
-            error!("Could not find signing key for issuer {} in db.", issuer_id);
+            error!("Could not find issuer {} in registry.", issuer_id);
+            UserError::BadRequest
+        })?;
+    let verification_method_id = URL::new(&req.verification_method_id).map_err(|_e| {
+        error!("Invalid verification method id.");
+        UserError::BadRequest
+    })?;
+    let verification_method = get_issuer_verification_method(&issuer, &verification_method_id)?;
+    // Verifiers can only find an endorser's key through a DID URL.
+    if verification_method_id.did().is_none() {
+        error!(
+            "Endorsing verification method {} is not a DID URL.",
+            verification_method_id
+        );
+        return Err(UserError::BadRequest);
+    }
+
+    let previous_proof = if req.chain {
+        let previous_proof = verifiable_credential.get_proof().last().ok_or_else(|| {
+            error!("No proofs found in verifiable credential.");
             UserError::BadRequest
         })?;
-    let proof_purpose = "Proof Purpose".to_string();
-    let created = Utc::now();
-    let domain = "Proof Domain".to_string();
-    let challenge = "Proof Challenge".to_string();
-    let proof_options = ProofOptions::new(
+        if previous_proof.get_id().is_none() {
+            error!("Cannot chain to a proof without an id.");
+            return Err(UserError::BadRequest);
+        }
+        Some(previous_proof)
+    } else {
+        None
+    };
+    let proof = generate_credential_proof(
+        &app_state.cryptosuites,
+        get_issuer_keystore(&app_state, &issuer_db).as_ref(),
         verification_method,
-        proof_purpose,
-        created,
-        domain,
-        challenge,
+        verifiable_credential.get_credential(),
+        previous_proof,
+    )?;
+    verifiable_credential.add_proof(proof);
+
+    info!("Issuer {} endorsed credential {}", issuer_id, credential_id);
+    Ok(HttpResponse::Ok().json(verifiable_credential))
+}
+
+#[derive(Deserialize)]
+struct CredentialSuspensionRequest {
+    credential_id: String,
+}
+
+async fn set_credential_suspension(
+    req: web::Json<CredentialSuspensionRequest>,
+    app_state: web::Data<AppState>,
+    suspended: bool,
+) -> Result<HttpResponse, UserError> {
+    let issuer_db = app_state.issuer_db.lock().map_err(|_e| {
+        error!("Could not lock issuer db.");
+        UserError::InternalServerError
+    })?;
+    let credential_id = URL::new(&req.credential_id).map_err(|_e| {
+        error!("Invalid credential id.");
+        UserError::BadRequest
+    })?;
+    status::set_credential_status(
+        &issuer_db,
+        &credential_id,
+        SUSPENSION_STATUS_PURPOSE,
+        suspended,
+    )?;
+
+    info!(
+        "Set suspension of credential {} to {}.",
+        credential_id, suspended
     );
-    let proof = cryptographic_suite
-        .generate_proof(&credential, &issuer_signing_key, &proof_options)
+    Ok(HttpResponse::Ok().json(suspended))
+}
+
+#[post("/suspend")]
+async fn suspend_credential(
+    req: web::Json<CredentialSuspensionRequest>,
+    app_state: web::Data<AppState>,
+) -> Result<HttpResponse, UserError> {
+    set_credential_suspension(req, app_state, true).await
+}
+
+#[post("/reinstate")]
+async fn reinstate_credential(
+    req: web::Json<CredentialSuspensionRequest>,
+    app_state: web::Data<AppState>,
+) -> Result<HttpResponse, UserError> {
+    set_credential_suspension(req, app_state, false).await
+}
+
+pub(crate) fn sign_credential(
+    cryptosuites: &CryptosuiteRegistry,
+    keystore: &dyn KeyStore,
+    verification_method: &VerificationMethod,
+    credential: Credential,
+) -> Result<VerifiableCredential, UserError> {
+    let proof = generate_credential_proof(
+        cryptosuites,
+        keystore,
+        verification_method,
+        &credential,
+        None,
+    )?;
+    Ok(VerifiableCredential::new(credential, vec![proof]))
+}
+
+// Finds the issuer's verification method with the given id, which must be authorized to sign
+// credentials.
+pub(crate) fn get_issuer_verification_method<'a>(
+    issuer: &'a Issuer,
+    verification_method_id: &URL,
+) -> Result<&'a VerificationMethod, UserError> {
+    let verification_method = issuer
+        .get_verification_method(verification_method_id)
+        .ok_or_else(|| {
+            error!(
+                "Could not find verification method {} for issuer {}.",
+                verification_method_id,
+                issuer.get_id()
+            );
+            UserError::BadRequest
+        })?;
+    if !issuer.is_authorized(
+        verification_method_id,
+        VerificationRelationship::AssertionMethod,
+    ) {
+        error!(
+            "Verification method {} is not an assertion method of issuer {}.",
+            verification_method_id,
+            issuer.get_id()
+        );
+        return Err(UserError::BadRequest);
+    }
+    Ok(verification_method)
+}
+
+// Signs the credential with the verification method's key. Passing a previous proof chains the
+// new proof to it.
+fn generate_credential_proof(
+    cryptosuites: &CryptosuiteRegistry,
+    keystore: &dyn KeyStore,
+    verification_method: &VerificationMethod,
+    credential: &Credential,
+    previous_proof: Option<&Proof>,
+) -> Result<Proof, UserError> {
+    let verification_method_id = verification_method.get_id();
+    if !verification_method.is_active() {
+        error!(
+            "Verification method {} is {} and can no longer sign.",
+            verification_method_id,
+            verification_method.get_status()
+        );
+        return Err(UserError::BadRequest);
+    }
+    let (key_type, _public_key) = verification_method.get_public_key().map_err(|e| {
+        error!(
+            "Could not decode verification method {} key: {:?}",
+            verification_method_id, e
+        );
+        UserError::InternalServerError
+    })?;
+    let signing_key = get_signing_key_handle(keystore, verification_method_id, key_type)?;
+    let cryptographic_suite = cryptosuites
+        .get_suite_for_verification_method(verification_method)
         .map_err(|e| {
-            error!("Error generating proof for verifiable credential: {:?}", e);
+            error!("Could not select a cryptosuite: {:?}", e);
             UserError::InternalServerError
         })?;
-    let verifiable_credential = VerifiableCredential::new(credential, vec![proof]);
+    let mut proof_options = get_credential_proof_options(verification_method.clone());
+    let proof_id = URL::new(&format!("urn:uuid:{}", Uuid::new_v4())).map_err(|e| {
+        error!("Invalid proof id: {:?}", e);
+        UserError::InternalServerError
+    })?;
+    proof_options.set_id(proof_id);
+    if let Some(previous_proof) = previous_proof {
+        proof_options.set_previous_proof(previous_proof.clone());
+    }
+    cryptographic_suite
+        .generate_proof(credential, signing_key.as_ref(), &proof_options)
+        .map_err(|e| {
+            error!("Error generating proof for verifiable credential: {:?}", e);
+            UserError::InternalServerError
+        })
+}
 
-    info!("Generated new credential for user: {}", credential_id);
-    Ok(HttpResponse::Ok().json(verifiable_credential))
+// Credential proofs carry no challenge or domain. Those bind presentations to a verifier's
+// request, and the issuer has none to answer.
+pub(crate) fn get_credential_proof_options(
+    verification_method: VerificationMethod,
+) -> ProofOptions {
+    ProofOptions::new(
+        verification_method,
+        ASSERTION_METHOD_PROOF_PURPOSE.to_string(),
+        Utc::now(),
+        None,
+        None,
+    )
 }
 
 pub fn init_routes() -> Scope {
-    web::scope("/credential").service(new_credential)
+    web::scope("/credential")
+        .service(new_credential)
+        .service(new_refresh_challenge)
+        .service(refresh_credential)
+        .service(endorse_credential)
+        .service(suspend_credential)
+        .service(reinstate_credential)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::{
+        new_test_app_state, new_test_credential, new_test_holder, new_test_issuer,
+        new_test_presentation,
+    };
+
+    fn new_refresh_challenge(app_state: &AppState, issuer_id: &URL) -> String {
+        let issuer_db = app_state.issuer_db.lock().unwrap();
+        challenge::new_challenge(&issuer_db, ISSUER_CHALLENGE_CF_PATH, issuer_id)
+            .unwrap()
+            .0
+    }
+
+    #[test]
+    fn refreshes_credentials_presented_by_their_subject() {
+        let app_state = new_test_app_state("refresh");
+        let issuer_id = new_test_issuer(&app_state);
+        let (signing_key, holder) = new_test_holder();
+        let verifiable_credential = new_test_credential(&app_state, &issuer_id, Some(&holder));
+        let challenge = new_refresh_challenge(&app_state, &issuer_id);
+        let verifiable_presentation = new_test_presentation(
+            &signing_key,
+            &holder,
+            vec![verifiable_credential.clone()],
+            issuer_id.get_str(),
+            &challenge,
+        );
+
+        let refreshed_credential =
+            refresh_presented_credential(&verifiable_presentation, &app_state).unwrap();
+        assert!(
+            refreshed_credential.get_credential().get_id()
+                == verifiable_credential.get_credential().get_id()
+        );
+        assert!(
+            refreshed_credential.get_credential().get_valid_from()
+                > verifiable_credential.get_credential().get_valid_from()
+        );
+        let registry = app_state.registry.lock().unwrap();
+        let issuer = registry.get_issuer(&issuer_id).unwrap().unwrap();
+        let newest_verification_method = issuer
+            .get_newest_active_verification_method(VerificationRelationship::AssertionMethod)
+            .unwrap();
+        assert!(
+            refreshed_credential.get_proof()[0].get_verification_method()
+                == newest_verification_method.get_id()
+        );
+        drop(registry);
+
+        // The challenge was used up.
+        assert!(refresh_presented_credential(&verifiable_presentation, &app_state).is_err());
+    }
+
+    #[test]
+    fn rejects_refreshes_by_another_holder() {
+        let app_state = new_test_app_state("refresh-other-holder");
+        let issuer_id = new_test_issuer(&app_state);
+        let (_signing_key, holder) = new_test_holder();
+        let (other_signing_key, other_holder) = new_test_holder();
+        let verifiable_credential = new_test_credential(&app_state, &issuer_id, Some(&holder));
+        let challenge = new_refresh_challenge(&app_state, &issuer_id);
+        let verifiable_presentation = new_test_presentation(
+            &other_signing_key,
+            &other_holder,
+            vec![verifiable_credential],
+            issuer_id.get_str(),
+            &challenge,
+        );
+        assert!(refresh_presented_credential(&verifiable_presentation, &app_state).is_err());
+    }
+
+    #[test]
+    fn rejects_refreshes_of_suspended_credentials() {
+        let app_state = new_test_app_state("refresh-suspended");
+        let issuer_id = new_test_issuer(&app_state);
+        let (signing_key, holder) = new_test_holder();
+        let verifiable_credential = new_test_credential(&app_state, &issuer_id, Some(&holder));
+        status::set_credential_status(
+            &app_state.issuer_db.lock().unwrap(),
+            verifiable_credential.get_credential().get_id(),
+            SUSPENSION_STATUS_PURPOSE,
+            true,
+        )
+        .unwrap();
+        let challenge = new_refresh_challenge(&app_state, &issuer_id);
+        let verifiable_presentation = new_test_presentation(
+            &signing_key,
+            &holder,
+            vec![verifiable_credential],
+            issuer_id.get_str(),
+            &challenge,
+        );
+        assert!(refresh_presented_credential(&verifiable_presentation, &app_state).is_err());
+    }
 }
@@ -1,11 +1,20 @@
 use super::UserError;
-use crate::{AppState, ISSUER_SIGNING_KEY_CF_PATH};
+use crate::keystore::{delete_signing_key, get_issuer_keystore, store_signing_key};
+use crate::registry::{RegistryError, VerifiableDataRegistry};
+use crate::AppState;
 use actix_web::{get, post, web, HttpResponse, Scope};
+use chrono::{DateTime, Utc};
 use k256::ecdsa::{SigningKey, VerifyingKey};
 use log::{error, info};
 use rand_core::OsRng;
 use serde::Deserialize;
-use vc_core::{Issuer, VerificationMethod, URL};
+use vc_core::cryptosuite::MY_K256_VERIFICATION_METHOD_TYPE;
+use vc_core::jwk::JWK;
+use vc_core::multibase::{self, KeyType};
+use vc_core::{
+    did_key, Issuer, VerificationMethod, VerificationRelationship,
+    ECDSA_SECP256R1_VERIFICATION_KEY_2019_TYPE, ED25519_VERIFICATION_KEY_2020_TYPE, URL,
+};
 
 #[derive(Deserialize)]
 struct AddIssuerRequest {
@@ -81,10 +90,157 @@ async fn get_all_issuers(
     Ok(HttpResponse::Ok().json(issuers))
 }
 
+#[derive(Deserialize)]
+struct AddDIDKeyIssuerRequest {
+    name: String,
+}
+
+#[post("/did_key")]
+async fn new_did_key_issuer(
+    req: web::Json<AddDIDKeyIssuerRequest>,
+    app_state: web::Data<AppState>,
+) -> Result<HttpResponse, UserError> {
+    let mut registry = app_state.registry.lock().map_err(|_e| {
+        error!("Could not lock registry.");
+        UserError::InternalServerError
+    })?;
+    let issuer_db = app_state.issuer_db.lock().map_err(|_e| {
+        error!("Could not lock issuer db.");
+        UserError::InternalServerError
+    })?;
+    let keystore = get_issuer_keystore(&app_state, &issuer_db);
+
+    let signing_key = SigningKey::random(&mut OsRng);
+    let verifying_key = VerifyingKey::from(&signing_key).to_sec1_bytes();
+    let issuer_id = did_key::did_from_secp256k1_public_key(&verifying_key).map_err(|e| {
+        error!("Error deriving did:key: {:?}", e);
+        UserError::InternalServerError
+    })?;
+    let did_document = did_key::resolve(&issuer_id).map_err(|e| {
+        error!("Error resolving {}: {:?}", issuer_id, e);
+        UserError::InternalServerError
+    })?;
+    let mut issuer = Issuer::new(issuer_id.clone(), req.name.clone());
+    for verification_method in did_document.get_verification_methods() {
+        store_signing_key(
+            keystore.as_ref(),
+            verification_method.get_id(),
+            &signing_key.to_bytes(),
+        )?;
+        let verification_relationships: Vec<VerificationRelationship> =
+            VerificationRelationship::ALL
+                .into_iter()
+                .filter(|relationship| {
+                    did_document.is_authorized(verification_method.get_id(), *relationship)
+                })
+                .collect();
+        let mut verification_method = verification_method.clone();
+        verification_method.set_created(Utc::now());
+        issuer
+            .new_verification_method(verification_method, &verification_relationships)
+            .map_err(|e| {
+                error!("Error adding verification method to {}: {:?}", issuer_id, e);
+                UserError::InternalServerError
+            })?;
+    }
+
+    registry.new_issuer(issuer).map_err(|e| {
+        error!("Error adding issuer {} to registry: {:?}", issuer_id, e);
+        UserError::InternalServerError
+    })?;
+
+    info!("Added did:key issuer to registry: {}", issuer_id);
+    Ok(HttpResponse::Ok().json(issuer_id))
+}
+
+// Generates a key pair for the requested verification method type.
+fn generate_key_pair(
+    verification_method_type: &str,
+) -> Result<(KeyType, Vec<u8>, Vec<u8>), UserError> {
+    match verification_method_type {
+        MY_K256_VERIFICATION_METHOD_TYPE => {
+            let signing_key = SigningKey::random(&mut OsRng);
+            let verifying_key = VerifyingKey::from(&signing_key);
+            Ok((
+                KeyType::Secp256k1,
+                signing_key.to_bytes().to_vec(),
+                verifying_key.to_encoded_point(true).as_bytes().to_vec(),
+            ))
+        }
+        ED25519_VERIFICATION_KEY_2020_TYPE => {
+            let signing_key = ed25519_dalek::SigningKey::generate(&mut OsRng);
+            Ok((
+                KeyType::Ed25519,
+                signing_key.to_bytes().to_vec(),
+                signing_key.verifying_key().to_bytes().to_vec(),
+            ))
+        }
+        ECDSA_SECP256R1_VERIFICATION_KEY_2019_TYPE => {
+            let signing_key = p256::ecdsa::SigningKey::random(&mut OsRng);
+            let verifying_key = p256::ecdsa::VerifyingKey::from(&signing_key);
+            Ok((
+                KeyType::P256,
+                signing_key.to_bytes().to_vec(),
+                verifying_key.to_encoded_point(true).as_bytes().to_vec(),
+            ))
+        }
+        _ => {
+            error!(
+                "Unsupported verification method type: {}",
+                verification_method_type
+            );
+            Err(UserError::BadRequest)
+        }
+    }
+}
+
+fn get_registry_user_error(e: RegistryError) -> UserError {
+    match e {
+        RegistryError::ArgumentError(_) => UserError::BadRequest,
+        RegistryError::ConflictError(_) => UserError::Conflict,
+        _ => UserError::InternalServerError,
+    }
+}
+
+// Rejects a verification method id that is already taken before its signing key is stored, since
+// the key would replace the one stored under that id.
+fn check_new_verification_method_id(
+    registry: &VerifiableDataRegistry,
+    verification_method_id: &URL,
+) -> Result<(), UserError> {
+    let is_taken = registry
+        .has_verification_method(verification_method_id)
+        .map_err(|e| {
+            error!(
+                "Error checking verification method {}: {:?}",
+                verification_method_id, e
+            );
+            UserError::InternalServerError
+        })?;
+    if is_taken {
+        error!(
+            "Verification method {} already exists.",
+            verification_method_id
+        );
+        return Err(UserError::Conflict);
+    }
+    Ok(())
+}
+
+// Keys are authorized for signing credentials and presentations unless stated otherwise.
+fn default_verification_relationships() -> Vec<VerificationRelationship> {
+    vec![
+        VerificationRelationship::AssertionMethod,
+        VerificationRelationship::Authentication,
+    ]
+}
+
 #[derive(Deserialize)]
 struct AddVerificationMethodRequest {
     verification_method_id: String,
     type_: String,
+    #[serde(default = "default_verification_relationships")]
+    verification_relationships: Vec<VerificationRelationship>,
 }
 
 #[post("/{id}/verification_method")]
@@ -101,12 +257,69 @@ async fn new_verification_method(
         error!("Could not lock issuer db.");
         UserError::InternalServerError
     })?;
-    let signing_key_cf = issuer_db
-        .cf_handle(ISSUER_SIGNING_KEY_CF_PATH)
-        .ok_or_else(|| {
-            error!("Could not get issuer signing key cf.");
-            UserError::InternalServerError
+    let keystore = get_issuer_keystore(&app_state, &issuer_db);
+    let issuer_id = URL::new(&path.into_inner()).map_err(|_e| {
+        error!("Invalid issuer id.");
+        UserError::BadRequest
+    })?;
+    let verification_method_id = URL::new(&req.verification_method_id).map_err(|_e| {
+        error!("Invalid verification method id.");
+        UserError::BadRequest
+    })?;
+
+    check_new_verification_method_id(&registry, &verification_method_id)?;
+
+    let (key_type, signing_key, verifying_key) = generate_key_pair(&req.type_)?;
+    store_signing_key(keystore.as_ref(), &verification_method_id, &signing_key)?;
+
+    let mut verification_method = VerificationMethod::new(
+        verification_method_id.clone(),
+        req.type_.clone(),
+        issuer_id.clone(),
+        multibase::encode_public_key(key_type, &verifying_key),
+    );
+    verification_method.set_created(Utc::now());
+
+    registry
+        .new_verification_method(
+            &issuer_id,
+            verification_method,
+            &req.verification_relationships,
+        )
+        .map_err(|e| {
+            error!(
+                "Error adding verification method {} to registry: {:?}",
+                verification_method_id, e
+            );
+            get_registry_user_error(e)
         })?;
+
+    Ok(HttpResponse::Ok().json(verification_method_id))
+}
+
+#[derive(Deserialize)]
+struct ImportVerificationMethodRequest {
+    verification_method_id: String,
+    jwk: JWK,
+    #[serde(default = "default_verification_relationships")]
+    verification_relationships: Vec<VerificationRelationship>,
+}
+
+#[post("/{id}/verification_method/jwk")]
+async fn import_verification_method(
+    req: web::Json<ImportVerificationMethodRequest>,
+    path: web::Path<String>,
+    app_state: web::Data<AppState>,
+) -> Result<HttpResponse, UserError> {
+    let mut registry = app_state.registry.lock().map_err(|_e| {
+        error!("Could not lock registry.");
+        UserError::InternalServerError
+    })?;
+    let issuer_db = app_state.issuer_db.lock().map_err(|_e| {
+        error!("Could not lock issuer db.");
+        UserError::InternalServerError
+    })?;
+    let keystore = get_issuer_keystore(&app_state, &issuer_db);
     let issuer_id = URL::new(&path.into_inner()).map_err(|_e| {
         error!("Invalid issuer id.");
         UserError::BadRequest
@@ -116,43 +329,195 @@ async fn new_verification_method(
         UserError::BadRequest
     })?;
 
-    let signing_key = SigningKey::random(&mut OsRng);
-    let verifying_key = VerifyingKey::from(&signing_key).to_sec1_bytes();
-    issuer_db
-        .put_cf(
-            signing_key_cf,
-            verification_method_id.get_str().as_bytes(),
-            signing_key.to_bytes(),
+    check_new_verification_method_id(&registry, &verification_method_id)?;
+
+    let jwk = &req.jwk;
+    if jwk.is_private() {
+        let (key_type, private_key) = jwk.get_private_key().map_err(|e| {
+            error!("Invalid private JWK: {:?}", e);
+            UserError::BadRequest
+        })?;
+        let is_valid_signing_key = match key_type {
+            KeyType::Secp256k1 => SigningKey::from_slice(&private_key).is_ok(),
+            KeyType::P256 => p256::ecdsa::SigningKey::from_slice(&private_key).is_ok(),
+            // Any 32 byte seed is a valid Ed25519 key, and its length was checked above.
+            KeyType::Ed25519 => true,
+        };
+        if !is_valid_signing_key {
+            error!("Invalid {:?} signing key.", key_type);
+            return Err(UserError::BadRequest);
+        }
+        store_signing_key(keystore.as_ref(), &verification_method_id, &private_key)?;
+    } else {
+        jwk.get_public_key().map_err(|e| {
+            error!("Invalid public JWK: {:?}", e);
+            UserError::BadRequest
+        })?;
+    }
+
+    let mut verification_method = VerificationMethod::new_jwk(
+        verification_method_id.clone(),
+        issuer_id.clone(),
+        jwk.clone(),
+    );
+    verification_method.set_created(Utc::now());
+
+    registry
+        .new_verification_method(
+            &issuer_id,
+            verification_method,
+            &req.verification_relationships,
         )
         .map_err(|e| {
-            error!("Error adding signing key to db: {:?}", e);
-            UserError::InternalServerError
+            error!(
+                "Error adding verification method {} to registry: {:?}",
+                verification_method_id, e
+            );
+            get_registry_user_error(e)
         })?;
 
+    info!(
+        "Imported verification method {} for issuer {}",
+        verification_method_id, issuer_id
+    );
+    Ok(HttpResponse::Ok().json(verification_method_id))
+}
+
+#[derive(Deserialize)]
+struct RotateVerificationMethodRequest {
+    verification_method_id: String,
+    new_verification_method_id: String,
+    type_: String,
+}
+
+// Replaces an active key with a freshly generated one. Credentials signed with the old key before
+// the rotation still verify, but the old key can no longer sign.
+#[post("/{id}/verification_method/rotate")]
+async fn rotate_verification_method(
+    req: web::Json<RotateVerificationMethodRequest>,
+    path: web::Path<String>,
+    app_state: web::Data<AppState>,
+) -> Result<HttpResponse, UserError> {
+    let mut registry = app_state.registry.lock().map_err(|_e| {
+        error!("Could not lock registry.");
+        UserError::InternalServerError
+    })?;
+    let issuer_db = app_state.issuer_db.lock().map_err(|_e| {
+        error!("Could not lock issuer db.");
+        UserError::InternalServerError
+    })?;
+    let keystore = get_issuer_keystore(&app_state, &issuer_db);
+    let issuer_id = URL::new(&path.into_inner()).map_err(|_e| {
+        error!("Invalid issuer id.");
+        UserError::BadRequest
+    })?;
+    let verification_method_id = URL::new(&req.verification_method_id).map_err(|_e| {
+        error!("Invalid verification method id.");
+        UserError::BadRequest
+    })?;
+    let new_verification_method_id = URL::new(&req.new_verification_method_id).map_err(|_e| {
+        error!("Invalid verification method id.");
+        UserError::BadRequest
+    })?;
+
+    let (key_type, signing_key, verifying_key) = generate_key_pair(&req.type_)?;
     let verification_method = VerificationMethod::new(
-        verification_method_id.clone(),
+        new_verification_method_id.clone(),
         req.type_.clone(),
         issuer_id.clone(),
-        verifying_key.to_vec(),
+        multibase::encode_public_key(key_type, &verifying_key),
+    );
+    registry
+        .rotate_verification_method(
+            &issuer_id,
+            &verification_method_id,
+            verification_method,
+            Utc::now(),
+        )
+        .map_err(|e| {
+            error!(
+                "Error rotating verification method {}: {:?}",
+                verification_method_id, e
+            );
+            get_registry_user_error(e)
+        })?;
+    store_signing_key(keystore.as_ref(), &new_verification_method_id, &signing_key)?;
+    delete_signing_key(keystore.as_ref(), &verification_method_id)?;
+
+    info!(
+        "Rotated verification method {} to {} for issuer {}",
+        verification_method_id, new_verification_method_id, issuer_id
     );
+    Ok(HttpResponse::Ok().json(new_verification_method_id))
+}
+
+#[derive(Deserialize)]
+struct RevokeVerificationMethodRequest {
+    verification_method_id: String,
+    revoked: Option<String>, // Expects a RFC3339 formatted DateTime string, defaults to now
+}
+
+// Revokes a compromised key. Proofs created with it from the revocation time on are rejected, so
+// only credentials signed after the compromise need to be re-issued.
+#[post("/{id}/verification_method/revoke")]
+async fn revoke_verification_method(
+    req: web::Json<RevokeVerificationMethodRequest>,
+    path: web::Path<String>,
+    app_state: web::Data<AppState>,
+) -> Result<HttpResponse, UserError> {
+    let mut registry = app_state.registry.lock().map_err(|_e| {
+        error!("Could not lock registry.");
+        UserError::InternalServerError
+    })?;
+    let issuer_db = app_state.issuer_db.lock().map_err(|_e| {
+        error!("Could not lock issuer db.");
+        UserError::InternalServerError
+    })?;
+    let keystore = get_issuer_keystore(&app_state, &issuer_db);
+    let issuer_id = URL::new(&path.into_inner()).map_err(|_e| {
+        error!("Invalid issuer id.");
+        UserError::BadRequest
+    })?;
+    let verification_method_id = URL::new(&req.verification_method_id).map_err(|_e| {
+        error!("Invalid verification method id.");
+        UserError::BadRequest
+    })?;
+    let revoked = match &req.revoked {
+        Some(revoked) => DateTime::parse_from_rfc3339(revoked)
+            .map_err(|_e| {
+                error!("Invalid revoked date.");
+                UserError::BadRequest
+            })?
+            .with_timezone(&Utc),
+        None => Utc::now(),
+    };
 
     registry
-        .new_verification_method(&issuer_id, verification_method)
+        .revoke_verification_method(&issuer_id, &verification_method_id, revoked)
         .map_err(|e| {
             error!(
-                "Error adding verification method {} to registry: {:?}",
+                "Error revoking verification method {}: {:?}",
                 verification_method_id, e
             );
-            UserError::InternalServerError
+            get_registry_user_error(e)
         })?;
+    delete_signing_key(keystore.as_ref(), &verification_method_id)?;
 
+    info!(
+        "Revoked verification method {} for issuer {} as of {}",
+        verification_method_id, issuer_id, revoked
+    );
     Ok(HttpResponse::Ok().json(verification_method_id))
 }
 
 pub fn init_routes() -> Scope {
     web::scope("/issuer")
         .service(new_issuer)
+        .service(new_did_key_issuer)
         .service(get_issuer)
         .service(get_all_issuers)
         .service(new_verification_method)
+        .service(import_verification_method)
+        .service(rotate_verification_method)
+        .service(revoke_verification_method)
 }
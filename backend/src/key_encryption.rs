@@ -0,0 +1,324 @@
+use crate::ISSUER_KEY_ENCRYPTION_CF_PATH;
+use argon2::Argon2;
+use chacha20poly1305::aead::{Aead, KeyInit, Payload};
+use chacha20poly1305::{XChaCha20Poly1305, XNonce};
+use rand_core::{OsRng, RngCore};
+use rocksdb::DB;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::error::Error;
+use std::fmt;
+use vc_core::keystore::{KeyStorage, KeyStoreError};
+use vc_core::{multibase, URL};
+use zeroize::{Zeroize, Zeroizing};
+
+const PASSPHRASE_SALT_KEY: &str = "passphrase_salt";
+
+#[allow(clippy::enum_variant_names)]
+#[derive(Debug)]
+pub enum KeyEncryptionError {
+    ConfigurationError(String),
+    DatabaseError(String),
+    StorageError(String),
+    EncryptionError(String),
+    DecryptionError(String),
+    UnknownMasterKeyError(String),
+}
+
+impl Error for KeyEncryptionError {}
+
+impl fmt::Display for KeyEncryptionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::ConfigurationError(e) => write!(f, "Invalid master key configuration: {}", e),
+            Self::DatabaseError(e) => write!(f, "Database error: {}", e),
+            Self::StorageError(e) => write!(f, "Key storage error: {}", e),
+            Self::EncryptionError(e) => write!(f, "Encryption error: {}", e),
+            Self::DecryptionError(e) => write!(f, "Decryption error: {}", e),
+            Self::UnknownMasterKeyError(e) => write!(f, "Unknown master key: {}", e),
+        }
+    }
+}
+
+// The key that signing keys are encrypted under, identified by a fingerprint so that each
+// encrypted signing key records which master key it needs.
+pub struct MasterKey {
+    id: String,
+    key: [u8; 32],
+}
+
+impl MasterKey {
+    pub fn new(key: [u8; 32]) -> Self {
+        Self {
+            id: multibase::encode(&Sha256::digest(key)[..8]),
+            key,
+        }
+    }
+
+    pub fn from_passphrase(passphrase: &str, salt: &[u8]) -> Result<Self, KeyEncryptionError> {
+        let mut key = Zeroizing::new([0u8; 32]);
+        Argon2::default()
+            .hash_password_into(passphrase.as_bytes(), salt, key.as_mut())
+            .map_err(|e| KeyEncryptionError::ConfigurationError(e.to_string()))?;
+        Ok(Self::new(*key))
+    }
+
+    // Reads the master key from the `<var>` (a multibase encoded 32 byte key) or `<var>_PASSPHRASE`
+    // environment variables. Passphrases are stretched with a salt kept in the issuer db.
+    pub fn from_env(issuer_db: &DB, var: &str) -> Result<Option<Self>, KeyEncryptionError> {
+        if let Ok(encoded_key) = std::env::var(var).map(Zeroizing::new) {
+            let key =
+                Zeroizing::new(multibase::decode(&encoded_key).map_err(|e| {
+                    KeyEncryptionError::ConfigurationError(format!("{}: {}", var, e))
+                })?);
+            let key: [u8; 32] = key.as_slice().try_into().map_err(|_e| {
+                KeyEncryptionError::ConfigurationError(format!(
+                    "{}: expected a 32 byte key, found {} bytes",
+                    var,
+                    key.len()
+                ))
+            })?;
+            return Ok(Some(Self::new(key)));
+        }
+        match std::env::var(format!("{}_PASSPHRASE", var)).map(Zeroizing::new) {
+            Ok(passphrase) => Ok(Some(Self::from_passphrase(
+                &passphrase,
+                &get_passphrase_salt(issuer_db)?,
+            )?)),
+            Err(_) => Ok(None),
+        }
+    }
+
+    pub fn get_id(&self) -> &String {
+        &self.id
+    }
+
+    // The verification method id is authenticated with the signing key, so an encrypted key
+    // cannot be moved to another verification method.
+    pub fn encrypt(
+        &self,
+        verification_method_id: &URL,
+        signing_key: &[u8],
+    ) -> Result<EncryptedSigningKey, KeyEncryptionError> {
+        let mut nonce = [0u8; 24];
+        OsRng.fill_bytes(&mut nonce);
+        let ciphertext = XChaCha20Poly1305::new(&self.key.into())
+            .encrypt(
+                XNonce::from_slice(&nonce),
+                Payload {
+                    msg: signing_key,
+                    aad: verification_method_id.get_str().as_bytes(),
+                },
+            )
+            .map_err(|e| KeyEncryptionError::EncryptionError(e.to_string()))?;
+        Ok(EncryptedSigningKey {
+            master_key_id: self.id.clone(),
+            nonce: multibase::encode(&nonce),
+            ciphertext: multibase::encode(&ciphertext),
+        })
+    }
+
+    pub fn decrypt(
+        &self,
+        verification_method_id: &URL,
+        encrypted_signing_key: &EncryptedSigningKey,
+    ) -> Result<Zeroizing<Vec<u8>>, KeyEncryptionError> {
+        if encrypted_signing_key.master_key_id != self.id {
+            return Err(KeyEncryptionError::UnknownMasterKeyError(format!(
+                "signing key for {} is encrypted under master key {}, not {}",
+                verification_method_id, encrypted_signing_key.master_key_id, self.id
+            )));
+        }
+        let nonce = multibase::decode(&encrypted_signing_key.nonce)
+            .map_err(|e| KeyEncryptionError::DecryptionError(e.to_string()))?;
+        if nonce.len() != 24 {
+            return Err(KeyEncryptionError::DecryptionError(format!(
+                "expected a 24 byte nonce, found {} bytes",
+                nonce.len()
+            )));
+        }
+        let ciphertext = multibase::decode(&encrypted_signing_key.ciphertext)
+            .map_err(|e| KeyEncryptionError::DecryptionError(e.to_string()))?;
+        XChaCha20Poly1305::new(&self.key.into())
+            .decrypt(
+                XNonce::from_slice(&nonce),
+                Payload {
+                    msg: &ciphertext,
+                    aad: verification_method_id.get_str().as_bytes(),
+                },
+            )
+            .map(Zeroizing::new)
+            .map_err(|_e| {
+                KeyEncryptionError::DecryptionError(format!(
+                    "could not authenticate signing key for {}",
+                    verification_method_id
+                ))
+            })
+    }
+}
+
+impl Drop for MasterKey {
+    fn drop(&mut self) {
+        self.key.zeroize();
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct EncryptedSigningKey {
+    master_key_id: String,
+    nonce: String,
+    ciphertext: String,
+}
+
+impl EncryptedSigningKey {
+    // Signing keys stored before encryption was introduced are raw key bytes, not JSON.
+    pub fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        serde_json::from_slice(bytes).ok()
+    }
+
+    pub fn to_bytes(&self) -> Result<Vec<u8>, KeyEncryptionError> {
+        serde_json::to_vec(self).map_err(|e| KeyEncryptionError::EncryptionError(e.to_string()))
+    }
+
+    pub fn get_master_key_id(&self) -> &String {
+        &self.master_key_id
+    }
+}
+
+fn get_passphrase_salt(issuer_db: &DB) -> Result<Vec<u8>, KeyEncryptionError> {
+    let key_encryption_cf = issuer_db
+        .cf_handle(ISSUER_KEY_ENCRYPTION_CF_PATH)
+        .ok_or_else(|| {
+            KeyEncryptionError::DatabaseError("could not get key encryption cf".to_string())
+        })?;
+    if let Some(salt) = issuer_db
+        .get_cf(key_encryption_cf, PASSPHRASE_SALT_KEY)
+        .map_err(|e| KeyEncryptionError::DatabaseError(e.to_string()))?
+    {
+        return Ok(salt);
+    }
+    let mut salt = [0u8; 16];
+    OsRng.fill_bytes(&mut salt);
+    issuer_db
+        .put_cf(key_encryption_cf, PASSPHRASE_SALT_KEY, salt)
+        .map_err(|e| KeyEncryptionError::DatabaseError(e.to_string()))?;
+    Ok(salt.to_vec())
+}
+
+// Encrypts the signing keys of the given verification methods under the new master key. Keys
+// encrypted under a previous master key are decrypted with it first, and unencrypted keys are
+// encrypted as they are. Every key is re-encrypted before any is written, so a key that cannot be
+// decrypted leaves the storage unchanged. An interrupted run can be repeated, since keys already
+// under the new master key are skipped.
+pub fn reencrypt_signing_keys(
+    storage: &dyn KeyStorage,
+    verification_method_ids: &[URL],
+    previous_master_key: Option<&MasterKey>,
+    master_key: &MasterKey,
+) -> Result<usize, KeyEncryptionError> {
+    let mut reencrypted_signing_keys = Vec::new();
+    for verification_method_id in verification_method_ids {
+        let stored_key = match storage.get_key_data(verification_method_id) {
+            Ok(stored_key) => Zeroizing::new(stored_key),
+            // Not every verification method has its private key held here.
+            Err(KeyStoreError::NotFound(_)) => continue,
+            Err(e) => return Err(KeyEncryptionError::StorageError(e.to_string())),
+        };
+        let signing_key = match EncryptedSigningKey::from_bytes(&stored_key) {
+            Some(encrypted_signing_key) => {
+                if encrypted_signing_key.get_master_key_id() == master_key.get_id() {
+                    continue;
+                }
+                previous_master_key
+                    .ok_or_else(|| {
+                        KeyEncryptionError::UnknownMasterKeyError(format!(
+                            "signing key for {} is encrypted under master key {}",
+                            verification_method_id,
+                            encrypted_signing_key.get_master_key_id()
+                        ))
+                    })?
+                    .decrypt(verification_method_id, &encrypted_signing_key)?
+            }
+            None => stored_key,
+        };
+        let encrypted_signing_key = master_key.encrypt(verification_method_id, &signing_key)?;
+        reencrypted_signing_keys.push((verification_method_id, encrypted_signing_key.to_bytes()?));
+    }
+
+    for (verification_method_id, encrypted_signing_key) in &reencrypted_signing_keys {
+        storage
+            .put_key_data(verification_method_id, encrypted_signing_key)
+            .map_err(|e| KeyEncryptionError::StorageError(e.to_string()))?;
+    }
+    Ok(reencrypted_signing_keys.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::keystore::DBKeyStorage;
+    use crate::open_issuer_db;
+    use crate::test_util::temp_db_path;
+    use vc_core::keystore::FileKeyStore;
+
+    #[test]
+    fn reencrypts_signing_keys_under_the_new_master_key() {
+        let issuer_db = open_issuer_db(&temp_db_path("reencrypt")).unwrap();
+        let file_keystore = FileKeyStore::new(temp_db_path("reencrypt-keys").into()).unwrap();
+        let storages: [Box<dyn KeyStorage>; 2] = [
+            Box::new(DBKeyStorage::new(&issuer_db)),
+            Box::new(file_keystore),
+        ];
+        let previous_master_key = MasterKey::new([1u8; 32]);
+        let master_key = MasterKey::new([2u8; 32]);
+        let plaintext_key_id = URL::new("did:example:issuer#key-1").unwrap();
+        let encrypted_key_id = URL::new("did:example:issuer#key-2").unwrap();
+        let missing_key_id = URL::new("did:example:issuer#key-3").unwrap();
+        let key_ids = [
+            plaintext_key_id.clone(),
+            encrypted_key_id.clone(),
+            missing_key_id,
+        ];
+
+        for storage in storages {
+            storage.put_key_data(&plaintext_key_id, &[3u8; 32]).unwrap();
+            let encrypted_key = previous_master_key
+                .encrypt(&encrypted_key_id, &[4u8; 32])
+                .unwrap();
+            storage
+                .put_key_data(&encrypted_key_id, &encrypted_key.to_bytes().unwrap())
+                .unwrap();
+
+            // Without the previous master key nothing is rewritten.
+            assert!(reencrypt_signing_keys(storage.as_ref(), &key_ids, None, &master_key).is_err());
+            assert_eq!(storage.get_key_data(&plaintext_key_id).unwrap(), [3u8; 32]);
+
+            assert_eq!(
+                reencrypt_signing_keys(
+                    storage.as_ref(),
+                    &key_ids,
+                    Some(&previous_master_key),
+                    &master_key
+                )
+                .unwrap(),
+                2
+            );
+            for (key_id, secret_key) in [
+                (&plaintext_key_id, [3u8; 32]),
+                (&encrypted_key_id, [4u8; 32]),
+            ] {
+                let stored_key = storage.get_key_data(key_id).unwrap();
+                let encrypted_key = EncryptedSigningKey::from_bytes(&stored_key).unwrap();
+                assert_eq!(
+                    *master_key.decrypt(key_id, &encrypted_key).unwrap(),
+                    secret_key
+                );
+            }
+            // Keys already under the master key are left alone.
+            assert_eq!(
+                reencrypt_signing_keys(storage.as_ref(), &key_ids, None, &master_key).unwrap(),
+                0
+            );
+        }
+    }
+}
@@ -0,0 +1,254 @@
+use super::UserError;
+use crate::key_encryption::{EncryptedSigningKey, MasterKey};
+use crate::{AppState, ISSUER_SIGNING_KEY_CF_PATH};
+use log::error;
+use rocksdb::DB;
+use vc_core::keystore::{FileKeyStore, KeyStorage, KeyStore, KeyStoreError};
+use vc_core::multibase::KeyType;
+use vc_core::proof::{InMemoryKeyHandle, KeyHandle};
+use vc_core::URL;
+use zeroize::Zeroizing;
+
+// Keeps issuer private keys in the issuer db's signing key column family.
+pub struct DBKeyStorage<'a> {
+    issuer_db: &'a DB,
+}
+
+impl<'a> DBKeyStorage<'a> {
+    pub fn new(issuer_db: &'a DB) -> Self {
+        Self { issuer_db }
+    }
+
+    fn get_signing_key_cf(&self) -> Result<&rocksdb::ColumnFamily, KeyStoreError> {
+        self.issuer_db
+            .cf_handle(ISSUER_SIGNING_KEY_CF_PATH)
+            .ok_or_else(|| {
+                KeyStoreError::StorageError("could not get issuer signing key cf".to_string())
+            })
+    }
+}
+
+impl KeyStorage for DBKeyStorage<'_> {
+    fn put_key_data(&self, key_id: &URL, data: &[u8]) -> Result<(), KeyStoreError> {
+        self.issuer_db
+            .put_cf(
+                self.get_signing_key_cf()?,
+                key_id.get_str().as_bytes(),
+                data,
+            )
+            .map_err(|e| KeyStoreError::StorageError(e.to_string()))
+    }
+
+    fn get_key_data(&self, key_id: &URL) -> Result<Vec<u8>, KeyStoreError> {
+        self.issuer_db
+            .get_cf(self.get_signing_key_cf()?, key_id.get_str().as_bytes())
+            .map_err(|e| KeyStoreError::StorageError(e.to_string()))?
+            .ok_or_else(|| KeyStoreError::NotFound(key_id.to_string()))
+    }
+
+    fn delete_key_data(&self, key_id: &URL) -> Result<(), KeyStoreError> {
+        self.issuer_db
+            .delete_cf(self.get_signing_key_cf()?, key_id.get_str().as_bytes())
+            .map_err(|e| KeyStoreError::StorageError(e.to_string()))
+    }
+}
+
+// Encrypts issuer private keys under the master key before they reach the storage backend. The
+// master key is only absent when unencrypted keys were explicitly allowed at startup.
+pub struct EncryptedKeyStore<'a> {
+    storage: Box<dyn KeyStorage + 'a>,
+    master_key: Option<&'a MasterKey>,
+}
+
+impl<'a> EncryptedKeyStore<'a> {
+    pub fn new(storage: Box<dyn KeyStorage + 'a>, master_key: Option<&'a MasterKey>) -> Self {
+        Self {
+            storage,
+            master_key,
+        }
+    }
+}
+
+impl KeyStore for EncryptedKeyStore<'_> {
+    fn store_key(&self, key_id: &URL, secret_key: &[u8]) -> Result<(), KeyStoreError> {
+        let stored_key = match self.master_key {
+            Some(master_key) => master_key
+                .encrypt(key_id, secret_key)
+                .and_then(|encrypted_key| encrypted_key.to_bytes())
+                .map_err(|e| KeyStoreError::StorageError(e.to_string()))?,
+            None => secret_key.to_vec(),
+        };
+        self.storage.put_key_data(key_id, &stored_key)
+    }
+
+    fn get_key_handle(
+        &self,
+        key_id: &URL,
+        key_type: KeyType,
+    ) -> Result<Box<dyn KeyHandle>, KeyStoreError> {
+        let stored_key = Zeroizing::new(self.storage.get_key_data(key_id)?);
+        let secret_key =
+            match (
+                EncryptedSigningKey::from_bytes(&stored_key),
+                self.master_key,
+            ) {
+                (Some(encrypted_key), Some(master_key)) => master_key
+                    .decrypt(key_id, &encrypted_key)
+                    .map_err(|e| KeyStoreError::StorageError(e.to_string()))?,
+                // A plaintext key in storage was not written by this key store, so it is not trusted.
+                (None, Some(_)) => {
+                    return Err(KeyStoreError::StorageError(format!(
+                        "signing key for {} is not encrypted under the master key",
+                        key_id
+                    )))
+                }
+                (Some(_), None) => {
+                    return Err(KeyStoreError::StorageError(format!(
+                        "signing key for {} is encrypted, but no master key is configured",
+                        key_id
+                    )))
+                }
+                (None, None) => stored_key,
+            };
+        let key_handle = InMemoryKeyHandle::new(key_type, &secret_key)
+            .map_err(|e| KeyStoreError::InvalidKeyError(e.to_string()))?;
+        Ok(Box::new(key_handle))
+    }
+
+    fn delete_key(&self, key_id: &URL) -> Result<(), KeyStoreError> {
+        self.storage.delete_key_data(key_id)
+    }
+}
+
+// Issuer keys live in the file keystore when one is configured, and in the issuer db otherwise.
+pub fn get_issuer_key_storage<'a>(
+    issuer_keystore: Option<&FileKeyStore>,
+    issuer_db: &'a DB,
+) -> Box<dyn KeyStorage + 'a> {
+    match issuer_keystore {
+        Some(file_keystore) => Box::new(file_keystore.clone()),
+        None => Box::new(DBKeyStorage::new(issuer_db)),
+    }
+}
+
+// Either way issuer keys are encrypted under the master key.
+pub fn get_issuer_keystore<'a>(
+    app_state: &'a AppState,
+    issuer_db: &'a DB,
+) -> Box<dyn KeyStore + 'a> {
+    Box::new(EncryptedKeyStore::new(
+        get_issuer_key_storage(app_state.issuer_keystore.as_ref(), issuer_db),
+        app_state.issuer_master_key.as_ref(),
+    ))
+}
+
+pub fn store_signing_key(
+    keystore: &dyn KeyStore,
+    verification_method_id: &URL,
+    signing_key: &[u8],
+) -> Result<(), UserError> {
+    keystore
+        .store_key(verification_method_id, signing_key)
+        .map_err(|e| {
+            error!(
+                "Error storing signing key for verification method {}: {:?}",
+                verification_method_id, e
+            );
+            UserError::InternalServerError
+        })
+}
+
+pub fn delete_signing_key(
+    keystore: &dyn KeyStore,
+    verification_method_id: &URL,
+) -> Result<(), UserError> {
+    keystore.delete_key(verification_method_id).map_err(|e| {
+        error!(
+            "Error removing signing key for verification method {}: {:?}",
+            verification_method_id, e
+        );
+        UserError::InternalServerError
+    })
+}
+
+pub fn get_signing_key_handle(
+    keystore: &dyn KeyStore,
+    verification_method_id: &URL,
+    key_type: KeyType,
+) -> Result<Box<dyn KeyHandle>, UserError> {
+    keystore
+        .get_key_handle(verification_method_id, key_type)
+        .map_err(|e| {
+            error!(
+                "Could not get signing key for verification method {}: {:?}",
+                verification_method_id, e
+            );
+            match e {
+                KeyStoreError::NotFound(_) => UserError::BadRequest,
+                _ => UserError::InternalServerError,
+            }
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::{new_test_app_state, temp_db_path};
+
+    #[test]
+    fn encrypts_keys_in_every_storage_backend() {
+        let mut app_state = new_test_app_state("encrypted-keystore");
+        let file_keystore = FileKeyStore::new(temp_db_path("keys").into()).unwrap();
+        let key_id = URL::new("did:example:issuer#key-1").unwrap();
+        let secret_key = [1u8; 32];
+
+        for use_file_keystore in [false, true] {
+            app_state.issuer_keystore = use_file_keystore.then(|| file_keystore.clone());
+            let issuer_db = app_state.issuer_db.lock().unwrap();
+            let keystore = get_issuer_keystore(&app_state, &issuer_db);
+            keystore.store_key(&key_id, &secret_key).unwrap();
+
+            let stored_key = match use_file_keystore {
+                true => file_keystore.get_key_data(&key_id).unwrap(),
+                false => DBKeyStorage::new(&issuer_db).get_key_data(&key_id).unwrap(),
+            };
+            let encrypted_key = EncryptedSigningKey::from_bytes(&stored_key).unwrap();
+            assert_eq!(
+                encrypted_key.get_master_key_id(),
+                app_state.issuer_master_key.as_ref().unwrap().get_id()
+            );
+            let key_handle = keystore
+                .get_key_handle(&key_id, KeyType::Secp256k1)
+                .unwrap();
+            let expected_key_handle =
+                InMemoryKeyHandle::new(KeyType::Secp256k1, &secret_key).unwrap();
+            assert_eq!(
+                key_handle.sign(b"data").unwrap(),
+                expected_key_handle.sign(b"data").unwrap()
+            );
+
+            keystore.delete_key(&key_id).unwrap();
+            assert!(matches!(
+                keystore.get_key_handle(&key_id, KeyType::Secp256k1),
+                Err(KeyStoreError::NotFound(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn rejects_unencrypted_keys_when_a_master_key_is_configured() {
+        let app_state = new_test_app_state("unencrypted-keystore");
+        let issuer_db = app_state.issuer_db.lock().unwrap();
+        let key_id = URL::new("did:example:issuer#key-1").unwrap();
+        let storage = DBKeyStorage::new(&issuer_db);
+        storage.put_key_data(&key_id, &[1u8; 32]).unwrap();
+
+        let keystore = get_issuer_keystore(&app_state, &issuer_db);
+        assert!(matches!(
+            keystore.get_key_handle(&key_id, KeyType::Secp256k1),
+            Err(KeyStoreError::StorageError(_))
+        ));
+        let keystore = EncryptedKeyStore::new(Box::new(storage), None);
+        assert!(keystore.get_key_handle(&key_id, KeyType::Secp256k1).is_ok());
+    }
+}
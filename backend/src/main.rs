@@ -1,21 +1,32 @@
 use actix_cors::Cors;
 use actix_web::middleware::Logger;
 use actix_web::{get, web, App, HttpRequest, HttpResponse, HttpServer, ResponseError};
+use key_encryption::MasterKey;
+use log::{info, warn};
 use registry::VerifiableDataRegistry;
 use rocksdb::{ColumnFamilyDescriptor, Options, DB};
 use std::fmt;
 use std::sync::Mutex;
+use vc_core::cryptosuite::CryptosuiteRegistry;
+use vc_core::keystore::FileKeyStore;
 
+mod challenge;
 mod credential;
 mod issuer;
+mod key_encryption;
+mod keystore;
 mod registry;
 mod schema;
+mod status;
+#[cfg(test)]
+mod test_util;
 mod verifier;
 
 #[derive(Debug)]
 pub enum UserError {
     BadRequest,
     NotFound,
+    Conflict,
     InternalServerError,
 }
 
@@ -24,6 +35,7 @@ impl ResponseError for UserError {
         match *self {
             UserError::BadRequest => HttpResponse::BadRequest().body("Bad Request"),
             UserError::NotFound => HttpResponse::NotFound().body("Resource Not Found"),
+            UserError::Conflict => HttpResponse::Conflict().body("Conflict"),
             UserError::InternalServerError => {
                 HttpResponse::InternalServerError().body("Internal Server Error")
             }
@@ -50,43 +62,160 @@ pub struct AppState {
     pub registry: Mutex<VerifiableDataRegistry>,
     pub issuer_db: Mutex<DB>,
     pub verifier_db: Mutex<DB>,
+    pub cryptosuites: CryptosuiteRegistry,
+    pub issuer_keystore: Option<FileKeyStore>,
+    pub issuer_master_key: Option<MasterKey>,
 }
 
-pub const VERIFIABLE_DATA_REGISTRY_DB_PATH: &'static str = "verifiable_data_registry";
-pub const ISSUER_DB_PATH: &'static str = "issuer";
-pub const ISSUER_SIGNING_KEY_CF_PATH: &'static str = "signing_key";
-pub const VERIFIER_DB_PATH: &'static str = "verifier";
-pub const VERIFIER_VERIFIER_CF_PATH: &'static str = "verifier";
+pub const VERIFIABLE_DATA_REGISTRY_DB_PATH: &str = "verifiable_data_registry";
+pub const ISSUER_DB_PATH: &str = "issuer";
+pub const ISSUER_SIGNING_KEY_CF_PATH: &str = "signing_key";
+pub const ISSUER_STATUS_LIST_CF_PATH: &str = "status_list";
+pub const ISSUER_CREDENTIAL_STATUS_CF_PATH: &str = "credential_status";
+pub const ISSUER_KEY_ENCRYPTION_CF_PATH: &str = "key_encryption";
+// Challenges the issuer hands out to holders refreshing their credentials.
+pub const ISSUER_CHALLENGE_CF_PATH: &str = "challenge";
+// Signing keys are encrypted under the master key given by this variable, or derived from the
+// passphrase in the same variable suffixed with _PASSPHRASE.
+pub const ISSUER_MASTER_KEY_ENV: &str = "ISSUER_MASTER_KEY";
+// Setting this to "true" lets the backend start without a master key and store signing keys
+// unencrypted. Only meant for development.
+pub const ISSUER_INSECURE_UNENCRYPTED_KEYS_ENV: &str = "ISSUER_INSECURE_UNENCRYPTED_KEYS";
+// The master key being replaced when re-encrypting signing keys.
+pub const ISSUER_PREVIOUS_MASTER_KEY_ENV: &str = "ISSUER_PREVIOUS_MASTER_KEY";
+pub const REENCRYPT_SIGNING_KEYS_COMMAND: &str = "reencrypt-signing-keys";
+// Keeps issuer signing keys in files under this directory instead of the issuer db when set.
+pub const ISSUER_KEYSTORE_PATH_ENV: &str = "ISSUER_KEYSTORE_PATH";
+pub const VERIFIER_DB_PATH: &str = "verifier";
+pub const VERIFIER_VERIFIER_CF_PATH: &str = "verifier";
+pub const VERIFIER_CHALLENGE_CF_PATH: &str = "challenge";
 pub const DEFAULT_RESOURCE_LIMIT: usize = 20;
+pub const BASE_URL: &str = "http://localhost:8000";
 
-#[actix_web::main]
-async fn main() -> std::io::Result<()> {
-    std::env::set_var("RUST_LOG", "backend=debug,actix_web=debug,vc_core=debug");
-    env_logger::init();
-    let registry = VerifiableDataRegistry::new(VERIFIABLE_DATA_REGISTRY_DB_PATH)
-        .expect("Could not create registry.");
-
+pub fn open_issuer_db(db_path: &str) -> Result<DB, rocksdb::Error> {
     let mut issuer_db_options = Options::default();
     issuer_db_options.create_if_missing(true);
     issuer_db_options.create_missing_column_families(true);
     let signing_key_cf =
         ColumnFamilyDescriptor::new(ISSUER_SIGNING_KEY_CF_PATH, Options::default());
-    let issuer_db =
-        DB::open_cf_descriptors(&issuer_db_options, ISSUER_DB_PATH, vec![signing_key_cf])
-            .expect("Could not open issuer db.");
+    let status_list_cf =
+        ColumnFamilyDescriptor::new(ISSUER_STATUS_LIST_CF_PATH, Options::default());
+    let credential_status_cf =
+        ColumnFamilyDescriptor::new(ISSUER_CREDENTIAL_STATUS_CF_PATH, Options::default());
+    let key_encryption_cf =
+        ColumnFamilyDescriptor::new(ISSUER_KEY_ENCRYPTION_CF_PATH, Options::default());
+    let challenge_cf = ColumnFamilyDescriptor::new(ISSUER_CHALLENGE_CF_PATH, Options::default());
+    DB::open_cf_descriptors(
+        &issuer_db_options,
+        db_path,
+        vec![
+            signing_key_cf,
+            status_list_cf,
+            credential_status_cf,
+            key_encryption_cf,
+            challenge_cf,
+        ],
+    )
+}
 
+pub fn open_verifier_db(db_path: &str) -> Result<DB, rocksdb::Error> {
     let mut verifier_db_options = Options::default();
     verifier_db_options.create_if_missing(true);
     verifier_db_options.create_missing_column_families(true);
     let verifier_cf = ColumnFamilyDescriptor::new(VERIFIER_VERIFIER_CF_PATH, Options::default());
-    let verifier_db =
-        DB::open_cf_descriptors(&verifier_db_options, VERIFIER_DB_PATH, vec![verifier_cf])
-            .expect("Could not open verifier db.");
+    let challenge_cf = ColumnFamilyDescriptor::new(VERIFIER_CHALLENGE_CF_PATH, Options::default());
+    DB::open_cf_descriptors(
+        &verifier_db_options,
+        db_path,
+        vec![verifier_cf, challenge_cf],
+    )
+}
+
+#[actix_web::main]
+async fn main() -> std::io::Result<()> {
+    std::env::set_var("RUST_LOG", "backend=debug,actix_web=debug,vc_core=debug");
+    env_logger::init();
+    let mut registry = VerifiableDataRegistry::new(VERIFIABLE_DATA_REGISTRY_DB_PATH)
+        .expect("Could not create registry.");
+    let issuer_migration = registry
+        .migrate_issuers()
+        .expect("Could not read registry issuers.");
+    info!(
+        "Migrated {} legacy issuers in registry.",
+        issuer_migration.get_migrated()
+    );
+    if issuer_migration.get_failed() > 0 {
+        warn!(
+            "Could not migrate {} legacy issuers in registry; they were left unchanged.",
+            issuer_migration.get_failed()
+        );
+    }
+
+    let issuer_db = open_issuer_db(ISSUER_DB_PATH).expect("Could not open issuer db.");
+
+    let issuer_keystore = std::env::var_os(ISSUER_KEYSTORE_PATH_ENV)
+        .map(|path| FileKeyStore::new(path.into()).expect("Could not open issuer keystore."));
+    let issuer_key_storage = keystore::get_issuer_key_storage(issuer_keystore.as_ref(), &issuer_db);
+    let verification_method_ids = registry
+        .get_verification_method_ids()
+        .expect("Could not read registry verification methods.");
+
+    let issuer_master_key = MasterKey::from_env(&issuer_db, ISSUER_MASTER_KEY_ENV)
+        .expect("Could not load issuer master key.");
+    if std::env::args().nth(1).as_deref() == Some(REENCRYPT_SIGNING_KEYS_COMMAND) {
+        let master_key =
+            issuer_master_key.expect("Re-encrypting signing keys requires a master key.");
+        let previous_master_key = MasterKey::from_env(&issuer_db, ISSUER_PREVIOUS_MASTER_KEY_ENV)
+            .expect("Could not load previous issuer master key.");
+        let reencrypted = key_encryption::reencrypt_signing_keys(
+            issuer_key_storage.as_ref(),
+            &verification_method_ids,
+            previous_master_key.as_ref(),
+            &master_key,
+        )
+        .expect("Could not re-encrypt signing keys.");
+        info!(
+            "Re-encrypted {} signing keys under master key {}.",
+            reencrypted,
+            master_key.get_id()
+        );
+        return Ok(());
+    }
+    match &issuer_master_key {
+        Some(master_key) => {
+            let encrypted = key_encryption::reencrypt_signing_keys(
+                issuer_key_storage.as_ref(),
+                &verification_method_ids,
+                None,
+                master_key,
+            )
+            .expect("Could not encrypt signing keys.");
+            info!("Encrypted {} plaintext signing keys.", encrypted);
+        }
+        None => {
+            if std::env::var(ISSUER_INSECURE_UNENCRYPTED_KEYS_ENV).as_deref() != Ok("true") {
+                panic!(
+                    "No issuer master key is configured. Set {} or {}_PASSPHRASE, or set {}=true \
+                     to store signing keys unencrypted for development.",
+                    ISSUER_MASTER_KEY_ENV,
+                    ISSUER_MASTER_KEY_ENV,
+                    ISSUER_INSECURE_UNENCRYPTED_KEYS_ENV
+                );
+            }
+            warn!("No issuer master key is configured, so signing keys are stored unencrypted.")
+        }
+    }
+    drop(issuer_key_storage);
+
+    let verifier_db = open_verifier_db(VERIFIER_DB_PATH).expect("Could not open verifier db.");
 
     let app_state = AppState {
         registry: Mutex::new(registry),
         issuer_db: Mutex::new(issuer_db),
         verifier_db: Mutex::new(verifier_db),
+        cryptosuites: CryptosuiteRegistry::default(),
+        issuer_keystore,
+        issuer_master_key,
     };
     let app_data = web::Data::new(app_state);
 
@@ -102,6 +231,7 @@ async fn main() -> std::io::Result<()> {
             .service(schema::init_routes())
             .service(credential::init_routes())
             .service(verifier::init_routes())
+            .service(status::init_routes())
             .default_service(web::to(not_found))
     })
     .bind("127.0.0.1:8000")?
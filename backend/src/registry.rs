@@ -1,28 +1,61 @@
+use chrono::{DateTime, Utc};
 use log::warn;
 use rocksdb::{ColumnFamily, ColumnFamilyDescriptor, IteratorMode, Options, DB};
 use std::error::Error;
 use std::fmt;
-use vc_core::{CredentialSchema, Issuer, VerificationMethod, URL};
+use std::sync::Mutex;
+use vc_core::resolver::{DIDResolutionError, DIDResolver};
+use vc_core::{
+    CredentialSchema, DIDDocument, Issuer, IssuerError, VerificationMethod,
+    VerificationRelationship, URL,
+};
 
+#[allow(clippy::enum_variant_names)]
 #[derive(Debug)]
 pub enum RegistryError {
     SerializationError(String),
     DatabaseError(String),
     ArgumentError(String),
+    ConflictError(String),
 }
 
 impl Error for RegistryError {}
 
+impl From<IssuerError> for RegistryError {
+    fn from(e: IssuerError) -> Self {
+        match e {
+            IssuerError::DuplicateVerificationMethod(e) => RegistryError::ConflictError(e),
+            IssuerError::UnknownVerificationMethod(e) => RegistryError::ArgumentError(e),
+        }
+    }
+}
+
 impl fmt::Display for RegistryError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             RegistryError::SerializationError(e) => write!(f, "Serialization error: {}", e),
             RegistryError::DatabaseError(e) => write!(f, "Database error: {}", e),
             RegistryError::ArgumentError(e) => write!(f, "Authorization error: {}", e),
+            RegistryError::ConflictError(e) => write!(f, "Conflict error: {}", e),
         }
     }
 }
 
+pub struct IssuerMigration {
+    migrated: usize,
+    failed: usize,
+}
+
+impl IssuerMigration {
+    pub fn get_migrated(&self) -> usize {
+        self.migrated
+    }
+
+    pub fn get_failed(&self) -> usize {
+        self.failed
+    }
+}
+
 pub struct VerifiableDataRegistry {
     db: DB,
 }
@@ -151,14 +184,118 @@ impl VerifiableDataRegistry {
             .collect())
     }
 
+    // Rewrites issuers stored before verification method keys were multibase encoded. Issuers
+    // that cannot be migrated are logged and left as they are.
+    pub fn migrate_issuers(&mut self) -> Result<IssuerMigration, RegistryError> {
+        let mut legacy_issuers = Vec::new();
+        let mut failed = 0;
+        for result in self.db.iterator_cf(self.issuer_cf()?, IteratorMode::Start) {
+            let (key, value) = result.map_err(|_e| {
+                RegistryError::DatabaseError("Could not fetch an issuer.".to_string())
+            })?;
+            let issuer_key = String::from_utf8_lossy(&key);
+            let Ok(issuer_json) = String::from_utf8(value.to_vec()) else {
+                warn!(
+                    "Could not migrate issuer {}: {:?}",
+                    issuer_key,
+                    RegistryError::SerializationError(
+                        "Could not deserialize an issuer from bytes to string.".to_string()
+                    )
+                );
+                failed += 1;
+                continue;
+            };
+            if let Ok(mut issuer) = serde_json::from_str::<Issuer>(&issuer_json) {
+                if issuer.migrate_verification_relationships() {
+                    legacy_issuers.push(issuer);
+                }
+                continue;
+            }
+            match Issuer::from_legacy_json(&issuer_json) {
+                Ok(issuer) => legacy_issuers.push(issuer),
+                Err(e) => {
+                    warn!(
+                        "Could not migrate issuer {}: {:?}",
+                        issuer_key,
+                        RegistryError::SerializationError(e.to_string())
+                    );
+                    failed += 1;
+                }
+            }
+        }
+
+        let migrated = legacy_issuers.len();
+        for issuer in legacy_issuers {
+            self.new_issuer(issuer)?;
+        }
+        Ok(IssuerMigration { migrated, failed })
+    }
+
+    // Signing keys are stored by verification method id, so ids must be unique across issuers.
+    pub fn has_verification_method(
+        &self,
+        verification_method_id: &URL,
+    ) -> Result<bool, RegistryError> {
+        for result in self.db.iterator_cf(self.issuer_cf()?, IteratorMode::Start) {
+            let (_key, value) = result.map_err(|_e| {
+                RegistryError::DatabaseError("Could not fetch an issuer.".to_string())
+            })?;
+            let issuer = serde_json::from_slice::<Issuer>(&value).map_err(|_e| {
+                RegistryError::SerializationError("Could not deserialize issuer.".to_string())
+            })?;
+            if issuer
+                .get_verification_method(verification_method_id)
+                .is_some()
+            {
+                return Ok(true);
+            }
+        }
+        Ok(false)
+    }
+
+    // The ids of every issuer's verification methods, whatever their status.
+    pub fn get_verification_method_ids(&self) -> Result<Vec<URL>, RegistryError> {
+        let mut verification_method_ids = Vec::new();
+        for result in self.db.iterator_cf(self.issuer_cf()?, IteratorMode::Start) {
+            let (_key, value) = result.map_err(|_e| {
+                RegistryError::DatabaseError("Could not fetch an issuer.".to_string())
+            })?;
+            let issuer = serde_json::from_slice::<Issuer>(&value).map_err(|_e| {
+                RegistryError::SerializationError("Could not deserialize issuer.".to_string())
+            })?;
+            verification_method_ids.extend(
+                issuer
+                    .get_verification_methods()
+                    .iter()
+                    .map(|verification_method| verification_method.get_id().clone()),
+            );
+        }
+        Ok(verification_method_ids)
+    }
+
+    fn check_new_verification_method_id(
+        &self,
+        verification_method_id: &URL,
+    ) -> Result<(), RegistryError> {
+        if self.has_verification_method(verification_method_id)? {
+            return Err(RegistryError::ConflictError(format!(
+                "Verification method {} already exists in the registry.",
+                verification_method_id
+            )));
+        }
+        Ok(())
+    }
+
     pub fn new_verification_method(
         &mut self,
         issuer_id: &URL,
         verification_method: VerificationMethod,
+        verification_relationships: &[VerificationRelationship],
     ) -> Result<(), RegistryError> {
+        self.check_new_verification_method_id(verification_method.get_id())?;
         match self.get_issuer(issuer_id) {
             Ok(Some(mut issuer)) => {
-                issuer.new_verification_method(verification_method);
+                issuer.new_verification_method(verification_method, verification_relationships)?;
                 self.new_issuer(issuer)
             }
             Ok(None) => Err(RegistryError::ArgumentError(format!(
@@ -169,6 +306,45 @@ impl VerifiableDataRegistry {
         }
     }
 
+    pub fn rotate_verification_method(
+        &mut self,
+        issuer_id: &URL,
+        verification_method_id: &URL,
+        verification_method: VerificationMethod,
+        rotated: DateTime<Utc>,
+    ) -> Result<(), RegistryError> {
+        let mut issuer = self.get_issuer(issuer_id)?.ok_or_else(|| {
+            RegistryError::ArgumentError(format!(
+                "Issuer {} does not exist in the registry.",
+                issuer_id
+            ))
+        })?;
+        self.check_new_verification_method_id(verification_method.get_id())?;
+        issuer.rotate_verification_method(verification_method_id, verification_method, rotated)?;
+        self.new_issuer(issuer)
+    }
+
+    pub fn revoke_verification_method(
+        &mut self,
+        issuer_id: &URL,
+        verification_method_id: &URL,
+        revoked: DateTime<Utc>,
+    ) -> Result<(), RegistryError> {
+        let mut issuer = self.get_issuer(issuer_id)?.ok_or_else(|| {
+            RegistryError::ArgumentError(format!(
+                "Issuer {} does not exist in the registry.",
+                issuer_id
+            ))
+        })?;
+        if !issuer.revoke_verification_method(verification_method_id, revoked) {
+            return Err(RegistryError::ArgumentError(format!(
+                "Issuer {} has no verification method {}.",
+                issuer_id, verification_method_id
+            )));
+        }
+        self.new_issuer(issuer)
+    }
+
     pub fn new_schema(&mut self, schema: CredentialSchema) -> Result<(), RegistryError> {
         let schema_json = serde_json::to_string(&schema).map_err(|_e| {
             RegistryError::SerializationError("Could not serialize schema.".to_string())
@@ -264,3 +440,169 @@ impl VerifiableDataRegistry {
             .collect())
     }
 }
+
+impl DIDResolver for VerifiableDataRegistry {
+    fn resolve(&self, did: &URL) -> Result<DIDDocument, DIDResolutionError> {
+        match self.get_issuer(did) {
+            Ok(Some(issuer)) => Ok(issuer.get_did_document()),
+            Ok(None) => Err(DIDResolutionError::NotFound(did.to_string())),
+            Err(e) => Err(DIDResolutionError::ResolutionError(e.to_string())),
+        }
+    }
+}
+
+// Resolves DIDs from a shared registry, holding its lock only for each lookup so that slow
+// resolution by other resolvers in a chain does not block the registry.
+pub struct RegistryResolver<'a> {
+    registry: &'a Mutex<VerifiableDataRegistry>,
+}
+
+impl<'a> RegistryResolver<'a> {
+    pub fn new(registry: &'a Mutex<VerifiableDataRegistry>) -> Self {
+        Self { registry }
+    }
+}
+
+impl DIDResolver for RegistryResolver<'_> {
+    fn resolve(&self, did: &URL) -> Result<DIDDocument, DIDResolutionError> {
+        self.registry
+            .lock()
+            .map_err(|_e| {
+                DIDResolutionError::ResolutionError("Could not lock registry.".to_string())
+            })?
+            .resolve(did)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::temp_db_path;
+    use vc_core::multibase::{self, KeyType};
+
+    // The secp256k1 generator point, SEC1 compressed.
+    const LEGACY_PUBLIC_KEY: [u8; 33] = [
+        0x02, 0x79, 0xbe, 0x66, 0x7e, 0xf9, 0xdc, 0xbb, 0xac, 0x55, 0xa0, 0x62, 0x95, 0xce, 0x87,
+        0x0b, 0x07, 0x02, 0x9b, 0xfc, 0xdb, 0x2d, 0xce, 0x28, 0xd9, 0x59, 0xf2, 0x81, 0x5b, 0x16,
+        0xf8, 0x17, 0x98,
+    ];
+
+    fn put_issuer_json(registry: &VerifiableDataRegistry, issuer_id: &str, issuer_json: &str) {
+        registry
+            .db
+            .put_cf(registry.issuer_cf().unwrap(), issuer_id, issuer_json)
+            .unwrap();
+    }
+
+    fn legacy_issuer_json(issuer_id: &str, public_key: &[u8]) -> String {
+        serde_json::json!({
+            "id": issuer_id,
+            "name": "Legacy Issuer",
+            "verification_methods": [{
+                "id": format!("{}#key-1", issuer_id),
+                "type_": "EcdsaSecp256k1VerificationKey2019",
+                "controller_id": issuer_id,
+                "public_key_multibase": public_key,
+            }],
+        })
+        .to_string()
+    }
+
+    #[test]
+    fn skips_issuers_that_cannot_be_migrated() {
+        let mut registry = VerifiableDataRegistry::new(&temp_db_path("migration")).unwrap();
+        put_issuer_json(
+            &registry,
+            "did:example:legacy",
+            &legacy_issuer_json("did:example:legacy", &LEGACY_PUBLIC_KEY),
+        );
+        let malformed_json = legacy_issuer_json("did:example:malformed", &[1, 2, 3]);
+        put_issuer_json(&registry, "did:example:malformed", &malformed_json);
+        put_issuer_json(&registry, "did:example:garbage", "not an issuer");
+
+        let issuer_migration = registry.migrate_issuers().unwrap();
+        assert_eq!(issuer_migration.get_migrated(), 1);
+        assert_eq!(issuer_migration.get_failed(), 2);
+
+        let issuer = registry
+            .get_issuer(&URL::new("did:example:legacy").unwrap())
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            issuer.get_verification_methods()[0].get_public_key_multibase(),
+            &Some(multibase::encode_public_key(
+                KeyType::Secp256k1,
+                &LEGACY_PUBLIC_KEY
+            ))
+        );
+        // Issuers that failed to migrate are left for an operator to repair.
+        let malformed_value = registry
+            .db
+            .get_cf(registry.issuer_cf().unwrap(), "did:example:malformed")
+            .unwrap()
+            .unwrap();
+        assert_eq!(malformed_value, malformed_json.as_bytes());
+    }
+
+    #[test]
+    fn rejects_verification_method_ids_used_by_another_issuer() {
+        let mut registry = VerifiableDataRegistry::new(&temp_db_path("duplicate-key")).unwrap();
+        let issuer_1 = URL::new("did:example:issuer-1").unwrap();
+        let issuer_2 = URL::new("did:example:issuer-2").unwrap();
+        registry
+            .new_issuer(Issuer::new(issuer_1.clone(), "Issuer 1".to_string()))
+            .unwrap();
+        registry
+            .new_issuer(Issuer::new(issuer_2.clone(), "Issuer 2".to_string()))
+            .unwrap();
+        let verification_method_id = URL::new("did:example:issuer-1#key-1").unwrap();
+        let verification_method = |controller_id: &URL| {
+            VerificationMethod::new(
+                verification_method_id.clone(),
+                "Multikey".to_string(),
+                controller_id.clone(),
+                multibase::encode_public_key(KeyType::Secp256k1, &LEGACY_PUBLIC_KEY),
+            )
+        };
+        let relationships = [VerificationRelationship::AssertionMethod];
+
+        assert!(!registry
+            .has_verification_method(&verification_method_id)
+            .unwrap());
+        registry
+            .new_verification_method(&issuer_1, verification_method(&issuer_1), &relationships)
+            .unwrap();
+        assert!(registry
+            .has_verification_method(&verification_method_id)
+            .unwrap());
+        assert!(matches!(
+            registry.new_verification_method(
+                &issuer_2,
+                verification_method(&issuer_2),
+                &relationships
+            ),
+            Err(RegistryError::ConflictError(_))
+        ));
+        registry
+            .new_verification_method(
+                &issuer_2,
+                VerificationMethod::new(
+                    URL::new("did:example:issuer-2#key-1").unwrap(),
+                    "Multikey".to_string(),
+                    issuer_2.clone(),
+                    multibase::encode_public_key(KeyType::Secp256k1, &LEGACY_PUBLIC_KEY),
+                ),
+                &relationships,
+            )
+            .unwrap();
+        assert!(matches!(
+            registry.rotate_verification_method(
+                &issuer_2,
+                &URL::new("did:example:issuer-2#key-1").unwrap(),
+                verification_method(&issuer_2),
+                Utc::now(),
+            ),
+            Err(RegistryError::ConflictError(_))
+        ));
+    }
+}
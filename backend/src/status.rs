@@ -0,0 +1,631 @@
+use super::UserError;
+use crate::credential::sign_credential;
+use crate::keystore::get_issuer_keystore;
+use crate::registry::VerifiableDataRegistry;
+use crate::{AppState, BASE_URL, ISSUER_CREDENTIAL_STATUS_CF_PATH, ISSUER_STATUS_LIST_CF_PATH};
+use actix_web::{get, post, web, HttpResponse, Scope};
+use chrono::{Duration, Utc};
+use log::{error, info};
+use rocksdb::DB;
+use serde::{Deserialize, Serialize};
+use vc_core::status_list::{self, StatusList, MIN_STATUS_LIST_LENGTH, REVOCATION_STATUS_PURPOSE};
+use vc_core::{CredentialStatus, VerifiableCredential, VerificationRelationship, URL};
+
+#[derive(Serialize, Deserialize)]
+struct StatusListRecord {
+    issuer_id: URL,
+    status_purpose: String,
+    next_index: usize,
+    encoded_list: String,
+    // The last signed status list credential, served until the list changes or it expires.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    signed_credential: Option<VerifiableCredential>,
+}
+
+impl StatusListRecord {
+    fn new(issuer_id: &URL, status_purpose: &str) -> Result<Self, UserError> {
+        let encoded_list = StatusList::new(MIN_STATUS_LIST_LENGTH)
+            .encode()
+            .map_err(|e| {
+                error!("Could not encode status list: {:?}", e);
+                UserError::InternalServerError
+            })?;
+        Ok(Self {
+            issuer_id: issuer_id.clone(),
+            status_purpose: status_purpose.to_string(),
+            next_index: 0,
+            encoded_list,
+            signed_credential: None,
+        })
+    }
+}
+
+// Each issuer has a sequence of status lists per purpose, addressed by the hex encoded issuer id
+// and the list's position in the sequence. A new list is started when the last one is full.
+fn get_status_list_id(issuer_id: &URL, status_purpose: &str, list_number: usize) -> String {
+    let status_list_id = format!("{}-{}", hex::encode(issuer_id.get_str()), status_purpose);
+    match list_number {
+        0 => status_list_id,
+        list_number => format!("{}-{}", status_list_id, list_number),
+    }
+}
+
+fn get_status_list_url(status_list_id: &str) -> Result<URL, UserError> {
+    URL::new(&format!("{}/status/{}", BASE_URL, status_list_id)).map_err(|e| {
+        error!("Invalid status list url: {:?}", e);
+        UserError::InternalServerError
+    })
+}
+
+// Returns the id of a status list served by this backend, if the url points to one.
+pub(crate) fn get_local_status_list_id(status_list_url: &URL) -> Option<&str> {
+    status_list_url
+        .get_str()
+        .strip_prefix(BASE_URL)
+        .and_then(|path| path.strip_prefix("/status/"))
+}
+
+fn get_status_list_record(
+    issuer_db: &DB,
+    status_list_id: &str,
+) -> Result<Option<StatusListRecord>, UserError> {
+    let status_list_cf = issuer_db
+        .cf_handle(ISSUER_STATUS_LIST_CF_PATH)
+        .ok_or_else(|| {
+            error!("Could not get issuer status list cf.");
+            UserError::InternalServerError
+        })?;
+    let record_bytes = issuer_db
+        .get_cf(status_list_cf, status_list_id.as_bytes())
+        .map_err(|e| {
+            error!("Error getting status list from db: {:?}", e);
+            UserError::InternalServerError
+        })?;
+    match record_bytes {
+        Some(record_bytes) => serde_json::from_slice::<StatusListRecord>(&record_bytes)
+            .map(Some)
+            .map_err(|_e| {
+                error!("Could not deserialize status list.");
+                UserError::InternalServerError
+            }),
+        None => Ok(None),
+    }
+}
+
+fn put_status_list_record(
+    issuer_db: &DB,
+    status_list_id: &str,
+    record: &StatusListRecord,
+) -> Result<(), UserError> {
+    let status_list_cf = issuer_db
+        .cf_handle(ISSUER_STATUS_LIST_CF_PATH)
+        .ok_or_else(|| {
+            error!("Could not get issuer status list cf.");
+            UserError::InternalServerError
+        })?;
+    let record_json = serde_json::to_string(record).map_err(|_e| {
+        error!("Could not serialize status list.");
+        UserError::InternalServerError
+    })?;
+    issuer_db
+        .put_cf(
+            status_list_cf,
+            status_list_id.as_bytes(),
+            record_json.as_bytes(),
+        )
+        .map_err(|e| {
+            error!("Error adding status list to db: {:?}", e);
+            UserError::InternalServerError
+        })
+}
+
+// Reserves the next index in the issuer's last status list for the given purpose, starting a new
+// list once it is full.
+pub(crate) fn allocate_credential_status(
+    issuer_db: &DB,
+    issuer_id: &URL,
+    status_purpose: &str,
+) -> Result<CredentialStatus, UserError> {
+    let mut list_number = 0;
+    let (status_list_id, mut record) = loop {
+        let status_list_id = get_status_list_id(issuer_id, status_purpose, list_number);
+        match get_status_list_record(issuer_db, &status_list_id)? {
+            Some(record) if record.next_index >= MIN_STATUS_LIST_LENGTH => list_number += 1,
+            Some(record) => break (status_list_id, record),
+            None => {
+                if list_number > 0 {
+                    info!("Starting status list {}.", status_list_id);
+                }
+                break (
+                    status_list_id,
+                    StatusListRecord::new(issuer_id, status_purpose)?,
+                );
+            }
+        }
+    };
+    let status_list_index = record.next_index;
+    record.next_index += 1;
+    put_status_list_record(issuer_db, &status_list_id, &record)?;
+
+    let status_list_url = get_status_list_url(&status_list_id)?;
+    let status_id =
+        URL::new(&format!("{}#{}", status_list_url, status_list_index)).map_err(|e| {
+            error!("Invalid credential status id: {:?}", e);
+            UserError::InternalServerError
+        })?;
+    Ok(CredentialStatus::new(
+        status_id,
+        status_purpose.to_string(),
+        status_list_index,
+        status_list_url,
+    ))
+}
+
+// Returns the signed status list credential, signing a fresh one from the stored bitstring when the
+// list has changed, the last one has expired, or the issuer's signing key has changed since.
+pub(crate) fn get_status_list_credential(
+    app_state: &AppState,
+    registry: &VerifiableDataRegistry,
+    issuer_db: &DB,
+    status_list_id: &str,
+) -> Result<VerifiableCredential, UserError> {
+    let mut record = get_status_list_record(issuer_db, status_list_id)?.ok_or_else(|| {
+        error!("Could not find status list {} in db.", status_list_id);
+        UserError::NotFound
+    })?;
+    let issuer = registry
+        .get_issuer(&record.issuer_id)
+        .map_err(|e| {
+            error!(
+                "Error getting issuer {} from registry: {:?}",
+                record.issuer_id, e
+            );
+            UserError::InternalServerError
+        })?
+        .ok_or_else(|| {
+            error!("Could not find issuer {} in registry.", record.issuer_id);
+            UserError::InternalServerError
+        })?;
+    // Status lists are always signed with the issuer's newest active key.
+    let verification_method = issuer
+        .get_newest_active_verification_method(VerificationRelationship::AssertionMethod)
+        .ok_or_else(|| {
+            error!(
+                "Issuer {} has no active verification methods.",
+                record.issuer_id
+            );
+            UserError::InternalServerError
+        })?;
+    if let Some(signed_credential) = &record.signed_credential {
+        let is_unexpired = signed_credential
+            .get_credential()
+            .get_valid_until()
+            .is_some_and(|valid_until| valid_until > Utc::now());
+        let is_signed_with_current_key = signed_credential
+            .get_proof()
+            .iter()
+            .all(|proof| proof.get_verification_method() == verification_method.get_id());
+        if is_unexpired && is_signed_with_current_key {
+            return Ok(signed_credential.clone());
+        }
+    }
+
+    let status_list = StatusList::decode(&record.encoded_list).map_err(|e| {
+        error!("Could not decode status list: {:?}", e);
+        UserError::InternalServerError
+    })?;
+    let valid_from = Utc::now();
+    let valid_until = valid_from + Duration::days(1);
+    let credential = status_list::new_status_list_credential(
+        get_status_list_url(status_list_id)?,
+        record.issuer_id.clone(),
+        valid_from,
+        valid_until,
+        &record.status_purpose,
+        &status_list,
+    )
+    .map_err(|e| {
+        error!("Could not create status list credential: {:?}", e);
+        UserError::InternalServerError
+    })?;
+
+    let signed_credential = sign_credential(
+        &app_state.cryptosuites,
+        get_issuer_keystore(app_state, issuer_db).as_ref(),
+        verification_method,
+        credential,
+    )?;
+    record.signed_credential = Some(signed_credential.clone());
+    put_status_list_record(issuer_db, status_list_id, &record)?;
+    Ok(signed_credential)
+}
+
+#[get("/{id}")]
+async fn get_status_list(
+    path: web::Path<String>,
+    app_state: web::Data<AppState>,
+) -> Result<HttpResponse, UserError> {
+    let registry = app_state.registry.lock().map_err(|_e| {
+        error!("Could not lock registry.");
+        UserError::InternalServerError
+    })?;
+    let issuer_db = app_state.issuer_db.lock().map_err(|_e| {
+        error!("Could not lock issuer db.");
+        UserError::InternalServerError
+    })?;
+    let status_list_credential =
+        get_status_list_credential(&app_state, &registry, &issuer_db, &path.into_inner())?;
+
+    Ok(HttpResponse::Ok().json(status_list_credential))
+}
+
+fn set_status_list_bit(
+    issuer_db: &DB,
+    status_list_id: &str,
+    status_list_index: usize,
+    status: bool,
+) -> Result<(), UserError> {
+    let mut record = get_status_list_record(issuer_db, status_list_id)?.ok_or_else(|| {
+        error!("Could not find status list {} in db.", status_list_id);
+        UserError::NotFound
+    })?;
+    if status_list_index >= record.next_index {
+        error!(
+            "Index {} has not been allocated in status list {}.",
+            status_list_index, status_list_id
+        );
+        return Err(UserError::BadRequest);
+    }
+    // Revocation is permanent; suspension is the status that can be lifted again.
+    if !status && record.status_purpose == REVOCATION_STATUS_PURPOSE {
+        error!(
+            "Cannot clear index {} in revocation list {}.",
+            status_list_index, status_list_id
+        );
+        return Err(UserError::BadRequest);
+    }
+    let mut status_list = StatusList::decode(&record.encoded_list).map_err(|e| {
+        error!("Could not decode status list: {:?}", e);
+        UserError::InternalServerError
+    })?;
+    status_list.set(status_list_index, status).map_err(|e| {
+        error!("Could not set status: {:?}", e);
+        UserError::BadRequest
+    })?;
+    record.encoded_list = status_list.encode().map_err(|e| {
+        error!("Could not encode status list: {:?}", e);
+        UserError::InternalServerError
+    })?;
+    record.signed_credential = None;
+    put_status_list_record(issuer_db, status_list_id, &record)?;
+
+    info!(
+        "Set status {} at index {} in status list {}.",
+        status, status_list_index, status_list_id
+    );
+    Ok(())
+}
+
+// Status entries are looked up by credential id, so a second credential with the same id would
+// leave the first one without a status that can be changed.
+pub(crate) fn check_new_credential_id(
+    issuer_db: &DB,
+    credential_id: &URL,
+) -> Result<(), UserError> {
+    if get_credential_status(issuer_db, credential_id)?.is_some() {
+        error!("Credential {} has already been issued.", credential_id);
+        return Err(UserError::Conflict);
+    }
+    Ok(())
+}
+
+pub(crate) fn put_credential_status(
+    issuer_db: &DB,
+    credential_id: &URL,
+    credential_status: &[CredentialStatus],
+) -> Result<(), UserError> {
+    check_new_credential_id(issuer_db, credential_id)?;
+    let credential_status_cf = issuer_db
+        .cf_handle(ISSUER_CREDENTIAL_STATUS_CF_PATH)
+        .ok_or_else(|| {
+            error!("Could not get issuer credential status cf.");
+            UserError::InternalServerError
+        })?;
+    let credential_status_json = serde_json::to_string(credential_status).map_err(|_e| {
+        error!("Could not serialize credential status.");
+        UserError::InternalServerError
+    })?;
+    issuer_db
+        .put_cf(
+            credential_status_cf,
+            credential_id.get_str().as_bytes(),
+            credential_status_json.as_bytes(),
+        )
+        .map_err(|e| {
+            error!("Error adding credential status to db: {:?}", e);
+            UserError::InternalServerError
+        })
+}
+
+fn get_credential_status(
+    issuer_db: &DB,
+    credential_id: &URL,
+) -> Result<Option<Vec<CredentialStatus>>, UserError> {
+    let credential_status_cf = issuer_db
+        .cf_handle(ISSUER_CREDENTIAL_STATUS_CF_PATH)
+        .ok_or_else(|| {
+            error!("Could not get issuer credential status cf.");
+            UserError::InternalServerError
+        })?;
+    let credential_status_bytes = issuer_db
+        .get_cf(credential_status_cf, credential_id.get_str().as_bytes())
+        .map_err(|e| {
+            error!("Error getting credential status from db: {:?}", e);
+            UserError::InternalServerError
+        })?;
+    match credential_status_bytes {
+        Some(credential_status_bytes) => {
+            serde_json::from_slice::<Vec<CredentialStatus>>(&credential_status_bytes)
+                .map(Some)
+                .map_err(|_e| {
+                    error!("Could not deserialize credential status.");
+                    UserError::InternalServerError
+                })
+        }
+        None => Ok(None),
+    }
+}
+
+// Returns the id of the local status list and the index that a status entry points to.
+fn get_status_list_location(
+    credential_status: &CredentialStatus,
+) -> Result<(&str, usize), UserError> {
+    let status_list_id = get_local_status_list_id(credential_status.get_status_list_credential())
+        .ok_or_else(|| {
+        error!(
+            "Status list {} is not served by this issuer.",
+            credential_status.get_status_list_credential()
+        );
+        UserError::InternalServerError
+    })?;
+    let status_list_index = credential_status.get_status_list_index().map_err(|e| {
+        error!("Invalid status list index: {:?}", e);
+        UserError::InternalServerError
+    })?;
+    Ok((status_list_id, status_list_index))
+}
+
+// Sets the bit of every status entry with the given purpose that was issued for a credential.
+pub(crate) fn set_credential_status(
+    issuer_db: &DB,
+    credential_id: &URL,
+    status_purpose: &str,
+    status: bool,
+) -> Result<(), UserError> {
+    let credential_status = get_credential_status(issuer_db, credential_id)?.ok_or_else(|| {
+        error!(
+            "Could not find status for credential {} in db.",
+            credential_id
+        );
+        UserError::NotFound
+    })?;
+    let entries: Vec<&CredentialStatus> = credential_status
+        .iter()
+        .filter(|entry| entry.get_status_purpose() == status_purpose)
+        .collect();
+    if entries.is_empty() {
+        error!(
+            "Credential {} has no {} status entry.",
+            credential_id, status_purpose
+        );
+        return Err(UserError::BadRequest);
+    }
+    for entry in entries {
+        let (status_list_id, status_list_index) = get_status_list_location(entry)?;
+        set_status_list_bit(issuer_db, status_list_id, status_list_index, status)?;
+    }
+    Ok(())
+}
+
+// Returns whether any status entry with the given purpose is set for a credential issued here.
+pub(crate) fn is_credential_status_set(
+    issuer_db: &DB,
+    credential_id: &URL,
+    status_purpose: &str,
+) -> Result<bool, UserError> {
+    let credential_status = match get_credential_status(issuer_db, credential_id)? {
+        Some(credential_status) => credential_status,
+        None => return Ok(false),
+    };
+    for entry in credential_status
+        .iter()
+        .filter(|entry| entry.get_status_purpose() == status_purpose)
+    {
+        let (status_list_id, status_list_index) = get_status_list_location(entry)?;
+        let record = get_status_list_record(issuer_db, status_list_id)?.ok_or_else(|| {
+            error!("Could not find status list {} in db.", status_list_id);
+            UserError::InternalServerError
+        })?;
+        let status_list = StatusList::decode(&record.encoded_list).map_err(|e| {
+            error!("Could not decode status list: {:?}", e);
+            UserError::InternalServerError
+        })?;
+        let status = status_list.get(status_list_index).map_err(|e| {
+            error!("Could not get status: {:?}", e);
+            UserError::InternalServerError
+        })?;
+        if status {
+            return Ok(true);
+        }
+    }
+    Ok(false)
+}
+
+#[derive(Deserialize)]
+struct SetStatusRequest {
+    status: bool,
+}
+
+#[post("/{id}/{index}")]
+async fn set_status(
+    path: web::Path<(String, usize)>,
+    req: web::Json<SetStatusRequest>,
+    app_state: web::Data<AppState>,
+) -> Result<HttpResponse, UserError> {
+    let issuer_db = app_state.issuer_db.lock().map_err(|_e| {
+        error!("Could not lock issuer db.");
+        UserError::InternalServerError
+    })?;
+    let (status_list_id, status_list_index) = path.into_inner();
+    set_status_list_bit(&issuer_db, &status_list_id, status_list_index, req.status)?;
+
+    Ok(HttpResponse::Ok().json(req.status))
+}
+
+pub fn init_routes() -> Scope {
+    web::scope("/status")
+        .service(get_status_list)
+        .service(set_status)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::{new_test_app_state, new_test_issuer};
+    use vc_core::status_list::SUSPENSION_STATUS_PURPOSE;
+
+    #[test]
+    fn starts_a_new_status_list_when_the_last_is_full() {
+        let app_state = new_test_app_state("status-allocation");
+        let issuer_db = app_state.issuer_db.lock().unwrap();
+        let issuer_id = URL::new("did:example:issuer").unwrap();
+        let status_list_id = get_status_list_id(&issuer_id, REVOCATION_STATUS_PURPOSE, 0);
+        let mut record = StatusListRecord::new(&issuer_id, REVOCATION_STATUS_PURPOSE).unwrap();
+        record.next_index = MIN_STATUS_LIST_LENGTH - 1;
+        put_status_list_record(&issuer_db, &status_list_id, &record).unwrap();
+
+        let last_status =
+            allocate_credential_status(&issuer_db, &issuer_id, REVOCATION_STATUS_PURPOSE).unwrap();
+        assert_eq!(
+            last_status.get_status_list_index().unwrap(),
+            MIN_STATUS_LIST_LENGTH - 1
+        );
+        assert_eq!(
+            get_local_status_list_id(last_status.get_status_list_credential()),
+            Some(status_list_id.as_str())
+        );
+
+        let next_list_id = get_status_list_id(&issuer_id, REVOCATION_STATUS_PURPOSE, 1);
+        for index in 0..2 {
+            let status =
+                allocate_credential_status(&issuer_db, &issuer_id, REVOCATION_STATUS_PURPOSE)
+                    .unwrap();
+            assert_eq!(status.get_status_list_index().unwrap(), index);
+            assert_eq!(
+                get_local_status_list_id(status.get_status_list_credential()),
+                Some(next_list_id.as_str())
+            );
+        }
+    }
+
+    #[test]
+    fn reuses_the_signed_status_list_until_it_changes() {
+        let app_state = new_test_app_state("status-signing");
+        let issuer_id = new_test_issuer(&app_state);
+        let registry = app_state.registry.lock().unwrap();
+        let issuer_db = app_state.issuer_db.lock().unwrap();
+        let credential_status =
+            allocate_credential_status(&issuer_db, &issuer_id, REVOCATION_STATUS_PURPOSE).unwrap();
+        let status_list_id =
+            get_local_status_list_id(credential_status.get_status_list_credential()).unwrap();
+        let get_signed_status_list = || {
+            get_status_list_credential(&app_state, &registry, &issuer_db, status_list_id).unwrap()
+        };
+
+        let signed_status_list = get_signed_status_list();
+        assert!(
+            serde_json::to_value(get_signed_status_list()).unwrap()
+                == serde_json::to_value(&signed_status_list).unwrap()
+        );
+
+        set_status_list_bit(&issuer_db, status_list_id, 0, true).unwrap();
+        let updated_status_list = get_signed_status_list();
+        assert!(
+            serde_json::to_value(&updated_status_list).unwrap()
+                != serde_json::to_value(&signed_status_list).unwrap()
+        );
+        assert!(
+            status_list::get_status(&credential_status, updated_status_list.get_credential())
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn only_clears_suspension_bits() {
+        let app_state = new_test_app_state("status-clearing");
+        let issuer_db = app_state.issuer_db.lock().unwrap();
+        let issuer_id = URL::new("did:example:issuer").unwrap();
+        for status_purpose in [REVOCATION_STATUS_PURPOSE, SUSPENSION_STATUS_PURPOSE] {
+            let credential_status =
+                allocate_credential_status(&issuer_db, &issuer_id, status_purpose).unwrap();
+            let status_list_id =
+                get_local_status_list_id(credential_status.get_status_list_credential()).unwrap();
+            set_status_list_bit(&issuer_db, status_list_id, 0, true).unwrap();
+
+            let cleared = set_status_list_bit(&issuer_db, status_list_id, 0, false);
+            let record = get_status_list_record(&issuer_db, status_list_id)
+                .unwrap()
+                .unwrap();
+            let status = StatusList::decode(&record.encoded_list)
+                .unwrap()
+                .get(0)
+                .unwrap();
+            if status_purpose == REVOCATION_STATUS_PURPOSE {
+                assert!(matches!(cleared, Err(UserError::BadRequest)));
+                assert!(status);
+            } else {
+                assert!(cleared.is_ok());
+                assert!(!status);
+            }
+        }
+    }
+
+    #[test]
+    fn rejects_credential_ids_that_already_have_a_status() {
+        let app_state = new_test_app_state("credential-status");
+        let issuer_db = app_state.issuer_db.lock().unwrap();
+        let issuer_id = URL::new("did:example:issuer").unwrap();
+        let credential_id = URL::new("urn:uuid:58172aac-d8ba-11ed-83dd-0b3aef56cc33").unwrap();
+        let new_status = || {
+            vec![
+                allocate_credential_status(&issuer_db, &issuer_id, REVOCATION_STATUS_PURPOSE)
+                    .unwrap(),
+            ]
+        };
+        let first_status = new_status();
+        assert!(check_new_credential_id(&issuer_db, &credential_id).is_ok());
+        put_credential_status(&issuer_db, &credential_id, &first_status).unwrap();
+
+        assert!(matches!(
+            check_new_credential_id(&issuer_db, &credential_id),
+            Err(UserError::Conflict)
+        ));
+        assert!(matches!(
+            put_credential_status(&issuer_db, &credential_id, &new_status()),
+            Err(UserError::Conflict)
+        ));
+        // The first credential can still be revoked.
+        set_credential_status(&issuer_db, &credential_id, REVOCATION_STATUS_PURPOSE, true).unwrap();
+        assert!(
+            is_credential_status_set(&issuer_db, &credential_id, REVOCATION_STATUS_PURPOSE)
+                .unwrap()
+        );
+        let stored_status = get_credential_status(&issuer_db, &credential_id)
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            stored_status[0].get_status_list_index().unwrap(),
+            first_status[0].get_status_list_index().unwrap()
+        );
+    }
+}
@@ -0,0 +1,172 @@
+use crate::credential::sign_credential;
+use crate::key_encryption::MasterKey;
+use crate::keystore::{get_issuer_keystore, store_signing_key};
+use crate::registry::VerifiableDataRegistry;
+use crate::status;
+use crate::{open_issuer_db, open_verifier_db, AppState, BASE_URL};
+use chrono::{Duration, Utc};
+use k256::ecdsa::{SigningKey, VerifyingKey};
+use rand_core::{OsRng, RngCore};
+use std::collections::HashMap;
+use std::sync::Mutex;
+use uuid::Uuid;
+use vc_core::cryptosuite::CryptosuiteRegistry;
+use vc_core::multibase::KeyType;
+use vc_core::proof::{
+    CryptographicSuite, InMemoryKeyHandle, MyEcdsaSecp256k1Presentation, ProofOptions,
+};
+use vc_core::status_list::{REVOCATION_STATUS_PURPOSE, SUSPENSION_STATUS_PURPOSE};
+use vc_core::{
+    did_key, ClaimProperty, ClaimPropertyValue, Credential, Issuer, RefreshService,
+    VerifiableCredential, VerifiablePresentation, VerificationRelationship,
+    AUTHENTICATION_PROOF_PURPOSE, CREDENTIALS_CONTEXT, URL,
+};
+
+// A fresh path under the system temp directory, so each test gets its own databases.
+pub fn temp_db_path(name: &str) -> String {
+    std::env::temp_dir()
+        .join(format!("backend-test-{}-{}", name, OsRng.next_u64()))
+        .to_string_lossy()
+        .to_string()
+}
+
+pub fn new_test_app_state(name: &str) -> AppState {
+    let db_path = temp_db_path(name);
+    let mut master_key = [0u8; 32];
+    OsRng.fill_bytes(&mut master_key);
+    AppState {
+        registry: Mutex::new(
+            VerifiableDataRegistry::new(&format!("{}/registry", db_path)).unwrap(),
+        ),
+        issuer_db: Mutex::new(open_issuer_db(&format!("{}/issuer", db_path)).unwrap()),
+        verifier_db: Mutex::new(open_verifier_db(&format!("{}/verifier", db_path)).unwrap()),
+        cryptosuites: CryptosuiteRegistry::default(),
+        issuer_keystore: None,
+        issuer_master_key: Some(MasterKey::new(master_key)),
+    }
+}
+
+// Adds a did:key issuer to the registry and stores its signing key, as the did:key issuer
+// endpoint does. Returns the issuer id.
+pub fn new_test_issuer(app_state: &AppState) -> URL {
+    let mut registry = app_state.registry.lock().unwrap();
+    let issuer_db = app_state.issuer_db.lock().unwrap();
+    let keystore = get_issuer_keystore(app_state, &issuer_db);
+    let signing_key = SigningKey::random(&mut OsRng);
+    let verifying_key = VerifyingKey::from(&signing_key).to_sec1_bytes();
+    let issuer_id = did_key::did_from_secp256k1_public_key(&verifying_key).unwrap();
+    let did_document = did_key::resolve(&issuer_id).unwrap();
+    let mut issuer = Issuer::new(issuer_id.clone(), "Test Issuer".to_string());
+    for verification_method in did_document.get_verification_methods() {
+        store_signing_key(
+            keystore.as_ref(),
+            verification_method.get_id(),
+            &signing_key.to_bytes(),
+        )
+        .unwrap();
+        issuer
+            .new_verification_method(
+                verification_method.clone(),
+                &[
+                    VerificationRelationship::AssertionMethod,
+                    VerificationRelationship::Authentication,
+                ],
+            )
+            .unwrap();
+    }
+    registry.new_issuer(issuer).unwrap();
+    issuer_id
+}
+
+// Issues a credential about the subject, with status entries, as the new credential endpoint does.
+pub fn new_test_credential(
+    app_state: &AppState,
+    issuer_id: &URL,
+    subject_id: Option<&URL>,
+) -> VerifiableCredential {
+    let registry = app_state.registry.lock().unwrap();
+    let issuer_db = app_state.issuer_db.lock().unwrap();
+    let issuer = registry.get_issuer(issuer_id).unwrap().unwrap();
+    let credential_id = URL::new(&format!("urn:uuid:{}", Uuid::new_v4())).unwrap();
+    let mut credential_subject = HashMap::new();
+    if let Some(subject_id) = subject_id {
+        credential_subject.insert(
+            "id".to_string(),
+            ClaimProperty::Value(ClaimPropertyValue::Text(subject_id.get_str().to_string())),
+        );
+    }
+    let mut credential = Credential::new(
+        vec![URL::new(CREDENTIALS_CONTEXT).unwrap()],
+        credential_id.clone(),
+        vec!["VerifiableCredential".to_string()],
+        issuer_id.clone(),
+        Utc::now() - Duration::hours(1),
+        Utc::now() + Duration::hours(1),
+        credential_subject,
+        Vec::new(),
+    );
+    for status_purpose in [REVOCATION_STATUS_PURPOSE, SUSPENSION_STATUS_PURPOSE] {
+        let credential_status =
+            status::allocate_credential_status(&issuer_db, issuer_id, status_purpose).unwrap();
+        credential.add_credential_status(credential_status);
+    }
+    status::put_credential_status(
+        &issuer_db,
+        &credential_id,
+        credential.get_credential_status(),
+    )
+    .unwrap();
+    credential.add_refresh_service(RefreshService::new(
+        URL::new(&format!("{}/credential/refresh", BASE_URL)).unwrap(),
+    ));
+    let verification_method = issuer
+        .get_newest_active_verification_method(VerificationRelationship::AssertionMethod)
+        .unwrap();
+    let keystore = get_issuer_keystore(app_state, &issuer_db);
+    sign_credential(
+        &app_state.cryptosuites,
+        keystore.as_ref(),
+        verification_method,
+        credential,
+    )
+    .unwrap()
+}
+
+// A did:key holder and its signing key.
+pub fn new_test_holder() -> (SigningKey, URL) {
+    let signing_key = SigningKey::random(&mut OsRng);
+    let verifying_key = VerifyingKey::from(&signing_key).to_sec1_bytes();
+    let holder = did_key::did_from_secp256k1_public_key(&verifying_key).unwrap();
+    (signing_key, holder)
+}
+
+// Presents the credentials as the holder, answering the challenge for the domain.
+pub fn new_test_presentation(
+    signing_key: &SigningKey,
+    holder: &URL,
+    verifiable_credentials: Vec<VerifiableCredential>,
+    domain: &str,
+    challenge: &str,
+) -> VerifiablePresentation {
+    let mut verifiable_presentation = VerifiablePresentation::new(
+        vec![URL::new(CREDENTIALS_CONTEXT).unwrap()],
+        URL::new(&format!("urn:uuid:{}", Uuid::new_v4())).unwrap(),
+        vec!["VerifiablePresentation".to_string()],
+        verifiable_credentials,
+        Some(holder.clone()),
+        Vec::new(),
+    );
+    let proof_options = ProofOptions::new(
+        did_key::resolve_verification_method(holder).unwrap(),
+        AUTHENTICATION_PROOF_PURPOSE.to_string(),
+        Utc::now(),
+        Some(domain.to_string()),
+        Some(challenge.to_string()),
+    );
+    let key_handle = InMemoryKeyHandle::new(KeyType::Secp256k1, &signing_key.to_bytes()).unwrap();
+    let proof = MyEcdsaSecp256k1Presentation::new()
+        .generate_proof(&verifiable_presentation, &key_handle, &proof_options)
+        .unwrap();
+    verifiable_presentation.add_proof(proof);
+    verifiable_presentation
+}
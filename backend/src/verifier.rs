@@ -1,15 +1,27 @@
 use super::UserError;
-use crate::{AppState, DEFAULT_RESOURCE_LIMIT, VERIFIER_VERIFIER_CF_PATH};
+use crate::challenge;
+use crate::credential::get_credential_proof_options;
+use crate::registry::{RegistryResolver, VerifiableDataRegistry};
+use crate::status;
+use crate::{
+    AppState, DEFAULT_RESOURCE_LIMIT, VERIFIER_CHALLENGE_CF_PATH, VERIFIER_VERIFIER_CF_PATH,
+};
 use actix_web::{get, post, web, HttpResponse, Scope};
-use chrono::Utc;
+use chrono::{DateTime, Utc};
 use log::{error, info, warn};
-use rocksdb::IteratorMode;
+use rocksdb::{IteratorMode, DB};
 use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
 use vc_core::{
-    proof::{CryptographicSuite, MyEcdsaSecp256k1, ProofOptions},
-    ClaimProperty, ClaimPropertyValue, Credential, CredentialSchema, Proof, SchemaProperty,
-    SchemaPropertyValue, SchemaPropertyValueType, VerifiableCredential, VerificationMethod,
-    Verifier, URL,
+    cryptosuite::CryptosuiteRegistry,
+    http,
+    proof::{ProofOptions, ProofVerificationError},
+    resolver::{ChainedDIDResolver, DIDResolver, KeyDIDResolver, WebDIDResolver},
+    status_list::{self, REVOCATION_STATUS_PURPOSE, SUSPENSION_STATUS_PURPOSE},
+    ClaimProperty, ClaimPropertyValue, Credential, CredentialSchema, CredentialStatus, DIDDocument,
+    Proof, SchemaProperty, SchemaPropertyValue, SchemaPropertyValueType, VerifiableCredential,
+    VerifiablePresentation, VerificationMethod, VerificationRelationship, Verifier,
+    ASSERTION_METHOD_PROOF_PURPOSE, AUTHENTICATION_PROOF_PURPOSE, URL,
 };
 
 #[derive(Deserialize)]
@@ -17,6 +29,10 @@ struct AddVerifierRequest {
     id: String,
     name: String,
     schema_id: String,
+    #[serde(default)]
+    actions: Vec<String>,
+    #[serde(default)]
+    allow_bearer_credentials: bool,
 }
 
 #[post("/")]
@@ -43,7 +59,14 @@ async fn new_verifier(
         error!("Invalid schema id.");
         UserError::BadRequest
     })?;
-    let verifier = Verifier::new(verifier_id.clone(), name, schema_id.clone());
+    let actions = req.actions.clone();
+    let verifier = Verifier::new(
+        verifier_id.clone(),
+        name,
+        schema_id.clone(),
+        actions,
+        req.allow_bearer_credentials,
+    );
 
     let verifier_json = serde_json::to_string(&verifier).map_err(|_e| {
         error!("Could not serialize verifier.");
@@ -154,41 +177,281 @@ async fn get_all_verifiers(
     Ok(HttpResponse::Ok().json(verifiers))
 }
 
-#[derive(Deserialize)]
-pub struct VerifyCredentialRequest {
-    verifier_id: String,
-    verifiable_credential: String,
-}
-
 #[derive(Serialize, Deserialize)]
 pub struct VerifyCredentialResponse {
     verified: bool,
     reason: String,
 }
 
+#[derive(Deserialize)]
+pub struct VerifyCredentialRequest {
+    verifier_id: String,
+    verifiable_credential: String,
+}
+
 #[post("/verify")]
 async fn verify_credential(
     req: web::Json<VerifyCredentialRequest>,
     app_state: web::Data<AppState>,
 ) -> Result<HttpResponse, UserError> {
-    let registry = app_state.registry.lock().map_err(|_e| {
-        error!("Could not lock registry.");
-        UserError::InternalServerError
+    let verifier_id = URL::new(&req.verifier_id).map_err(|_e| {
+        error!("Invalid verifier id.");
+        UserError::BadRequest
     })?;
+    let (verifier, schema) = get_verifier_and_schema(&app_state, &verifier_id)?;
+
+    let verifiable_credential_string = req.verifiable_credential.clone();
+    let verifiable_credential = VerifiableCredential::from_json(&verifiable_credential_string)
+        .map_err(|_| {
+            error!("Could not deserialize verifiable credential.");
+            UserError::BadRequest
+        })?;
+    let resp = run_blocking(move || {
+        verify_verifiable_credential(&verifiable_credential, &verifier, &schema, &app_state)
+    })
+    .await?;
+    Ok(HttpResponse::Ok().json(resp))
+}
+
+#[derive(Serialize)]
+struct PresentationChallengeResponse {
+    challenge: String,
+    domain: String,
+    expires: DateTime<Utc>,
+}
+
+// Issues a single-use challenge that a holder must sign into their presentation for this verifier.
+#[post("/{id}/challenge")]
+async fn new_presentation_challenge(
+    path: web::Path<String>,
+    app_state: web::Data<AppState>,
+) -> Result<HttpResponse, UserError> {
     let verifier_db = app_state.verifier_db.lock().map_err(|_e| {
         error!("Could not lock verifier db.");
         UserError::InternalServerError
     })?;
+    let verifier_id = URL::new(&path.into_inner()).map_err(|_e| {
+        error!("Invalid verifier id.");
+        UserError::BadRequest
+    })?;
+    let verifier = get_verifier_from_db(&verifier_db, &verifier_id)?;
+    let (challenge, expires) =
+        challenge::new_challenge(&verifier_db, VERIFIER_CHALLENGE_CF_PATH, verifier.get_id())?;
+
+    info!(
+        "Issued presentation challenge for verifier: {}",
+        verifier_id
+    );
+    Ok(HttpResponse::Ok().json(PresentationChallengeResponse {
+        challenge,
+        domain: verifier.get_id().get_str().to_string(),
+        expires,
+    }))
+}
+
+#[derive(Deserialize)]
+pub struct VerifyPresentationRequest {
+    verifier_id: String,
+    verifiable_presentation: String,
+}
+
+#[post("/verify_presentation")]
+async fn verify_presentation(
+    req: web::Json<VerifyPresentationRequest>,
+    app_state: web::Data<AppState>,
+) -> Result<HttpResponse, UserError> {
+    let verifier_id = URL::new(&req.verifier_id).map_err(|_e| {
+        error!("Invalid verifier id.");
+        UserError::BadRequest
+    })?;
+    let (verifier, schema) = get_verifier_and_schema(&app_state, &verifier_id)?;
+
+    let verifiable_presentation = serde_json::from_str::<VerifiablePresentation>(
+        &req.verifiable_presentation,
+    )
+    .map_err(|_| {
+        error!("Could not deserialize verifiable presentation.");
+        UserError::BadRequest
+    })?;
+    let resp = run_blocking(move || {
+        verify_holder_presentation(&verifiable_presentation, &verifier, &schema, &app_state)
+    })
+    .await?;
+    Ok(HttpResponse::Ok().json(resp))
+}
+
+// Checks that the presentation answers an unused challenge from the verifier and is signed by its
+// holder, then verifies each credential it contains.
+fn verify_holder_presentation(
+    verifiable_presentation: &VerifiablePresentation,
+    verifier: &Verifier,
+    schema: &CredentialSchema,
+    app_state: &AppState,
+) -> Result<VerifyCredentialResponse, UserError> {
+    if let Some(reason) = get_presentation_rejection(
+        verifiable_presentation,
+        verifier.get_id(),
+        &app_state.verifier_db,
+        VERIFIER_CHALLENGE_CF_PATH,
+        &app_state.registry,
+        &app_state.cryptosuites,
+    )? {
+        return Ok(VerifyCredentialResponse {
+            verified: false,
+            reason,
+        });
+    }
+    let holder = verifiable_presentation
+        .get_holder()
+        .as_ref()
+        .ok_or_else(|| {
+            error!("No holder found in verifiable presentation.");
+            UserError::BadRequest
+        })?;
+
+    let verifiable_credentials = verifiable_presentation.get_verifiable_credential();
+    if verifiable_credentials.is_empty() {
+        error!("No credentials found in verifiable presentation.");
+        return Err(UserError::BadRequest);
+    }
+    for verifiable_credential in verifiable_credentials {
+        if let Some(reason) = get_holder_binding_rejection(
+            verifiable_credential.get_credential(),
+            holder,
+            verifier.allows_bearer_credentials(),
+        ) {
+            return Ok(VerifyCredentialResponse {
+                verified: false,
+                reason,
+            });
+        }
+        let resp =
+            verify_verifiable_credential(verifiable_credential, verifier, schema, app_state)?;
+        if !resp.verified {
+            return Ok(resp);
+        }
+    }
+    Ok(VerifyCredentialResponse {
+        verified: true,
+        reason: "".to_string(),
+    })
+}
+
+// Checks that the presentation is signed by its holder for the audience (the verifier or issuer
+// it is presented to), answering a challenge that audience issued. The challenge is used up once
+// the proof verifies. Returns the reason for rejecting the presentation, if any.
+pub(crate) fn get_presentation_rejection(
+    verifiable_presentation: &VerifiablePresentation,
+    audience: &URL,
+    challenge_db: &Mutex<DB>,
+    challenge_cf_path: &str,
+    registry: &Mutex<VerifiableDataRegistry>,
+    cryptosuites: &CryptosuiteRegistry,
+) -> Result<Option<String>, UserError> {
+    let proof = verifiable_presentation.get_proof().first().ok_or_else(|| {
+        error!("No proofs found in verifiable presentation.");
+        UserError::BadRequest
+    })?;
+    let holder = verifiable_presentation
+        .get_holder()
+        .as_ref()
+        .ok_or_else(|| {
+            error!("No holder found in verifiable presentation.");
+            UserError::BadRequest
+        })?;
+
+    // Challenges are single use, so a captured presentation cannot be replayed. This check
+    // rejects stale challenges early; the challenge is only used up once the proof verifies.
+    let challenge = proof.get_challenge().clone().unwrap_or_default();
+    let check_challenge = |use_challenge: bool| {
+        let db = challenge_db.lock().map_err(|_e| {
+            error!("Could not lock challenge db.");
+            UserError::InternalServerError
+        })?;
+        if use_challenge {
+            challenge::use_challenge(&db, challenge_cf_path, &challenge, audience)
+        } else {
+            challenge::get_challenge_rejection(&db, challenge_cf_path, &challenge, audience)
+        }
+    };
+    if let Some(reason) = check_challenge(false)? {
+        return Ok(Some(reason));
+    }
+
+    let proof_verification_method = proof.get_verification_method();
+    if proof_verification_method.did() != Some(holder.get_str()) {
+        return Ok(Some(
+            "Presentation was not signed by its holder.".to_string(),
+        ));
+    }
+    let registry_resolver = RegistryResolver::new(registry);
+    let web_resolver = WebDIDResolver::new();
+    let resolver =
+        ChainedDIDResolver::new(vec![&registry_resolver, &KeyDIDResolver, &web_resolver]);
+    let holder_did_document = resolver.resolve(holder).map_err(|e| {
+        error!("Could not resolve holder {}: {:?}", holder, e);
+        UserError::BadRequest
+    })?;
+    let verification_method = holder_did_document
+        .get_verification_method(proof_verification_method)
+        .cloned()
+        .ok_or_else(|| {
+            error!(
+                "Could not find holder verification method {} in {}.",
+                proof_verification_method, holder
+            );
+            UserError::BadRequest
+        })?;
+    if !holder_did_document.is_authorized(
+        proof_verification_method,
+        VerificationRelationship::Authentication,
+    ) {
+        return Ok(Some(format!(
+            "Verification method {} is not authorized for authentication.",
+            proof_verification_method
+        )));
+    }
+    let (_key_type, verifying_key) = verification_method.get_public_key().map_err(|e| {
+        error!("Could not decode verification method key: {:?}", e);
+        UserError::BadRequest
+    })?;
+    let cryptographic_suite = match cryptosuites.get_presentation_suite_for_proof(proof) {
+        Ok(cryptographic_suite) => cryptographic_suite,
+        Err(e) => return Ok(Some(format!("{}.", e))),
+    };
+    let proof_options = ProofOptions::new(
+        verification_method,
+        AUTHENTICATION_PROOF_PURPOSE.to_string(),
+        Utc::now(),
+        Some(audience.get_str().to_string()),
+        Some(challenge.clone()),
+    );
+    match cryptographic_suite.verify_proof(
+        verifiable_presentation,
+        proof,
+        &verifying_key,
+        &proof_options,
+    ) {
+        Ok(true) => {}
+        Ok(false) => return Ok(Some("Invalid verifiable presentation proof.".to_string())),
+        Err(e) => {
+            return Ok(Some(format!(
+                "Invalid verifiable presentation proof: {}.",
+                e
+            )))
+        }
+    }
+
+    check_challenge(true)
+}
+
+fn get_verifier_from_db(verifier_db: &DB, verifier_id: &URL) -> Result<Verifier, UserError> {
     let verifier_cf = verifier_db
         .cf_handle(VERIFIER_VERIFIER_CF_PATH)
         .ok_or_else(|| {
             error!("Could not get verifier cf.");
             UserError::InternalServerError
         })?;
-    let verifier_id = URL::new(&req.verifier_id).map_err(|_e| {
-        error!("Invalid verifier id.");
-        UserError::BadRequest
-    })?;
     let verifier_bytes = verifier_db
         .get_cf(verifier_cf, verifier_id.get_str().as_bytes())
         .map_err(|e| {
@@ -199,7 +462,7 @@ async fn verify_credential(
             error!("Could not find verifier {} in db.", verifier_id);
             UserError::BadRequest
         })?;
-    let verifier = String::from_utf8(verifier_bytes)
+    String::from_utf8(verifier_bytes)
         .map_err(|_e| {
             error!("Could not deserialize verifier.");
             UserError::InternalServerError
@@ -209,10 +472,44 @@ async fn verify_credential(
                 error!("Could not deserialize verifier.");
                 UserError::InternalServerError
             })
-        })?;
+        })
+}
+
+fn get_verifier_and_schema(
+    app_state: &AppState,
+    verifier_id: &URL,
+) -> Result<(Verifier, CredentialSchema), UserError> {
+    let registry = app_state.registry.lock().map_err(|_e| {
+        error!("Could not lock registry.");
+        UserError::InternalServerError
+    })?;
+    let verifier_db = app_state.verifier_db.lock().map_err(|_e| {
+        error!("Could not lock verifier db.");
+        UserError::InternalServerError
+    })?;
+    let verifier = get_verifier_from_db(&verifier_db, verifier_id)?;
+    let schema = get_verifier_schema(&registry, &verifier)?;
+    Ok((verifier, schema))
+}
+
+// Verification may resolve DIDs and fetch status lists over the network, so it runs on the
+// blocking thread pool rather than the request's worker.
+pub(crate) async fn run_blocking<T: Send + 'static>(
+    f: impl FnOnce() -> Result<T, UserError> + Send + 'static,
+) -> Result<T, UserError> {
+    web::block(f).await.map_err(|e| {
+        error!("Blocking verification task failed: {:?}", e);
+        UserError::InternalServerError
+    })?
+}
+
+fn get_verifier_schema(
+    registry: &VerifiableDataRegistry,
+    verifier: &Verifier,
+) -> Result<CredentialSchema, UserError> {
     let schema_id = verifier.get_schema_id();
-    let schema = registry
-        .get_schema(&schema_id)
+    registry
+        .get_schema(schema_id)
         .map_err(|e| {
             error!("Could not get schema from registry: {:?}", e);
             UserError::BadRequest
@@ -220,70 +517,94 @@ async fn verify_credential(
         .ok_or_else(|| {
             error!("Could not find schema {} in registry.", schema_id);
             UserError::BadRequest
-        })?;
+        })
+}
 
-    let verifiable_credential_string = req.verifiable_credential.clone();
-    let verifiable_credential = serde_json::from_str::<VerifiableCredential>(
-        &verifiable_credential_string,
-    )
-    .map_err(|_| {
-        error!("Could not deserialize verifiable credential.");
-        UserError::BadRequest
-    })?;
+fn verify_verifiable_credential(
+    verifiable_credential: &VerifiableCredential,
+    verifier: &Verifier,
+    schema: &CredentialSchema,
+    app_state: &AppState,
+) -> Result<VerifyCredentialResponse, UserError> {
     let credential = verifiable_credential.get_credential();
-    let proofs = verifiable_credential.get_proof();
-    if proofs.len() == 0 {
-        error!("No proofs found in verifiable credential.");
-        return Err(UserError::BadRequest);
-    }
-    let proof = proofs
-        .get(0)
-        .expect("Proofs should have at least one element.");
-
-    let issuer_id = credential.get_issuer();
-    let issuer = registry
-        .get_issuer(issuer_id)
-        .map_err(|e| {
-            error!("Could not get issuer from registry: {:?}", e);
-            UserError::BadRequest
-        })?
-        .ok_or_else(|| {
-            error!("Could not find issuer {} in registry.", issuer_id);
-            UserError::BadRequest
-        })?;
-    let verification_methods = issuer.get_verification_methods();
-    if verification_methods.len() == 0 {
-        error!("No verification methods found for issuer.");
-        return Err(UserError::BadRequest);
-    }
-    let verification_method = verification_methods
-        .get(0)
-        .expect("Verification methods should have at least one element.");
-    let verifying_key = verification_method.get_public_key_multibase();
+    let registry_resolver = RegistryResolver::new(&app_state.registry);
 
     let mut resp = VerifyCredentialResponse {
         verified: true,
         reason: "".to_string(),
     };
-    if !is_valid_credential_format(&credential) {
+    if !is_valid_credential_format(credential) {
         resp.verified = false;
         resp.reason = "Invalid credential format.".to_string();
-    } else if !is_valid_credential_expiry(&credential) {
+    } else if !is_valid_credential_expiry(credential) {
         resp.verified = false;
         resp.reason = "Invalid credential expiry.".to_string();
-    } else if !is_valid_credential_schema(&credential, &schema) {
+    } else if !is_valid_credential_schema(credential, schema) {
         resp.verified = false;
         resp.reason = "Invalid credential schema.".to_string();
-    } else if !is_valid_verifiable_credential_proof(
-        &credential,
-        &proof,
-        verifying_key,
-        verification_method.clone(),
-    ) {
+    } else if !is_valid_terms_of_use(credential, verifier) {
         resp.verified = false;
-        resp.reason = "Invalid verifiable credential proof.".to_string();
+        resp.reason = "Credential terms of use do not permit this verifier.".to_string();
+    } else if let Some(reason) = get_proof_set_rejection(
+        &app_state.cryptosuites,
+        &registry_resolver,
+        verifiable_credential,
+    )? {
+        resp.verified = false;
+        resp.reason = reason;
+    } else {
+        let is_status_set = |status_purpose| {
+            is_credential_status_set(credential, status_purpose, &registry_resolver, app_state)
+        };
+        match (
+            is_status_set(REVOCATION_STATUS_PURPOSE),
+            is_status_set(SUSPENSION_STATUS_PURPOSE),
+        ) {
+            (Ok(true), _) => {
+                resp.verified = false;
+                resp.reason = "Credential has been revoked.".to_string();
+            }
+            (Ok(false), Ok(true)) => {
+                resp.verified = false;
+                resp.reason = "Credential has been suspended.".to_string();
+            }
+            (Ok(false), Ok(false)) => {}
+            _ => {
+                resp.verified = false;
+                resp.reason = "Could not check credential status.".to_string();
+            }
+        }
+    }
+    Ok(resp)
+}
+
+// A credential may only be presented by one of its subjects. Credentials without subject ids
+// (bearer credentials) can be presented by anyone, so they are only accepted when allowed.
+// Returns the reason for rejecting the credential, if any.
+pub(crate) fn get_holder_binding_rejection(
+    cred: &Credential,
+    holder: &URL,
+    allow_bearer_credentials: bool,
+) -> Option<String> {
+    let mut has_subject_id = false;
+    for credential_subject in cred.get_credential_subjects() {
+        match credential_subject.get("id") {
+            Some(ClaimProperty::Value(ClaimPropertyValue::Text(subject_id)))
+                if subject_id == holder.get_str() =>
+            {
+                return None
+            }
+            Some(_) => has_subject_id = true,
+            None => {}
+        }
+    }
+    if has_subject_id {
+        Some("Credential subject is not the presentation holder.".to_string())
+    } else if !allow_bearer_credentials {
+        Some("Credential has no subject id, and bearer credentials are not accepted.".to_string())
+    } else {
+        None
     }
-    Ok(HttpResponse::Ok().json(resp))
 }
 
 fn is_valid_credential_format(cred: &Credential) -> bool {
@@ -299,25 +620,26 @@ fn is_valid_credential_format(cred: &Credential) -> bool {
 
 fn is_valid_credential_expiry(cred: &Credential) -> bool {
     let current_time = Utc::now();
-    let after_valid_from = current_time >= *cred.get_valid_from();
-    let before_valid_until = current_time <= *cred.get_valid_until();
+    // A missing bound leaves the validity period open on that side.
+    let after_valid_from = cred
+        .get_valid_from()
+        .is_none_or(|valid_from| current_time >= valid_from);
+    let before_valid_until = cred
+        .get_valid_until()
+        .is_none_or(|valid_until| current_time <= valid_until);
 
     after_valid_from && before_valid_until
 }
 
 fn is_valid_credential_schema(cred: &Credential, schema: &CredentialSchema) -> bool {
-    let claims = cred.get_credential_subject();
     let schema_properties = schema.get_properties();
-    for (key, schema_prop) in schema_properties {
-        if let Some(claim_prop) = claims.get(key) {
-            if !is_valid_credential_schema_property(claim_prop, schema_prop) {
-                return false;
-            }
-        } else {
-            return false;
-        }
-    }
-    true
+    cred.get_credential_subjects().iter().all(|claims| {
+        schema_properties.iter().all(|(key, schema_prop)| {
+            claims.get(key).is_some_and(|claim_prop| {
+                is_valid_credential_schema_property(claim_prop, schema_prop)
+            })
+        })
+    })
 }
 
 fn is_valid_credential_schema_property(
@@ -359,39 +681,343 @@ fn is_valid_credential_schema_property_value(
     claim_value: &ClaimPropertyValue,
     schema_value: &SchemaPropertyValue,
 ) -> bool {
-    match (claim_value, schema_value.get_type()) {
-        (ClaimPropertyValue::Text(_), SchemaPropertyValueType::Text) => true,
-        (ClaimPropertyValue::Number(_), SchemaPropertyValueType::Number) => true,
-        (ClaimPropertyValue::Boolean(_), SchemaPropertyValueType::Boolean) => true,
-        _ => false,
+    matches!(
+        (claim_value, schema_value.get_type()),
+        (ClaimPropertyValue::Text(_), SchemaPropertyValueType::Text)
+            | (
+                ClaimPropertyValue::Number(_),
+                SchemaPropertyValueType::Number
+            )
+            | (
+                ClaimPropertyValue::Boolean(_),
+                SchemaPropertyValueType::Boolean
+            )
+    )
+}
+
+fn is_declared_action(verifier: &Verifier, action: &str) -> bool {
+    verifier
+        .get_actions()
+        .iter()
+        .any(|declared_action| declared_action.eq_ignore_ascii_case(action))
+}
+
+// Rejects credentials that prohibit an action the verifier declares, or oblige one it does not.
+fn is_valid_terms_of_use(cred: &Credential, verifier: &Verifier) -> bool {
+    let verifier_id = verifier.get_id();
+    for terms_of_use in cred.get_terms_of_use() {
+        for prohibition in terms_of_use.get_prohibition() {
+            if prohibition.applies_to(verifier_id)
+                && prohibition
+                    .get_action()
+                    .iter()
+                    .any(|action| is_declared_action(verifier, action))
+            {
+                return false;
+            }
+        }
+        for obligation in terms_of_use.get_obligation() {
+            if obligation.applies_to(verifier_id)
+                && !obligation
+                    .get_action()
+                    .iter()
+                    .all(|action| is_declared_action(verifier, action))
+            {
+                return false;
+            }
+        }
+    }
+    true
+}
+
+// Every proof in the set must verify, and at least one must be made with a verification method
+// controlled by the issuer. A chained proof is verified together with the proof named by its
+// previousProof. Returns the reason for rejecting the proof set, if any.
+pub(crate) fn get_proof_set_rejection(
+    cryptosuites: &CryptosuiteRegistry,
+    registry_resolver: &dyn DIDResolver,
+    verifiable_credential: &VerifiableCredential,
+) -> Result<Option<String>, UserError> {
+    let credential = verifiable_credential.get_credential();
+    let proofs = verifiable_credential.get_proof();
+    if proofs.is_empty() {
+        error!("No proofs found in verifiable credential.");
+        return Err(UserError::BadRequest);
+    }
+    let issuer_id = credential.get_issuer();
+    let web_resolver = WebDIDResolver::new();
+    let resolver = ChainedDIDResolver::new(vec![registry_resolver, &KeyDIDResolver, &web_resolver]);
+    let issuer_did_document = resolver.resolve(issuer_id).map_err(|e| {
+        error!("Could not resolve issuer {}: {:?}", issuer_id, e);
+        UserError::BadRequest
+    })?;
+
+    let mut is_issuer_signed = false;
+    for proof in proofs {
+        if let Err(e) = cryptosuites.get_suite_for_proof(proof) {
+            return Ok(Some(format!("{}.", e)));
+        }
+        let previous_proof = match proof.get_previous_proof() {
+            Some(previous_proof_id) => {
+                match verifiable_credential.get_proof_by_id(previous_proof_id) {
+                    Some(previous_proof) => Some(previous_proof),
+                    None => {
+                        return Ok(Some(format!(
+                            "Missing previous proof {}.",
+                            previous_proof_id
+                        )))
+                    }
+                }
+            }
+            None => None,
+        };
+        // Credential proofs assert claims, so they must be made with an assertion method.
+        if proof.get_proof_purpose() != ASSERTION_METHOD_PROOF_PURPOSE {
+            return Ok(Some(format!(
+                "Proof purpose {} is not {}.",
+                proof.get_proof_purpose(),
+                ASSERTION_METHOD_PROOF_PURPOSE
+            )));
+        }
+        let (verification_method, is_authorized) = resolve_proof_verification_method(
+            &resolver,
+            &issuer_did_document,
+            proof,
+            VerificationRelationship::AssertionMethod,
+        )?;
+        if !is_authorized {
+            return Ok(Some(format!(
+                "Verification method {} is not authorized as an assertion method.",
+                verification_method.get_id()
+            )));
+        }
+        if !verification_method.is_valid_at(proof.get_created()) {
+            return Ok(Some(format!(
+                "Verification method {} was revoked or not valid when the proof was created.",
+                verification_method.get_id()
+            )));
+        }
+        if verification_method.get_controller_id() == issuer_id {
+            is_issuer_signed = true;
+        }
+        let verifying_key = match verification_method.get_public_key() {
+            Ok((_key_type, verifying_key)) => verifying_key,
+            Err(e) => {
+                return Ok(Some(format!(
+                    "Could not decode verification method {} key: {}.",
+                    verification_method.get_id(),
+                    e
+                )))
+            }
+        };
+        match verify_verifiable_credential_proof(
+            cryptosuites,
+            credential,
+            proof,
+            previous_proof,
+            &verifying_key,
+            verification_method,
+        ) {
+            Ok(true) => {}
+            Ok(false) => return Ok(Some("Invalid verifiable credential proof.".to_string())),
+            Err(e) => return Ok(Some(format!("Invalid verifiable credential proof: {}.", e))),
+        }
+    }
+    if !is_issuer_signed {
+        return Ok(Some("Credential was not signed by its issuer.".to_string()));
     }
+    Ok(None)
 }
 
-fn is_valid_verifiable_credential_proof(
+// Finds the verification method named by a proof, and whether the DID document it belongs to
+// authorizes it for the given relationship. Verification methods without a DID URL can only be
+// found in the issuer's document.
+fn resolve_proof_verification_method(
+    resolver: &ChainedDIDResolver,
+    issuer_did_document: &DIDDocument,
+    proof: &Proof,
+    verification_relationship: VerificationRelationship,
+) -> Result<(VerificationMethod, bool), UserError> {
+    let proof_verification_method = proof.get_verification_method();
+    if let Some(verification_method) =
+        issuer_did_document.get_verification_method(proof_verification_method)
+    {
+        return Ok((
+            verification_method.clone(),
+            issuer_did_document.is_authorized(proof_verification_method, verification_relationship),
+        ));
+    }
+    let did = proof_verification_method
+        .did()
+        .and_then(|did| URL::new(did).ok())
+        .ok_or_else(|| {
+            error!(
+                "Verification method {} is not a DID URL.",
+                proof_verification_method
+            );
+            UserError::BadRequest
+        })?;
+    let did_document = resolver.resolve(&did).map_err(|e| {
+        error!("Could not resolve {}: {:?}", did, e);
+        UserError::BadRequest
+    })?;
+    let verification_method = did_document
+        .get_verification_method(proof_verification_method)
+        .cloned()
+        .ok_or_else(|| {
+            error!(
+                "Could not find verification method {} in {}.",
+                proof_verification_method, did
+            );
+            UserError::BadRequest
+        })?;
+    let is_authorized =
+        did_document.is_authorized(proof_verification_method, verification_relationship);
+    Ok((verification_method, is_authorized))
+}
+
+// Returns whether the proof's signature is valid, or why the proof could not be checked at all.
+pub(crate) fn verify_verifiable_credential_proof(
+    cryptosuites: &CryptosuiteRegistry,
     cred: &Credential,
     proof: &Proof,
+    previous_proof: Option<&Proof>,
     verifying_key: &[u8],
     verification_method: VerificationMethod,
-) -> bool {
-    let cryptographic_suite = MyEcdsaSecp256k1::new();
-    let proof_purpose = "Proof Purpose".to_string();
-    let created = Utc::now();
-    let domain = "Proof Domain".to_string();
-    let challenge = "Proof Challenge".to_string();
-    let proof_options = ProofOptions::new(
+) -> Result<bool, ProofVerificationError> {
+    let cryptographic_suite = cryptosuites
+        .get_suite_for_proof(proof)
+        .map_err(|e| ProofVerificationError::MalformedProofError(e.to_string()))?;
+    let mut proof_options = get_credential_proof_options(verification_method);
+    if let Some(previous_proof) = previous_proof {
+        proof_options.set_previous_proof(previous_proof.clone());
+    }
+    cryptographic_suite.verify_proof(cred, proof, verifying_key, &proof_options)
+}
+
+// Status lists served by this backend are read directly instead of over HTTP.
+fn fetch_status_list_credential(
+    app_state: &AppState,
+    status_list_url: &URL,
+) -> Result<VerifiableCredential, UserError> {
+    if let Some(status_list_id) = status::get_local_status_list_id(status_list_url) {
+        let registry = app_state.registry.lock().map_err(|_e| {
+            error!("Could not lock registry.");
+            UserError::InternalServerError
+        })?;
+        let issuer_db = app_state.issuer_db.lock().map_err(|_e| {
+            error!("Could not lock issuer db.");
+            UserError::InternalServerError
+        })?;
+        return status::get_status_list_credential(
+            app_state,
+            &registry,
+            &issuer_db,
+            status_list_id,
+        );
+    }
+    let status_list_json = http::new_agent(false)
+        .get(status_list_url.get_str())
+        .call()
+        .map_err(|e| {
+            error!("Could not fetch status list {}: {:?}", status_list_url, e);
+            UserError::BadRequest
+        })?
+        .into_string()
+        .map_err(|e| {
+            error!("Could not read status list {}: {:?}", status_list_url, e);
+            UserError::BadRequest
+        })?;
+    VerifiableCredential::from_json(&status_list_json).map_err(|e| {
+        error!("Could not deserialize status list credential: {:?}", e);
+        UserError::BadRequest
+    })
+}
+
+// Returns whether any status entry with the given purpose is set in its status list.
+fn is_credential_status_set(
+    cred: &Credential,
+    status_purpose: &str,
+    registry_resolver: &dyn DIDResolver,
+    app_state: &AppState,
+) -> Result<bool, UserError> {
+    for credential_status in cred.get_credential_status() {
+        if credential_status.get_status_purpose() == status_purpose
+            && is_status_list_bit_set(cred, credential_status, registry_resolver, app_state)?
+        {
+            return Ok(true);
+        }
+    }
+    Ok(false)
+}
+
+fn is_status_list_bit_set(
+    cred: &Credential,
+    credential_status: &CredentialStatus,
+    registry_resolver: &dyn DIDResolver,
+    app_state: &AppState,
+) -> Result<bool, UserError> {
+    let status_list_credential =
+        fetch_status_list_credential(app_state, credential_status.get_status_list_credential())?;
+    let status_list = status_list_credential.get_credential();
+    if status_list.get_issuer() != cred.get_issuer() {
+        error!("Status list was not issued by the credential issuer.");
+        return Err(UserError::BadRequest);
+    }
+    let status_list_proof = status_list_credential.get_proof().first().ok_or_else(|| {
+        error!("No proofs found in status list credential.");
+        UserError::BadRequest
+    })?;
+    let issuer_id = cred.get_issuer();
+    let web_resolver = WebDIDResolver::new();
+    let resolver = ChainedDIDResolver::new(vec![registry_resolver, &KeyDIDResolver, &web_resolver]);
+    let issuer_did_document = resolver.resolve(issuer_id).map_err(|e| {
+        error!("Could not resolve issuer {}: {:?}", issuer_id, e);
+        UserError::BadRequest
+    })?;
+    let (verification_method, is_authorized) = resolve_proof_verification_method(
+        &resolver,
+        &issuer_did_document,
+        status_list_proof,
+        VerificationRelationship::AssertionMethod,
+    )?;
+    if verification_method.get_controller_id() != issuer_id || !is_authorized {
+        error!("Status list was not signed by an assertion method of the credential issuer.");
+        return Err(UserError::BadRequest);
+    }
+    if !verification_method.is_valid_at(status_list_proof.get_created()) {
+        error!(
+            "Verification method {} was revoked or not valid when the status list was signed.",
+            verification_method.get_id()
+        );
+        return Err(UserError::BadRequest);
+    }
+    let (_key_type, verifying_key) = verification_method.get_public_key().map_err(|e| {
+        error!("Could not decode verification method key: {:?}", e);
+        UserError::BadRequest
+    })?;
+    match verify_verifiable_credential_proof(
+        &app_state.cryptosuites,
+        status_list,
+        status_list_proof,
+        None,
+        &verifying_key,
         verification_method,
-        proof_purpose,
-        created,
-        domain,
-        challenge,
-    );
-    match cryptographic_suite.verify_proof(cred, proof, verifying_key, &proof_options) {
-        Ok(is_valid_proof) => is_valid_proof,
+    ) {
+        Ok(true) => {}
+        Ok(false) => {
+            error!("Invalid status list credential proof.");
+            return Err(UserError::BadRequest);
+        }
         Err(e) => {
-            error!("Error verifying proof: {:?}", e);
-            false
+            error!("Invalid status list credential proof: {}", e);
+            return Err(UserError::BadRequest);
         }
     }
+    status_list::get_status(credential_status, status_list).map_err(|e| {
+        error!("Could not get credential status: {:?}", e);
+        UserError::BadRequest
+    })
 }
 
 pub fn init_routes() -> Scope {
@@ -400,4 +1026,256 @@ pub fn init_routes() -> Scope {
         .service(get_verifier)
         .service(get_all_verifiers)
         .service(verify_credential)
+        .service(new_presentation_challenge)
+        .service(verify_presentation)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::{
+        new_test_app_state, new_test_credential, new_test_holder, new_test_issuer,
+        new_test_presentation,
+    };
+    use serde_json::json;
+    use std::collections::HashMap;
+
+    fn new_credential(credential_subject: serde_json::Value) -> Credential {
+        serde_json::from_value(json!({
+            "@context": ["https://www.w3.org/ns/credentials/v2"],
+            "id": "urn:uuid:58172aac-d8ba-11ed-83dd-0b3aef56cc33",
+            "type": ["VerifiableCredential"],
+            "issuer": "did:example:issuer",
+            "credentialSubject": credential_subject,
+        }))
+        .unwrap()
+    }
+
+    fn new_verifier(allow_bearer_credentials: bool) -> Verifier {
+        Verifier::new(
+            URL::new("did:example:verifier").unwrap(),
+            "Test Verifier".to_string(),
+            URL::new("https://example.com/schema").unwrap(),
+            Vec::new(),
+            allow_bearer_credentials,
+        )
+    }
+
+    #[test]
+    fn accepts_credentials_presented_by_their_subject() {
+        let holder = URL::new("did:example:holder").unwrap();
+        let credential = new_credential(json!({"id": "did:example:holder", "name": "Alice"}));
+        assert_eq!(
+            get_holder_binding_rejection(&credential, &holder, false),
+            None
+        );
+
+        let credential = new_credential(json!([
+            {"id": "did:example:other"},
+            {"id": "did:example:holder"},
+        ]));
+        assert_eq!(
+            get_holder_binding_rejection(&credential, &holder, false),
+            None
+        );
+    }
+
+    #[test]
+    fn rejects_credentials_presented_by_another_holder() {
+        let holder = URL::new("did:example:holder").unwrap();
+        let credential = new_credential(json!({"id": "did:example:other"}));
+        for allow_bearer_credentials in [false, true] {
+            assert!(
+                get_holder_binding_rejection(&credential, &holder, allow_bearer_credentials)
+                    .is_some()
+            );
+        }
+    }
+
+    #[test]
+    fn rejects_bearer_credentials_unless_allowed() {
+        let holder = URL::new("did:example:holder").unwrap();
+        let credential = new_credential(json!({"name": "Alice"}));
+        assert!(get_holder_binding_rejection(&credential, &holder, false).is_some());
+        assert_eq!(
+            get_holder_binding_rejection(&credential, &holder, true),
+            None
+        );
+    }
+
+    fn new_schema() -> CredentialSchema {
+        CredentialSchema::new(
+            URL::new("https://example.com/schema").unwrap(),
+            "JsonSchema".to_string(),
+            "Test Schema".to_string(),
+            "".to_string(),
+            HashMap::new(),
+        )
+    }
+
+    fn new_challenge(app_state: &AppState, verifier: &Verifier) -> String {
+        let verifier_db = app_state.verifier_db.lock().unwrap();
+        challenge::new_challenge(&verifier_db, VERIFIER_CHALLENGE_CF_PATH, verifier.get_id())
+            .unwrap()
+            .0
+    }
+
+    #[test]
+    fn verifies_presentations_once_per_challenge() {
+        let app_state = new_test_app_state("verify-presentation");
+        let issuer_id = new_test_issuer(&app_state);
+        let (signing_key, holder) = new_test_holder();
+        let verifiable_credential = new_test_credential(&app_state, &issuer_id, Some(&holder));
+        let verifier = new_verifier(false);
+        let domain = verifier.get_id().get_str();
+        let challenge = new_challenge(&app_state, &verifier);
+
+        let verifiable_presentation = new_test_presentation(
+            &signing_key,
+            &holder,
+            vec![verifiable_credential],
+            domain,
+            &challenge,
+        );
+        let verify = || {
+            verify_holder_presentation(
+                &verifiable_presentation,
+                &verifier,
+                &new_schema(),
+                &app_state,
+            )
+            .unwrap()
+        };
+        let resp = verify();
+        assert!(resp.verified, "{}", resp.reason);
+        // The same presentation cannot be replayed.
+        assert!(!verify().verified);
+    }
+
+    #[test]
+    fn keeps_the_challenge_when_the_presentation_does_not_verify() {
+        let app_state = new_test_app_state("failed-presentation");
+        let issuer_id = new_test_issuer(&app_state);
+        let (signing_key, holder) = new_test_holder();
+        let (_other_signing_key, other_holder) = new_test_holder();
+        let verifiable_credential = new_test_credential(&app_state, &issuer_id, Some(&holder));
+        let verifier = new_verifier(false);
+        let domain = verifier.get_id().get_str();
+        let challenge = new_challenge(&app_state, &verifier);
+
+        // Signed for another domain.
+        let verifiable_presentation = new_test_presentation(
+            &signing_key,
+            &holder,
+            vec![verifiable_credential.clone()],
+            "did:example:other",
+            &challenge,
+        );
+        let resp = verify_holder_presentation(
+            &verifiable_presentation,
+            &verifier,
+            &new_schema(),
+            &app_state,
+        )
+        .unwrap();
+        assert!(!resp.verified);
+
+        // Presented by a holder the credential was not issued to.
+        let verifiable_presentation = new_test_presentation(
+            &signing_key,
+            &other_holder,
+            vec![verifiable_credential.clone()],
+            domain,
+            &challenge,
+        );
+        let resp = verify_holder_presentation(
+            &verifiable_presentation,
+            &verifier,
+            &new_schema(),
+            &app_state,
+        )
+        .unwrap();
+        assert!(!resp.verified);
+
+        let verifiable_presentation = new_test_presentation(
+            &signing_key,
+            &holder,
+            vec![verifiable_credential],
+            domain,
+            &challenge,
+        );
+        let resp = verify_holder_presentation(
+            &verifiable_presentation,
+            &verifier,
+            &new_schema(),
+            &app_state,
+        )
+        .unwrap();
+        assert!(resp.verified, "{}", resp.reason);
+    }
+
+    #[test]
+    fn reports_revoked_and_suspended_credentials() {
+        let app_state = new_test_app_state("verify-credential-status");
+        let issuer_id = new_test_issuer(&app_state);
+        let verifiable_credential = new_test_credential(&app_state, &issuer_id, None);
+        let credential_id = verifiable_credential.get_credential().get_id();
+        let verifier = new_verifier(true);
+        let verify = || {
+            verify_verifiable_credential(
+                &verifiable_credential,
+                &verifier,
+                &new_schema(),
+                &app_state,
+            )
+            .unwrap()
+        };
+        let set_status = |status_purpose| {
+            let issuer_db = app_state.issuer_db.lock().unwrap();
+            status::set_credential_status(&issuer_db, credential_id, status_purpose, true).unwrap();
+        };
+        let resp = verify();
+        assert!(resp.verified, "{}", resp.reason);
+
+        set_status(SUSPENSION_STATUS_PURPOSE);
+        let resp = verify();
+        assert!(!resp.verified);
+        assert_eq!(resp.reason, "Credential has been suspended.");
+
+        set_status(REVOCATION_STATUS_PURPOSE);
+        let resp = verify();
+        assert!(!resp.verified);
+        assert_eq!(resp.reason, "Credential has been revoked.");
+    }
+
+    #[test]
+    fn rejects_presentation_proofs_without_a_registered_suite() {
+        let app_state = new_test_app_state("unknown-presentation-suite");
+        let issuer_id = new_test_issuer(&app_state);
+        let (signing_key, holder) = new_test_holder();
+        let verifiable_credential = new_test_credential(&app_state, &issuer_id, Some(&holder));
+        let verifier = new_verifier(false);
+        let challenge = new_challenge(&app_state, &verifier);
+
+        let verifiable_presentation = new_test_presentation(
+            &signing_key,
+            &holder,
+            vec![verifiable_credential],
+            verifier.get_id().get_str(),
+            &challenge,
+        );
+        let mut presentation_json = serde_json::to_value(&verifiable_presentation).unwrap();
+        presentation_json["proof"][0]["type"] = json!("UnknownSignature");
+        let verifiable_presentation: VerifiablePresentation =
+            serde_json::from_value(presentation_json).unwrap();
+        let resp = verify_holder_presentation(
+            &verifiable_presentation,
+            &verifier,
+            &new_schema(),
+            &app_state,
+        )
+        .unwrap();
+        assert!(!resp.verified);
+        assert_eq!(resp.reason, "Unknown proof type: UnknownSignature.");
+    }
 }
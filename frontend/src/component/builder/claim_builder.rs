@@ -55,7 +55,7 @@ pub fn property_value_node(props: &PropertyValueNodeProps) -> Html {
                             let path = path.clone();
                             let target: Option<EventTarget> = e.target();
                             let input = target.and_then(|t| t.dyn_into::<HtmlInputElement>().ok());
-                            let value = input.expect("No claim input found.").value().parse::<i32>().expect("Input must be a number.");
+                            let value = input.expect("No claim input found.").value().parse::<serde_json::Number>().expect("Input must be a number.");
                             update_nested_claim_property.emit((path, ClaimPropertyValue::Number(value)));
                         })}
                     />
@@ -1,5 +1,7 @@
 use super::claim_builder::ClaimBuilder;
+use super::evidence_builder::{EvidenceBuilder, EvidenceForm};
 use crate::constants::BASE_URL;
+use crate::util::get_holder_did;
 use chrono::{Duration, Utc};
 use log::{debug, error};
 use serde_json::json;
@@ -8,8 +10,8 @@ use std::collections::HashMap;
 use std::rc::Rc;
 use uuid::Uuid;
 use vc_core::{
-    ClaimProperty, ClaimPropertyValue, CredentialSchema, Issuer, SchemaProperty,
-    SchemaPropertyValueType, VerifiableCredential,
+    ClaimProperty, ClaimPropertyValue, CredentialSchema, Evidence, Issuer, SchemaProperty,
+    SchemaPropertyValueType, VerifiableCredential, VerificationRelationship,
 };
 use yew::{platform::spawn_local, prelude::*};
 
@@ -19,7 +21,9 @@ fn build_claim_property_from_schema_property(property: &SchemaProperty) -> Claim
             SchemaPropertyValueType::Text => {
                 ClaimProperty::Value(ClaimPropertyValue::Text("".to_string()))
             }
-            SchemaPropertyValueType::Number => ClaimProperty::Value(ClaimPropertyValue::Number(0)),
+            SchemaPropertyValueType::Number => {
+                ClaimProperty::Value(ClaimPropertyValue::Number(0.into()))
+            }
             SchemaPropertyValueType::Boolean => {
                 ClaimProperty::Value(ClaimPropertyValue::Boolean(false))
             }
@@ -75,6 +79,20 @@ pub fn credential_builder(props: &CredentialBuilderProps) -> Html {
         )))
     });
     let claim_properties = claim_properties_state.clone();
+    // New credentials are signed with the issuer's newest key unless another one is picked.
+    let verification_method_id = use_state(|| {
+        issuer
+            .get_newest_active_verification_method(VerificationRelationship::AssertionMethod)
+            .map(|verification_method| verification_method.get_id().get_str().to_string())
+            .unwrap_or_default()
+    });
+    let evidence_form = use_state(EvidenceForm::default);
+    let set_evidence_form = {
+        let evidence_form = evidence_form.clone();
+        Callback::from(move |form: EvidenceForm| {
+            evidence_form.set(form);
+        })
+    };
 
     let update_nested_claim_property = {
         let schema_properties = schema_properties.clone();
@@ -130,27 +148,46 @@ pub fn credential_builder(props: &CredentialBuilderProps) -> Html {
     let issuer_id = issuer.get_id().get_str().to_string().clone();
     let schema_id = schema.get_id().get_str().to_string().clone();
     let submit_credential = {
+        let evidence_form = evidence_form.clone();
+        let verification_method_id = verification_method_id.clone();
         Callback::from(move |e: MouseEvent| {
             e.prevent_default();
             let context = vec!["https://www.w3.org/ns/credentials/v2".to_string()];
-            let credential_id = Uuid::new_v4().to_string();
+            let credential_id = format!("urn:uuid:{}", Uuid::new_v4());
             let type_ = vec!["VerifiableCredential".to_string()];
             let issuer_id = issuer_id.clone();
+            let verification_method_id = (*verification_method_id).clone();
             let valid_from = Utc::now().to_rfc3339();
             let valid_until = (Utc::now() + Duration::days(100)).to_rfc3339();
-            let credential_subject = claim_properties_state.borrow().clone();
+            let mut credential_subject = claim_properties_state.borrow().clone();
+            // Bind the credential to the holder, so that only they can present it.
+            match get_holder_did() {
+                Ok(holder) => {
+                    credential_subject.insert(
+                        "id".to_string(),
+                        ClaimProperty::Value(ClaimPropertyValue::Text(holder.to_string())),
+                    );
+                }
+                Err(e) => {
+                    error!("Error getting holder DID: {}", e);
+                    return;
+                }
+            }
             let schema_id = schema_id.clone();
             let credential_schema_ids = vec![schema_id];
+            let evidence: Vec<Evidence> = evidence_form.to_evidence().into_iter().collect();
             let set_credential = set_credential.clone();
             let request_data = json!({
                 "context": context,
                 "credential_id": credential_id,
                 "type_": type_,
                 "issuer_id": issuer_id,
+                "verification_method_id": verification_method_id,
                 "valid_from": valid_from,
                 "valid_until": valid_until,
                 "credential_subject": credential_subject,
                 "credential_schema_ids": credential_schema_ids,
+                "evidence": evidence,
             });
             let client = reqwest::Client::new();
             let future = async move {
@@ -180,12 +217,41 @@ pub fn credential_builder(props: &CredentialBuilderProps) -> Html {
         })
     };
 
+    let verification_method_list = issuer
+        .get_verification_methods()
+        .iter()
+        .filter(|verification_method| {
+            verification_method.is_active()
+                && issuer.is_authorized(
+                    verification_method.get_id(),
+                    VerificationRelationship::AssertionMethod,
+                )
+        })
+        .map(|verification_method| {
+            let id = verification_method.get_id().get_str().to_string();
+            let class = if *verification_method_id == id {
+                "block text-left font-bold"
+            } else {
+                "block text-left text-gray-600"
+            };
+            let verification_method_id = verification_method_id.clone();
+            let id_clone = id.clone();
+            html! {
+                <button class={class} onclick={move |_| verification_method_id.set(id_clone.clone())}>
+                    {id}{" ("}{verification_method.get_type()}{")"}
+                </button>
+            }
+        })
+        .collect::<Html>();
+
     let credential_form = html! {
         <div>
             <div class="p-4 border border-gray-200">
                 <div>{"Issuer: "}</div>
                 <h2 class="text-xl font-bold">{issuer.get_name()}</h2>
                 <p class="text-gray-600">{"ID: "}{issuer.get_id()}</p>
+                <div class="mt-2">{"Signing key: "}</div>
+                {verification_method_list}
             </div>
             <div class="p-4 border border-gray-200 mt-2">
                 <div>{"Schema: "}</div>
@@ -197,6 +263,10 @@ pub fn credential_builder(props: &CredentialBuilderProps) -> Html {
                 <div>{"Claims: "}</div>
                 <ClaimBuilder schema={schema.clone()} claim_properties={Rc::clone(&claim_properties)} update_nested_claim_property={update_nested_claim_property} />
             </div>
+            <div class="p-4 border border-gray-200 mt-2">
+                <div>{"Evidence: "}</div>
+                <EvidenceBuilder evidence_form={(*evidence_form).clone()} set_evidence_form={set_evidence_form} />
+            </div>
         </div>
     };
 
@@ -2,7 +2,7 @@ use crate::constants::INDEXEDDB_OBJECT_STORE_NAME;
 use crate::util::get_indexeddb_connector;
 use crate::Route;
 use indexed_db_futures::prelude::*;
-use vc_core::{ClaimProperty, ClaimPropertyValue, VerifiableCredential};
+use vc_core::{multibase, ClaimProperty, ClaimPropertyValue, Evidence, VerifiableCredential};
 use wasm_bindgen::prelude::*;
 use yew::{platform::spawn_local, prelude::*};
 use yew_router::prelude::Redirect;
@@ -94,6 +94,42 @@ pub fn claim_property_node(props: &ClaimPropertyNodeProps) -> Html {
     }
 }
 
+#[derive(Properties, PartialEq)]
+pub struct EvidenceNodeProps {
+    pub evidence: Evidence,
+}
+
+#[function_component(EvidenceNode)]
+pub fn evidence_node(props: &EvidenceNodeProps) -> Html {
+    let evidence = props.evidence.clone();
+    html! {
+        <div class="border-md rounded bg-slate-50 m-2">
+            <div>
+                {"Type: "} {evidence.get_type().join(", ")}
+            </div>
+            <div>
+                {"Verifier: "} {evidence.get_verifier()}
+            </div>
+            <div>
+                {"Document: "} {evidence.get_evidence_document()}
+            </div>
+            <div>
+                {"Subject Presence: "} {evidence.get_subject_presence().to_string()}
+            </div>
+            <div>
+                {"Document Presence: "} {evidence.get_document_presence().to_string()}
+            </div>
+            {for evidence.get_document_digests().iter().map(|document_digest| {
+                html! {
+                    <div>
+                        {"Digest of "} {document_digest.get_name()} {": "} {document_digest.get_digest_multibase()}
+                    </div>
+                }
+            })}
+        </div>
+    }
+}
+
 #[derive(Properties, PartialEq)]
 pub struct DisplayCredentialProps {
     pub verifiable_credential: VerifiableCredential,
@@ -106,6 +142,7 @@ pub fn display_credential(props: &DisplayCredentialProps) -> Html {
     let credential = verifiable_credential.get_credential().clone();
     let claims = credential.get_credential_subject();
     let proofs = verifiable_credential.get_proof();
+    let evidence = credential.get_evidence();
 
     let save_credential = {
         let verifiable_credential = verifiable_credential.clone();
@@ -151,8 +188,8 @@ pub fn display_credential(props: &DisplayCredentialProps) -> Html {
             <h2 class="text-xl font-bold">{"Credential: "}</h2>
             <p class="text-gray-600">{"ID: "}{credential.get_id()}</p>
             <p class="text-gray-600">{"Issuer: "}{credential.get_issuer()}</p>
-            <p class="text-gray-600">{"Valid From: "}{credential.get_valid_from()}</p>
-            <p class="text-gray-600">{"Valid Until: "}{credential.get_valid_until()}</p>
+            <p class="text-gray-600">{"Valid From: "}{credential.get_valid_from().map_or("-".to_string(), |valid_from| valid_from.to_string())}</p>
+            <p class="text-gray-600">{"Valid Until: "}{credential.get_valid_until().map_or("-".to_string(), |valid_until| valid_until.to_string())}</p>
             <div class="text-left">
                 {"{"}
                 {for claims.iter().map(|(key, value)| {
@@ -164,6 +201,16 @@ pub fn display_credential(props: &DisplayCredentialProps) -> Html {
                 })}
                 {"}"}
             </div>
+            if !evidence.is_empty() {
+                <div>
+                    <p class="text-l font-bold">{"Evidence: "}</p>
+                    <div class="text-center">
+                        {for evidence.iter().map(|evidence| {
+                            html! { <EvidenceNode evidence={evidence.clone()} /> }
+                        })}
+                    </div>
+                </div>
+            }
             <div>
                 <p class="text-l font-bold">{"Proofs: "}</p>
                 <div class="text-center">
@@ -183,7 +230,7 @@ pub fn display_credential(props: &DisplayCredentialProps) -> Html {
                                     {"Verification Method Id: "} {proof.get_verification_method()}
                                 </div>
                                 <div>
-                                    {"Proof Value: "} {hex::encode(proof.get_proof_value())}
+                                    {"Proof Value: "} {multibase::encode(proof.get_proof_value())}
                                 </div>
                             </div>
                         }
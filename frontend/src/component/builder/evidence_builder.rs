@@ -0,0 +1,141 @@
+use vc_core::{DocumentDigest, Evidence, Presence};
+use wasm_bindgen::JsCast;
+use web_sys::{EventTarget, HtmlInputElement};
+use yew::prelude::*;
+
+#[derive(Clone, Default, PartialEq)]
+pub struct EvidenceForm {
+    pub verifier: String,
+    pub evidence_document: String,
+    pub subject_remote: bool,
+    pub document_remote: bool,
+    pub document_name: String,
+    pub document_digest: String,
+}
+
+fn presence(remote: bool) -> Presence {
+    if remote {
+        Presence::Digital
+    } else {
+        Presence::Physical
+    }
+}
+
+impl EvidenceForm {
+    // Evidence is only attached once the verifier and document type have been filled in.
+    pub fn to_evidence(&self) -> Option<Evidence> {
+        if self.verifier.is_empty() || self.evidence_document.is_empty() {
+            return None;
+        }
+        let mut document_digests = Vec::new();
+        if !self.document_name.is_empty() && !self.document_digest.is_empty() {
+            document_digests.push(DocumentDigest::new(
+                self.document_name.clone(),
+                self.document_digest.clone(),
+            ));
+        }
+        Some(Evidence::new(
+            None,
+            self.verifier.clone(),
+            self.evidence_document.clone(),
+            presence(self.subject_remote),
+            presence(self.document_remote),
+            document_digests,
+        ))
+    }
+}
+
+#[derive(Properties, PartialEq)]
+pub struct EvidenceBuilderProps {
+    pub evidence_form: EvidenceForm,
+    pub set_evidence_form: Callback<EvidenceForm>,
+}
+
+#[function_component(EvidenceBuilder)]
+pub fn evidence_builder(props: &EvidenceBuilderProps) -> Html {
+    let evidence_form = props.evidence_form.clone();
+
+    let handle_text_input = |update: fn(&mut EvidenceForm, String)| {
+        let evidence_form = evidence_form.clone();
+        let set_evidence_form = props.set_evidence_form.clone();
+        Callback::from(move |e: InputEvent| {
+            let target: Option<EventTarget> = e.target();
+            let input = target.and_then(|t| t.dyn_into::<HtmlInputElement>().ok());
+
+            if let Some(input) = input {
+                let mut evidence_form = evidence_form.clone();
+                update(&mut evidence_form, input.value());
+                set_evidence_form.emit(evidence_form);
+            }
+        })
+    };
+    let handle_checkbox_click = |update: fn(&mut EvidenceForm)| {
+        let evidence_form = evidence_form.clone();
+        let set_evidence_form = props.set_evidence_form.clone();
+        Callback::from(move |_| {
+            let mut evidence_form = evidence_form.clone();
+            update(&mut evidence_form);
+            set_evidence_form.emit(evidence_form);
+        })
+    };
+
+    html! {
+        <div>
+            <div>
+                {"Verifier: "}
+                <input
+                    class="border rounded-md"
+                    type="text"
+                    placeholder="Verifier name"
+                    value={evidence_form.verifier.clone()}
+                    oninput={handle_text_input(|form, value| form.verifier = value)}
+                />
+            </div>
+            <div>
+                {"Document: "}
+                <input
+                    class="border rounded-md"
+                    type="text"
+                    placeholder="Document type"
+                    value={evidence_form.evidence_document.clone()}
+                    oninput={handle_text_input(|form, value| form.evidence_document = value)}
+                />
+            </div>
+            <div>
+                {"Subject checked remotely: "}
+                <input
+                    class="border rounded-md"
+                    type="checkbox"
+                    checked={evidence_form.subject_remote}
+                    onclick={handle_checkbox_click(|form| form.subject_remote = !form.subject_remote)}
+                />
+            </div>
+            <div>
+                {"Document checked remotely: "}
+                <input
+                    class="border rounded-md"
+                    type="checkbox"
+                    checked={evidence_form.document_remote}
+                    onclick={handle_checkbox_click(|form| form.document_remote = !form.document_remote)}
+                />
+            </div>
+            <div>
+                {"Supporting document: "}
+                <input
+                    class="border rounded-md"
+                    type="text"
+                    placeholder="Name"
+                    value={evidence_form.document_name.clone()}
+                    oninput={handle_text_input(|form, value| form.document_name = value)}
+                />
+                <input
+                    class="border rounded-md ml-2"
+                    type="text"
+                    placeholder="Digest (multibase)"
+                    value={evidence_form.document_digest.clone()}
+                    oninput={handle_text_input(|form, value| form.document_digest = value)}
+                />
+            </div>
+        </div>
+    }
+}
@@ -2,5 +2,6 @@ pub mod builder_home;
 pub mod claim_builder;
 pub mod credential_builder;
 pub mod display_credential;
+pub mod evidence_builder;
 pub mod select_issuer;
 pub mod select_schema;
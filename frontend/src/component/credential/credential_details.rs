@@ -1,9 +1,12 @@
-use crate::component::builder::display_credential::ClaimPropertyNode;
+use crate::component::builder::display_credential::{ClaimPropertyNode, EvidenceNode};
 use crate::component::nav_bar::NavBar;
-use crate::constants::INDEXEDDB_OBJECT_STORE_NAME;
-use crate::util::get_indexeddb_connector;
+use crate::constants::{CREDENTIAL_REFRESH_WINDOW_DAYS, INDEXEDDB_OBJECT_STORE_NAME};
+use crate::util::{get_indexeddb_connector, new_signed_presentation, PresentationChallenge};
+use chrono::{Duration, Utc};
 use indexed_db_futures::prelude::*;
-use vc_core::VerifiableCredential;
+use log::{debug, error};
+use serde_json::json;
+use vc_core::{multibase, VerifiableCredential};
 use wasm_bindgen::prelude::*;
 use yew::{platform::spawn_local, prelude::*};
 
@@ -45,9 +48,8 @@ pub fn credential_details(props: &CredentialDetailsProps) -> Html {
                     .expect("Could not get credential from IndexedDB store.")
                     .as_string()
                     .expect("Could not convert credential to string.");
-                let fetched_credential: VerifiableCredential =
-                    serde_json::from_str(&fetched_credential)
-                        .expect("Could not deserialize credential.");
+                let fetched_credential = VerifiableCredential::from_json(&fetched_credential)
+                    .expect("Could not deserialize credential.");
                 credential.set(Some(fetched_credential));
             };
             spawn_local(future);
@@ -62,18 +64,138 @@ pub fn credential_details(props: &CredentialDetailsProps) -> Html {
         (),
     );
 
+    let refresh_credential = {
+        let credential = credential.clone();
+        Callback::from(move |_| {
+            let credential = credential.clone();
+            let verifiable_credential = match (*credential).clone() {
+                Some(verifiable_credential) => verifiable_credential,
+                None => return,
+            };
+            let refresh_service = match verifiable_credential
+                .get_credential()
+                .get_refresh_service()
+                .first()
+            {
+                Some(refresh_service) => refresh_service.clone(),
+                None => return,
+            };
+            let issuer_id = verifiable_credential
+                .get_credential()
+                .get_issuer()
+                .get_str()
+                .to_string();
+            let client = reqwest::Client::new();
+            let future = async move {
+                // The holder proves they are the credential subject by presenting the credential
+                // for a challenge from the issuer.
+                let url = refresh_service.get_url().get_str();
+                let challenge_url = format!("{}/challenge", url);
+                let challenge_request_data = json!({
+                    "issuer_id": issuer_id,
+                });
+                let challenge = match client
+                    .post(challenge_url)
+                    .json(&challenge_request_data)
+                    .send()
+                    .await
+                {
+                    Ok(resp) => match resp.json::<PresentationChallenge>().await {
+                        Ok(challenge) => challenge,
+                        Err(e) => {
+                            error!("Error parsing refresh challenge: {:?}", e);
+                            return;
+                        }
+                    },
+                    Err(e) => {
+                        error!("Error getting refresh challenge: {:?}", e);
+                        return;
+                    }
+                };
+                let verifiable_presentation =
+                    match new_signed_presentation(verifiable_credential, challenge) {
+                        Ok(verifiable_presentation) => verifiable_presentation,
+                        Err(e) => {
+                            error!("Error signing verifiable presentation: {}", e);
+                            return;
+                        }
+                    };
+                let serialized_presentation = serde_json::to_string(&verifiable_presentation)
+                    .expect("Could not serialize verifiable presentation.");
+                let request_data = json!({
+                    "verifiable_presentation": serialized_presentation,
+                });
+                let resp = client.post(url).json(&request_data).send().await;
+                let refreshed_credential = match resp {
+                    Ok(resp) => {
+                        debug!("Response from refreshing credential: {:?}", resp);
+                        match resp.json::<VerifiableCredential>().await {
+                            Ok(refreshed_credential) => refreshed_credential,
+                            Err(e) => {
+                                error!(
+                                    "Error parsing response from refreshing credential: {:?}",
+                                    e
+                                );
+                                return;
+                            }
+                        }
+                    }
+                    Err(e) => {
+                        error!("Error refreshing credential: {:?}", e);
+                        return;
+                    }
+                };
+                let db = get_indexeddb_connector()
+                    .await
+                    .expect("Could not open IndexedDB.");
+                let tx: IdbTransaction = db
+                    .transaction_on_one_with_mode(
+                        INDEXEDDB_OBJECT_STORE_NAME,
+                        IdbTransactionMode::Readwrite,
+                    )
+                    .expect("Could not create IndexedDB transaction.");
+                let store: IdbObjectStore = tx
+                    .object_store(INDEXEDDB_OBJECT_STORE_NAME)
+                    .expect("Could not create IndexedDB object store.");
+                let key = refreshed_credential.get_credential().get_id().get_str();
+                let serialized_credential = serde_json::to_string(&refreshed_credential)
+                    .expect("Could not serialize credential.");
+                let serialized_credential_js = JsValue::from_str(&serialized_credential);
+                store
+                    .put_key_val_owned(key, &serialized_credential_js)
+                    .expect("Could not insert credential into IndexedDB store.");
+                credential.set(Some(refreshed_credential));
+            };
+            spawn_local(future);
+        })
+    };
+
     let content = match (*credential).clone() {
         Some(credential) => {
             let proofs = credential.get_proof().clone();
             let credential = credential.get_credential();
             let claims = credential.get_credential_subject();
+            let evidence = credential.get_evidence();
+            let is_expiring = credential.get_valid_until().is_some_and(|valid_until| {
+                valid_until <= Utc::now() + Duration::days(CREDENTIAL_REFRESH_WINDOW_DAYS)
+            });
+            let refresh_action = if is_expiring && !credential.get_refresh_service().is_empty() {
+                html! {
+                    <div class="text-center mt-2">
+                        <button onclick={refresh_credential} class="text-white bg-blue-300 rounded-md p-2">{"Refresh"}</button>
+                    </div>
+                }
+            } else {
+                html! {}
+            };
             html! {
                 <div class="m-8">
                     <h2 class="text-xl font-bold">{"Credential: "}</h2>
                     <p class="text-gray-600">{"ID: "}{credential.get_id()}</p>
                     <p class="text-gray-600">{"Issuer: "}{credential.get_issuer()}</p>
-                    <p class="text-gray-600">{"Valid From: "}{credential.get_valid_from()}</p>
-                    <p class="text-gray-600">{"Valid Until: "}{credential.get_valid_until()}</p>
+                    <p class="text-gray-600">{"Valid From: "}{credential.get_valid_from().map_or("-".to_string(), |valid_from| valid_from.to_string())}</p>
+                    <p class="text-gray-600">{"Valid Until: "}{credential.get_valid_until().map_or("-".to_string(), |valid_until| valid_until.to_string())}</p>
+                    {refresh_action}
                     <div class="text-left">
                         {"{"}
                         {for claims.iter().map(|(key, value)| {
@@ -85,6 +207,16 @@ pub fn credential_details(props: &CredentialDetailsProps) -> Html {
                         })}
                         {"}"}
                     </div>
+                    if !evidence.is_empty() {
+                        <div>
+                            <p class="text-l font-bold">{"Evidence: "}</p>
+                            <div class="text-center">
+                                {for evidence.iter().map(|evidence| {
+                                    html! { <EvidenceNode evidence={evidence.clone()} /> }
+                                })}
+                            </div>
+                        </div>
+                    }
                     <div>
                         <p class="text-l font-bold">{"Proofs: "}</p>
                         <div class="text-center">
@@ -104,7 +236,7 @@ pub fn credential_details(props: &CredentialDetailsProps) -> Html {
                                             {"Verification Method Id: "} {proof.get_verification_method()}
                                         </div>
                                         <div>
-                                            {"Proof Value: "} {hex::encode(proof.get_proof_value())}
+                                            {"Proof Value: "} {multibase::encode(proof.get_proof_value())}
                                         </div>
                                     </div>
                                 }
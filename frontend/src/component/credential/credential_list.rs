@@ -43,7 +43,7 @@ pub fn use_credentials() -> (Rc<Vec<VerifiableCredential>>, bool, Callback<()>)
                     let credential: String = credential
                         .as_string()
                         .expect("Could not convert credential to string.");
-                    let credential: VerifiableCredential = serde_json::from_str(&credential)
+                    let credential: VerifiableCredential = VerifiableCredential::from_json(&credential)
                         .expect("Could not deserialize credential.");
                     fetched_credentials.push(credential);
                 }
@@ -37,7 +37,7 @@ pub fn AddIssuer(props: &AddIssuerProps) -> Html {
             let fetch_issuers = fetch_issuers.clone();
             let client = client.clone();
             let request_data = json!({
-                "id": Uuid::new_v4().to_string(),
+                "id": format!("urn:uuid:{}", Uuid::new_v4()),
                 "name": *name,
             });
             let future = async move {
@@ -2,6 +2,7 @@ use crate::constants::BASE_URL;
 use log::{debug, error};
 use serde_json::json;
 use uuid::Uuid;
+use vc_core::cryptosuite::MY_K256_VERIFICATION_METHOD_TYPE;
 use wasm_bindgen::JsCast;
 use web_sys::{EventTarget, HtmlInputElement};
 use yew::{platform::spawn_local, prelude::*};
@@ -14,7 +15,7 @@ pub struct AddVerificationMethodProps {
 
 #[function_component]
 pub fn AddVerificationMethod(props: &AddVerificationMethodProps) -> Html {
-    let type_ = use_state(|| "MyK256VerificationMethod".to_string());
+    let type_ = use_state(|| MY_K256_VERIFICATION_METHOD_TYPE.to_string());
     let issuer_id = props.issuer_id.clone();
     let fetch_issuer = props.fetch_issuer.clone();
     let client = reqwest::Client::new();
@@ -40,7 +41,7 @@ pub fn AddVerificationMethod(props: &AddVerificationMethodProps) -> Html {
             let fetch_issuer = fetch_issuer.clone();
             let client = client.clone();
             let request_data = json!({
-                "verification_method_id": Uuid::new_v4().to_string(),
+                "verification_method_id": format!("urn:uuid:{}", Uuid::new_v4()),
                 "type_": *type_,
             });
             let future = async move {
@@ -2,7 +2,7 @@ use super::add_verification_method::AddVerificationMethod;
 use crate::component::nav_bar::NavBar;
 use crate::constants::BASE_URL;
 use log::error;
-use vc_core::Issuer;
+use vc_core::{Issuer, VerificationRelationship};
 use yew::{platform::spawn_local, prelude::*};
 
 async fn get_issuer(issuer_id: String) -> Result<Issuer, reqwest::Error> {
@@ -60,13 +60,35 @@ pub fn issuer_details(props: &IssuerDetailsProps) -> Html {
                                 <p class="text-gray-600">{"Verification Methods:"}</p>
                                 <ul>
                                     {for issuer.get_verification_methods().iter().map(|vm| {
+                                        let key = match vm.get_public_key_multibase() {
+                                            Some(public_key_multibase) => public_key_multibase.clone(),
+                                            None => vm
+                                                .get_public_key_jwk()
+                                                .ok()
+                                                .and_then(|jwk| serde_json::to_string(&jwk).ok())
+                                                .unwrap_or_default(),
+                                        };
+                                        let verification_relationships = VerificationRelationship::ALL
+                                            .into_iter()
+                                            .filter(|relationship| issuer.is_authorized(vm.get_id(), *relationship))
+                                            .map(|relationship| relationship.to_string())
+                                            .collect::<Vec<String>>()
+                                            .join(", ");
                                         html! {
                                             <div class="bg-slate-50 rounded m-2">
                                                 <li>
                                                     <p class="text-gray-600">{"ID: "} {vm.get_id()}</p>
                                                     <p class="text-gray-600">{"Type: "} {vm.get_type()}</p>
                                                     <p class="text-gray-600">{"Issuer ID: "} {vm.get_controller_id()}</p>
-                                                    <p class="text-gray-600">{"Key: "} {hex::encode(vm.get_public_key_multibase())}</p>
+                                                    <p class="text-gray-600">{"Key: "} {key}</p>
+                                                    <p class="text-gray-600">{"Relationships: "} {verification_relationships}</p>
+                                                    <p class="text-gray-600">{"Status: "} {vm.get_status()}</p>
+                                                    if let Some(created) = vm.get_created() {
+                                                        <p class="text-gray-600">{"Created: "} {created.to_rfc3339()}</p>
+                                                    }
+                                                    if let Some(revoked) = vm.get_revoked() {
+                                                        <p class="text-gray-600">{"Valid until: "} {revoked.to_rfc3339()}</p>
+                                                    }
                                                 </li>
                                             </div>
                                         }
@@ -1,4 +1,7 @@
-use crate::constants::BASE_URL;
+use crate::{
+    constants::BASE_URL,
+    util::{new_signed_presentation, PresentationChallenge},
+};
 use log::{debug, error};
 use serde::Deserialize;
 use serde_json::json;
@@ -32,32 +35,57 @@ pub fn presentation_builder(props: &PresentationBuilderProps) -> Html {
         e.prevent_default();
         let client = reqwest::Client::new();
         let verifier_id = verifier_id.clone();
-        let verifier_id_str = verifier_id.get_str();
+        let verifier_id_str = verifier_id.get_str().to_string();
         let verifiable_credential = verifiable_credential_clone.clone();
-        let verifiable_credential_str = serde_json::to_string(&verifiable_credential)
-            .expect("Could not serialize verifiable credential.");
         let set_verified = set_verified.clone();
-        let request_data = json!({
-            "verifier_id": verifier_id_str,
-            "verifiable_credential": verifiable_credential_str,
-        });
         let future = async move {
-            let url = format!("{}/verifier/verify", BASE_URL);
+            let url = format!("{}/verifier/{}/challenge", BASE_URL, verifier_id_str);
+            let challenge = match client.post(url).send().await {
+                Ok(resp) => match resp.json::<PresentationChallenge>().await {
+                    Ok(challenge) => challenge,
+                    Err(e) => {
+                        error!("Error parsing presentation challenge: {:?}", e);
+                        return;
+                    }
+                },
+                Err(e) => {
+                    error!("Error getting presentation challenge: {:?}", e);
+                    return;
+                }
+            };
+            let verifiable_presentation =
+                match new_signed_presentation(verifiable_credential, challenge) {
+                    Ok(verifiable_presentation) => verifiable_presentation,
+                    Err(e) => {
+                        error!("Error signing verifiable presentation: {}", e);
+                        return;
+                    }
+                };
+            let verifiable_presentation_str = serde_json::to_string(&verifiable_presentation)
+                .expect("Could not serialize verifiable presentation.");
+            let request_data = json!({
+                "verifier_id": verifier_id_str,
+                "verifiable_presentation": verifiable_presentation_str,
+            });
+            let url = format!("{}/verifier/verify_presentation", BASE_URL);
             let resp = client.post(url).json(&request_data).send().await;
             match resp {
                 Ok(resp) => {
-                    debug!("Response from verifying credential: {:?}", resp);
+                    debug!("Response from verifying presentation: {:?}", resp);
                     match resp.json::<VerifyCredentialResults>().await {
                         Ok(results) => {
                             set_verified.emit(Some(results));
                         }
                         Err(e) => {
-                            error!("Error parsing response from verifying credential: {:?}", e);
+                            error!(
+                                "Error parsing response from verifying presentation: {:?}",
+                                e
+                            );
                         }
                     }
                 }
                 Err(e) => {
-                    error!("Error verifying credential: {:?}", e);
+                    error!("Error verifying presentation: {:?}", e);
                 }
             }
         };
@@ -37,7 +37,7 @@ pub fn AddSchema(props: &AddSchemaProps) -> Html {
             let fetch_schemas = fetch_schemas.clone();
             let client = client.clone();
             let request_data = json!({
-                "id": Uuid::new_v4().to_string(),
+                "id": format!("urn:uuid:{}", Uuid::new_v4()),
                 "name": *name,
             });
             let future = async move {
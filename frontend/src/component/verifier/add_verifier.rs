@@ -15,6 +15,7 @@ pub struct AddVerifierProps {
 pub fn AddVerifier(props: &AddVerifierProps) -> Html {
     let name = use_state(|| "".to_string());
     let schema_id = use_state(|| "".to_string());
+    let actions = use_state(|| "".to_string());
     let fetch_verifiers = props.fetch_verifiers.clone();
     let client = reqwest::Client::new();
 
@@ -42,19 +43,39 @@ pub fn AddVerifier(props: &AddVerifierProps) -> Html {
         })
     };
 
+    let handle_actions_input = {
+        let actions = actions.clone();
+        Callback::from(move |e: InputEvent| {
+            let target: Option<EventTarget> = e.target();
+            let input = target.and_then(|t| t.dyn_into::<HtmlInputElement>().ok());
+
+            if let Some(input) = input {
+                actions.set(input.value());
+            }
+        })
+    };
+
     let on_submit = {
         let name = name.clone();
         let schema_id = schema_id.clone();
+        let actions = actions.clone();
         Callback::from(move |e: SubmitEvent| {
             e.prevent_default();
             let name = name.clone();
             let schema_id = schema_id.clone();
+            let actions = actions.clone();
+            let declared_actions: Vec<String> = actions
+                .split(',')
+                .map(|action| action.trim().to_string())
+                .filter(|action| !action.is_empty())
+                .collect();
             let fetch_verifiers = fetch_verifiers.clone();
             let client = client.clone();
             let request_data = json!({
-                "id": Uuid::new_v4().to_string(),
+                "id": format!("urn:uuid:{}", Uuid::new_v4()),
                 "name": *name,
                 "schema_id": *schema_id,
+                "actions": declared_actions,
             });
             let future = async move {
                 let url = format!("{}/verifier/", BASE_URL);
@@ -70,6 +91,7 @@ pub fn AddVerifier(props: &AddVerifierProps) -> Html {
                 }
                 name.set("".to_string());
                 schema_id.set("".to_string());
+                actions.set("".to_string());
             };
             spawn_local(future);
         })
@@ -93,6 +115,13 @@ pub fn AddVerifier(props: &AddVerifierProps) -> Html {
                 value={(*schema_id).clone()}
                 oninput={handle_schema_id_input}
             />
+            <input
+                class="border-slate-300 border-2 rounded p-2 mr-2"
+                type="text"
+                placeholder="Actions (e.g. archival, share)"
+                value={(*actions).clone()}
+                oninput={handle_actions_input}
+            />
             <button class="rounded bg-stone-200 p-2" type="submit">{"Submit"}</button>
         </form>
         </div>
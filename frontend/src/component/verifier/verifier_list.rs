@@ -20,6 +20,7 @@ pub fn verifier_list(props: &VerifierListProps) -> Html {
                 <h2 class="text-xl font-bold">{verifier.get_name()}</h2>
                     <p class="text-gray-600">{"ID: "}{verifier_id}</p>
                     <p class="text-gray-600">{"Schema ID: "}{verifier.get_schema_id()}</p>
+                    <p class="text-gray-600">{"Actions: "}{verifier.get_actions().join(", ")}</p>
                 </div>
             }
         })
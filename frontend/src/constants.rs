@@ -1,3 +1,5 @@
 pub const BASE_URL: &str = "http://localhost:8000";
 pub const INDEXEDDB_DATABASE_NAME: &str = "verifiable_credentials_holder";
 pub const INDEXEDDB_OBJECT_STORE_NAME: &str = "credentials";
+pub const CREDENTIAL_REFRESH_WINDOW_DAYS: i64 = 30;
+pub const HOLDER_KEY_STORAGE_KEY: &str = "holder_signing_key";
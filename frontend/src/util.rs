@@ -1,20 +1,104 @@
-use crate::constants::{INDEXEDDB_DATABASE_NAME, INDEXEDDB_OBJECT_STORE_NAME};
+use crate::constants::{
+    HOLDER_KEY_STORAGE_KEY, INDEXEDDB_DATABASE_NAME, INDEXEDDB_OBJECT_STORE_NAME,
+};
+use chrono::Utc;
 use indexed_db_futures::prelude::*;
+use k256::ecdsa::SigningKey;
+use rand_core::OsRng;
+use serde::Deserialize;
+use uuid::Uuid;
+use vc_core::{
+    did_key,
+    multibase::KeyType,
+    proof::{CryptographicSuite, InMemoryKeyHandle, MyEcdsaSecp256k1Presentation, ProofOptions},
+    VerifiableCredential, VerifiablePresentation, AUTHENTICATION_PROOF_PURPOSE,
+    CREDENTIALS_CONTEXT, URL,
+};
 use wasm_bindgen::JsValue;
 use web_sys::DomException;
 
 pub async fn get_indexeddb_connector() -> Result<IdbDatabase, DomException> {
     let mut db_req: OpenDbRequest = IdbDatabase::open_u32(INDEXEDDB_DATABASE_NAME, 1)?;
     db_req.set_on_upgrade_needed(Some(|evt: &IdbVersionChangeEvent| -> Result<(), JsValue> {
-        if let None = evt
+        if !evt
             .db()
             .object_store_names()
-            .find(|n| n == INDEXEDDB_OBJECT_STORE_NAME)
+            .any(|n| n == INDEXEDDB_OBJECT_STORE_NAME)
         {
             evt.db().create_object_store(INDEXEDDB_OBJECT_STORE_NAME)?;
         }
         Ok(())
     }));
 
-    Ok(db_req.into_future().await?)
+    db_req.into_future().await
+}
+
+// The holder key signs presentations, so it is kept in local storage and reused across sessions.
+pub fn get_holder_signing_key() -> Result<SigningKey, JsValue> {
+    let storage = web_sys::window()
+        .ok_or_else(|| JsValue::from_str("No window."))?
+        .local_storage()?
+        .ok_or_else(|| JsValue::from_str("No local storage."))?;
+    let stored_key = storage
+        .get_item(HOLDER_KEY_STORAGE_KEY)?
+        .and_then(|key| hex::decode(key).ok())
+        .and_then(|key| SigningKey::from_slice(&key).ok());
+    match stored_key {
+        Some(signing_key) => Ok(signing_key),
+        None => {
+            let signing_key = SigningKey::random(&mut OsRng);
+            storage.set_item(HOLDER_KEY_STORAGE_KEY, &hex::encode(signing_key.to_bytes()))?;
+            Ok(signing_key)
+        }
+    }
+}
+
+// The holder is identified by the did:key of their signing key, both as the subject of the
+// credentials issued to them and as the holder of their presentations.
+pub fn get_holder_did() -> Result<URL, String> {
+    let signing_key = get_holder_signing_key().map_err(|e| format!("{:?}", e))?;
+    let public_key = signing_key.verifying_key().to_encoded_point(true);
+    did_key::did_from_secp256k1_public_key(public_key.as_bytes()).map_err(|e| e.to_string())
+}
+
+#[derive(Deserialize)]
+pub struct PresentationChallenge {
+    pub challenge: String,
+    pub domain: String,
+}
+
+// Wraps the credential in a presentation signed by the holder for a verifier's or issuer's challenge.
+pub fn new_signed_presentation(
+    verifiable_credential: VerifiableCredential,
+    challenge: PresentationChallenge,
+) -> Result<VerifiablePresentation, String> {
+    let signing_key = get_holder_signing_key().map_err(|e| format!("{:?}", e))?;
+    let holder = get_holder_did()?;
+    let verification_method =
+        did_key::resolve_verification_method(&holder).map_err(|e| e.to_string())?;
+    let context = URL::new(CREDENTIALS_CONTEXT).map_err(|e| e.to_string())?;
+    let id = URL::new(&format!("urn:uuid:{}", Uuid::new_v4())).map_err(|e| e.to_string())?;
+    let mut verifiable_presentation = VerifiablePresentation::new(
+        vec![context],
+        id,
+        vec!["VerifiablePresentation".to_string()],
+        vec![verifiable_credential],
+        Some(holder),
+        Vec::new(),
+    );
+
+    let proof_options = ProofOptions::new(
+        verification_method,
+        AUTHENTICATION_PROOF_PURPOSE.to_string(),
+        Utc::now(),
+        Some(challenge.domain),
+        Some(challenge.challenge),
+    );
+    let key_handle = InMemoryKeyHandle::new(KeyType::Secp256k1, &signing_key.to_bytes())
+        .map_err(|e| e.to_string())?;
+    let proof = MyEcdsaSecp256k1Presentation::new()
+        .generate_proof(&verifiable_presentation, &key_handle, &proof_options)
+        .map_err(|e| e.to_string())?;
+    verifiable_presentation.add_proof(proof);
+    Ok(verifiable_presentation)
 }
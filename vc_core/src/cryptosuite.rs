@@ -0,0 +1,306 @@
+use super::*;
+use multibase::KeyType;
+use proof::{
+    CryptographicSuite, EcdsaJcs2019, EddsaJcs2022, MyEcdsaSecp256k1, MyEcdsaSecp256k1Presentation,
+};
+
+pub const MY_K256_VERIFICATION_METHOD_TYPE: &str = "MyK256VerificationMethod";
+
+pub type CredentialCryptographicSuite =
+    dyn CryptographicSuite<DataDocument = Credential, OutputProof = Proof> + Send + Sync;
+
+pub type PresentationCryptographicSuite = dyn CryptographicSuite<DataDocument = VerifiablePresentation, OutputProof = Proof>
+    + Send
+    + Sync;
+
+#[derive(Debug)]
+pub enum CryptosuiteRegistryError {
+    UnknownProofType(String),
+    UnsupportedVerificationMethod(String),
+}
+
+impl Error for CryptosuiteRegistryError {}
+
+impl fmt::Display for CryptosuiteRegistryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnknownProofType(e) => write!(f, "Unknown proof type: {}", e),
+            Self::UnsupportedVerificationMethod(e) => {
+                write!(f, "Unsupported verification method: {}", e)
+            }
+        }
+    }
+}
+
+// Maps proof types and verification method types to the suites that sign and verify credentials,
+// and proof types to the suites that verify holder presentations.
+pub struct CryptosuiteRegistry {
+    suites: Vec<Box<CredentialCryptographicSuite>>,
+    presentation_suites: Vec<Box<PresentationCryptographicSuite>>,
+    verification_method_types: HashMap<String, usize>,
+    key_types: HashMap<KeyType, usize>,
+}
+
+impl CryptosuiteRegistry {
+    pub fn new() -> Self {
+        Self {
+            suites: Vec::new(),
+            presentation_suites: Vec::new(),
+            verification_method_types: HashMap::new(),
+            key_types: HashMap::new(),
+        }
+    }
+
+    // Registers a suite for keys of the given type, and for verification methods of the given
+    // types. Verification methods of other types (e.g. Multikey) are matched on their key type.
+    pub fn register(
+        &mut self,
+        suite: Box<CredentialCryptographicSuite>,
+        key_type: KeyType,
+        verification_method_types: &[&str],
+    ) {
+        let index = self.suites.len();
+        self.suites.push(suite);
+        self.key_types.insert(key_type, index);
+        for verification_method_type in verification_method_types {
+            self.verification_method_types
+                .insert(verification_method_type.to_string(), index);
+        }
+    }
+
+    pub fn register_presentation_suite(&mut self, suite: Box<PresentationCryptographicSuite>) {
+        self.presentation_suites.push(suite);
+    }
+
+    pub fn get_suite_for_proof(
+        &self,
+        proof: &Proof,
+    ) -> Result<&CredentialCryptographicSuite, CryptosuiteRegistryError> {
+        self.suites
+            .iter()
+            .find(|suite| is_suite_for_proof(suite.as_ref(), proof))
+            .map(|suite| suite.as_ref())
+            .ok_or_else(|| get_unknown_proof_type_error(proof))
+    }
+
+    pub fn get_presentation_suite_for_proof(
+        &self,
+        proof: &Proof,
+    ) -> Result<&PresentationCryptographicSuite, CryptosuiteRegistryError> {
+        self.presentation_suites
+            .iter()
+            .find(|suite| is_suite_for_proof(suite.as_ref(), proof))
+            .map(|suite| suite.as_ref())
+            .ok_or_else(|| get_unknown_proof_type_error(proof))
+    }
+
+    pub fn get_suite_for_verification_method(
+        &self,
+        verification_method: &VerificationMethod,
+    ) -> Result<&CredentialCryptographicSuite, CryptosuiteRegistryError> {
+        let index = match self
+            .verification_method_types
+            .get(verification_method.get_type())
+        {
+            Some(index) => Some(index),
+            None => {
+                let (key_type, _public_key) =
+                    verification_method.get_public_key().map_err(|e| {
+                        CryptosuiteRegistryError::UnsupportedVerificationMethod(e.to_string())
+                    })?;
+                self.key_types.get(&key_type)
+            }
+        };
+        index
+            .map(|index| self.suites[*index].as_ref())
+            .ok_or_else(|| {
+                CryptosuiteRegistryError::UnsupportedVerificationMethod(
+                    verification_method.get_type().clone(),
+                )
+            })
+    }
+}
+
+fn is_suite_for_proof<D>(
+    suite: &(dyn CryptographicSuite<DataDocument = D, OutputProof = Proof> + Send + Sync),
+    proof: &Proof,
+) -> bool {
+    suite.get_type() == proof.get_type()
+        && suite.get_cryptosuite() == proof.get_cryptosuite().as_deref()
+}
+
+fn get_unknown_proof_type_error(proof: &Proof) -> CryptosuiteRegistryError {
+    CryptosuiteRegistryError::UnknownProofType(match proof.get_cryptosuite() {
+        Some(cryptosuite) => format!("{} ({})", proof.get_type(), cryptosuite),
+        None => proof.get_type().clone(),
+    })
+}
+
+// The default registry holds every suite implemented in this crate.
+impl Default for CryptosuiteRegistry {
+    fn default() -> Self {
+        let mut registry = Self::new();
+        registry.register(
+            Box::new(MyEcdsaSecp256k1::new()),
+            KeyType::Secp256k1,
+            &[MY_K256_VERIFICATION_METHOD_TYPE],
+        );
+        registry.register(
+            Box::new(EddsaJcs2022::new()),
+            KeyType::Ed25519,
+            &[ED25519_VERIFICATION_KEY_2020_TYPE],
+        );
+        registry.register(
+            Box::new(EcdsaJcs2019::new()),
+            KeyType::P256,
+            &[ECDSA_SECP256R1_VERIFICATION_KEY_2019_TYPE],
+        );
+        registry.register_presentation_suite(Box::new(MyEcdsaSecp256k1Presentation::new()));
+        registry
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proof::{ECDSA_JCS_2019_CRYPTOSUITE, EDDSA_JCS_2022_CRYPTOSUITE};
+
+    const SECP256K1_PUBLIC_KEY: &str = "zQ3shokFTS3brHcDQrn82RUDfCZESWL1ZdCEJwekUDPQiYBme";
+    const ED25519_PUBLIC_KEY: &str = "z6MkiTBz1ymuepAQ4HEHYSF1H8quG5GLVVQR3djdX3mDooWp";
+    const P256_PUBLIC_KEY: &str = "zDnaepBuvsQ8cpsWrVKw8fbpGpvPeNSjVPTWoq6cRqaYzBKVP";
+
+    fn new_proof(type_: &str, cryptosuite: Option<&str>) -> Proof {
+        Proof::new(
+            type_.to_string(),
+            cryptosuite.map(|cryptosuite| cryptosuite.to_string()),
+            Utc::now(),
+            URL::new("did:example:issuer#key-1").unwrap(),
+            "assertionMethod".to_string(),
+            None,
+            None,
+            Vec::new(),
+        )
+    }
+
+    fn new_verification_method(type_: &str, public_key_multibase: &str) -> VerificationMethod {
+        VerificationMethod::new(
+            URL::new("did:example:issuer#key-1").unwrap(),
+            type_.to_string(),
+            URL::new("did:example:issuer").unwrap(),
+            public_key_multibase.to_string(),
+        )
+    }
+
+    #[test]
+    fn finds_suites_by_proof_type_and_cryptosuite() {
+        let registry = CryptosuiteRegistry::default();
+        for (proof, suite_id) in [
+            (
+                new_proof("MyEcdsaSecp256k1Signature", None),
+                MyEcdsaSecp256k1::new().get_id().clone(),
+            ),
+            (
+                new_proof(DATA_INTEGRITY_PROOF_TYPE, Some(EDDSA_JCS_2022_CRYPTOSUITE)),
+                EddsaJcs2022::new().get_id().clone(),
+            ),
+            (
+                new_proof(DATA_INTEGRITY_PROOF_TYPE, Some(ECDSA_JCS_2019_CRYPTOSUITE)),
+                EcdsaJcs2019::new().get_id().clone(),
+            ),
+        ] {
+            let suite = registry.get_suite_for_proof(&proof).unwrap();
+            assert!(*suite.get_id() == suite_id);
+        }
+
+        let proof = new_proof("MyEcdsaSecp256k1Signature", None);
+        let suite = registry.get_presentation_suite_for_proof(&proof).unwrap();
+        assert!(*suite.get_id() == *MyEcdsaSecp256k1Presentation::new().get_id());
+    }
+
+    #[test]
+    fn finds_suites_by_verification_method_type_and_key_type() {
+        let registry = CryptosuiteRegistry::default();
+        for (type_, public_key_multibase, suite_id) in [
+            (
+                MY_K256_VERIFICATION_METHOD_TYPE,
+                SECP256K1_PUBLIC_KEY,
+                MyEcdsaSecp256k1::new().get_id().clone(),
+            ),
+            (
+                ED25519_VERIFICATION_KEY_2020_TYPE,
+                ED25519_PUBLIC_KEY,
+                EddsaJcs2022::new().get_id().clone(),
+            ),
+            (
+                ECDSA_SECP256R1_VERIFICATION_KEY_2019_TYPE,
+                P256_PUBLIC_KEY,
+                EcdsaJcs2019::new().get_id().clone(),
+            ),
+            (
+                "Multikey",
+                SECP256K1_PUBLIC_KEY,
+                MyEcdsaSecp256k1::new().get_id().clone(),
+            ),
+            (
+                "Multikey",
+                ED25519_PUBLIC_KEY,
+                EddsaJcs2022::new().get_id().clone(),
+            ),
+            (
+                "Multikey",
+                P256_PUBLIC_KEY,
+                EcdsaJcs2019::new().get_id().clone(),
+            ),
+        ] {
+            let verification_method = new_verification_method(type_, public_key_multibase);
+            let suite = registry
+                .get_suite_for_verification_method(&verification_method)
+                .unwrap();
+            assert!(*suite.get_id() == suite_id);
+        }
+    }
+
+    #[test]
+    fn rejects_unknown_proof_types_and_cryptosuites() {
+        let registry = CryptosuiteRegistry::default();
+        for proof in [
+            new_proof("UnknownSignature", None),
+            new_proof(DATA_INTEGRITY_PROOF_TYPE, None),
+            new_proof(DATA_INTEGRITY_PROOF_TYPE, Some("unknown-cryptosuite")),
+            new_proof(
+                "MyEcdsaSecp256k1Signature",
+                Some(EDDSA_JCS_2022_CRYPTOSUITE),
+            ),
+        ] {
+            assert!(matches!(
+                registry.get_suite_for_proof(&proof),
+                Err(CryptosuiteRegistryError::UnknownProofType(_))
+            ));
+        }
+        let proof = new_proof(DATA_INTEGRITY_PROOF_TYPE, Some(EDDSA_JCS_2022_CRYPTOSUITE));
+        assert!(matches!(
+            registry.get_presentation_suite_for_proof(&proof),
+            Err(CryptosuiteRegistryError::UnknownProofType(_))
+        ));
+    }
+
+    #[test]
+    fn rejects_unsupported_verification_methods() {
+        let mut registry = CryptosuiteRegistry::new();
+        registry.register(
+            Box::new(EddsaJcs2022::new()),
+            KeyType::Ed25519,
+            &[ED25519_VERIFICATION_KEY_2020_TYPE],
+        );
+        for verification_method in [
+            new_verification_method("Multikey", P256_PUBLIC_KEY),
+            new_verification_method(ECDSA_SECP256R1_VERIFICATION_KEY_2019_TYPE, P256_PUBLIC_KEY),
+            new_verification_method("Multikey", "not a multikey"),
+        ] {
+            assert!(matches!(
+                registry.get_suite_for_verification_method(&verification_method),
+                Err(CryptosuiteRegistryError::UnsupportedVerificationMethod(_))
+            ));
+        }
+    }
+}
@@ -0,0 +1,295 @@
+use super::*;
+use k256::ecdsa::VerifyingKey;
+use multibase::{KeyType, MultibaseError};
+
+const DID_KEY_PREFIX: &str = "did:key:";
+const MULTIKEY_VERIFICATION_METHOD_TYPE: &str = "Multikey";
+
+#[derive(Debug)]
+pub enum DIDKeyError {
+    InvalidDID(String),
+    InvalidEncoding(String),
+    UnsupportedKeyType(String),
+    InvalidPublicKey(String),
+}
+
+impl Error for DIDKeyError {}
+
+impl fmt::Display for DIDKeyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidDID(e) => write!(f, "Invalid DID: {}", e),
+            Self::InvalidEncoding(e) => write!(f, "Invalid encoding: {}", e),
+            Self::UnsupportedKeyType(e) => write!(f, "Unsupported key type: {}", e),
+            Self::InvalidPublicKey(e) => write!(f, "Invalid public key: {}", e),
+        }
+    }
+}
+
+impl From<MultibaseError> for DIDKeyError {
+    fn from(e: MultibaseError) -> Self {
+        match e {
+            MultibaseError::UnsupportedKeyType(e) => Self::UnsupportedKeyType(e),
+            e => Self::InvalidEncoding(e.to_string()),
+        }
+    }
+}
+
+fn validate_public_key(key_type: KeyType, public_key: &[u8]) -> Result<(), DIDKeyError> {
+    let is_valid = match key_type {
+        KeyType::Secp256k1 => {
+            public_key.len() == 33 && VerifyingKey::from_sec1_bytes(public_key).is_ok()
+        }
+        KeyType::Ed25519 => public_key.len() == 32,
+        KeyType::P256 => {
+            public_key.len() == 33 && p256::PublicKey::from_sec1_bytes(public_key).is_ok()
+        }
+    };
+    if !is_valid {
+        return Err(DIDKeyError::InvalidPublicKey(format!(
+            "Not a compressed {:?} key.",
+            key_type
+        )));
+    }
+    Ok(())
+}
+
+pub fn did_from_secp256k1_public_key(public_key: &[u8]) -> Result<URL, DIDKeyError> {
+    let verifying_key = VerifyingKey::from_sec1_bytes(public_key)
+        .map_err(|_e| DIDKeyError::InvalidPublicKey("Not a secp256k1 key.".to_string()))?;
+    let did = format!(
+        "{}{}",
+        DID_KEY_PREFIX,
+        multibase::encode_public_key(
+            KeyType::Secp256k1,
+            verifying_key.to_encoded_point(true).as_bytes()
+        )
+    );
+
+    URL::new(&did).map_err(|e| DIDKeyError::InvalidDID(e.to_string()))
+}
+
+pub fn resolve(did: &URL) -> Result<DIDDocument, DIDKeyError> {
+    if did.did_method() != Some("key") {
+        return Err(DIDKeyError::InvalidDID(format!(
+            "{} is not a did:key.",
+            did
+        )));
+    }
+    let method_specific_id = did
+        .did_method_specific_id()
+        .expect("A did:key should have a method-specific id.");
+    let did = URL::new(&format!("{}{}", DID_KEY_PREFIX, method_specific_id))
+        .map_err(|e| DIDKeyError::InvalidDID(e.to_string()))?;
+    let (key_type, public_key) = multibase::decode_public_key(method_specific_id)?;
+    validate_public_key(key_type, &public_key)?;
+
+    let verification_method_id = URL::new(&format!("{}#{}", did, method_specific_id))
+        .map_err(|e| DIDKeyError::InvalidDID(e.to_string()))?;
+    let verification_method = VerificationMethod::new(
+        verification_method_id.clone(),
+        MULTIKEY_VERIFICATION_METHOD_TYPE.to_string(),
+        did.clone(),
+        method_specific_id.to_string(),
+    );
+    let context = vec![URL::new(DID_CONTEXT).expect("The DID context should be a valid URL.")];
+
+    Ok(DIDDocument::new(
+        context,
+        did,
+        vec![verification_method],
+        vec![verification_method_id.clone()],
+        vec![verification_method_id.clone()],
+        vec![],
+        vec![verification_method_id],
+    ))
+}
+
+pub fn resolve_verification_method(
+    verification_method_id: &URL,
+) -> Result<VerificationMethod, DIDKeyError> {
+    let did_document = resolve(verification_method_id)?;
+    let verification_method = did_document
+        .get_verification_methods()
+        .first()
+        .expect("A did:key document should have one verification method.");
+    if verification_method_id.fragment().is_some()
+        && verification_method.get_id() != verification_method_id
+    {
+        return Err(DIDKeyError::InvalidDID(format!(
+            "{} does not identify the key of {}.",
+            verification_method_id,
+            did_document.get_id()
+        )));
+    }
+
+    Ok(verification_method.clone())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Examples from the did:key specification and the vc-di-eddsa and vc-di-ecdsa test vectors.
+    const SECP256K1_DID: &str = "did:key:zQ3shokFTS3brHcDQrn82RUDfCZESWL1ZdCEJwekUDPQiYBme";
+    const ED25519_DID: &str = "did:key:z6MkiTBz1ymuepAQ4HEHYSF1H8quG5GLVVQR3djdX3mDooWp";
+    const P256_DID: &str = "did:key:zDnaerDaTF5BXEavCrfRZEk316dpbLsfPDZ3WJ5hRTPFU2169";
+    const ED25519_SECRET_KEY: &str = "z3u2en7t5LR2WtQH5PfFqMqwVHBeXouLzo6haApm8XHqvjxq";
+    const ED25519_SECRET_KEY_DID: &str = "did:key:z6MkrJVnaZkeFzdQyMZu1cgjg7k1pZZ6pvBQ7XJPt4swbTQ2";
+    const P256_SECRET_KEY: &str = "z42twTcNeSYcnqg1FLuSFs2bsGH3ZqbRHFmvS9XMsYhjxvHN";
+    const P256_SECRET_KEY_DID: &str = "did:key:zDnaepBuvsQ8cpsWrVKw8fbpGpvPeNSjVPTWoq6cRqaYzBKVP";
+
+    fn did_from_public_key(key_type: KeyType, public_key: &[u8]) -> String {
+        format!(
+            "{}{}",
+            DID_KEY_PREFIX,
+            multibase::encode_public_key(key_type, public_key)
+        )
+    }
+
+    #[test]
+    fn resolves_specification_dids() {
+        for (did, key_type) in [
+            (SECP256K1_DID, KeyType::Secp256k1),
+            (ED25519_DID, KeyType::Ed25519),
+            (P256_DID, KeyType::P256),
+        ] {
+            let did = URL::new(did).unwrap();
+            let did_document = resolve(&did).unwrap();
+            assert!(*did_document.get_id() == did);
+            let verification_method = &did_document.get_verification_methods()[0];
+            let method_specific_id = did.did_method_specific_id().unwrap();
+            assert_eq!(
+                verification_method.get_id().get_str(),
+                format!("{}#{}", did, method_specific_id)
+            );
+            assert!(*verification_method.get_controller_id() == did);
+            let (resolved_key_type, public_key) = verification_method.get_public_key().unwrap();
+            assert_eq!(resolved_key_type, key_type);
+            assert_eq!(did_from_public_key(key_type, &public_key), did.get_str());
+            for relationship in [
+                VerificationRelationship::Authentication,
+                VerificationRelationship::AssertionMethod,
+                VerificationRelationship::CapabilityInvocation,
+            ] {
+                assert!(did_document.is_authorized(verification_method.get_id(), relationship));
+            }
+
+            let resolved = resolve_verification_method(verification_method.get_id()).unwrap();
+            assert!(resolved == *verification_method);
+            let other_key = URL::new(&format!("{}#key-1", did)).unwrap();
+            assert!(matches!(
+                resolve_verification_method(&other_key),
+                Err(DIDKeyError::InvalidDID(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn derives_dids_from_public_keys() {
+        let (_key_type, public_key) = multibase::decode_public_key(
+            URL::new(SECP256K1_DID)
+                .unwrap()
+                .did_method_specific_id()
+                .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(
+            did_from_secp256k1_public_key(&public_key)
+                .unwrap()
+                .get_str(),
+            SECP256K1_DID
+        );
+        // Uncompressed keys are compressed before they are encoded.
+        let uncompressed_key = VerifyingKey::from_sec1_bytes(&public_key)
+            .unwrap()
+            .to_encoded_point(false);
+        assert_eq!(
+            did_from_secp256k1_public_key(uncompressed_key.as_bytes())
+                .unwrap()
+                .get_str(),
+            SECP256K1_DID
+        );
+        assert!(matches!(
+            did_from_secp256k1_public_key(&[2u8; 32]),
+            Err(DIDKeyError::InvalidPublicKey(_))
+        ));
+
+        // Secret keys are prefixed with their ed25519-priv and p256-priv multicodecs.
+        let secret_key = multibase::decode(ED25519_SECRET_KEY).unwrap();
+        let signing_key =
+            ed25519_dalek::SigningKey::from_bytes(&secret_key[2..].try_into().unwrap());
+        assert_eq!(
+            did_from_public_key(KeyType::Ed25519, signing_key.verifying_key().as_bytes()),
+            ED25519_SECRET_KEY_DID
+        );
+        let secret_key = multibase::decode(P256_SECRET_KEY).unwrap();
+        let signing_key = p256::ecdsa::SigningKey::from_slice(&secret_key[2..]).unwrap();
+        assert_eq!(
+            did_from_public_key(
+                KeyType::P256,
+                signing_key
+                    .verifying_key()
+                    .to_encoded_point(true)
+                    .as_bytes()
+            ),
+            P256_SECRET_KEY_DID
+        );
+        assert!(resolve(&URL::new(ED25519_SECRET_KEY_DID).unwrap()).is_ok());
+        assert!(resolve(&URL::new(P256_SECRET_KEY_DID).unwrap()).is_ok());
+    }
+
+    #[test]
+    fn rejects_invalid_dids() {
+        assert!(matches!(
+            resolve(&URL::new("did:web:example.com").unwrap()),
+            Err(DIDKeyError::InvalidDID(_))
+        ));
+        // Not base58btc: 0, O, I and l are outside its alphabet.
+        assert!(matches!(
+            resolve(&URL::new("did:key:z6Mk0OIl").unwrap()),
+            Err(DIDKeyError::InvalidEncoding(_))
+        ));
+        // Only base58btc is allowed for did:key.
+        let base64url_did = format!(
+            "did:key:{}",
+            multibase::encode_base64url(&[0xed, 0x01, 1, 2, 3])
+        );
+        assert!(matches!(
+            resolve(&URL::new(&base64url_did).unwrap()),
+            Err(DIDKeyError::InvalidEncoding(_))
+        ));
+        // The x25519-pub multicodec is not a signing key type.
+        let x25519_did = format!(
+            "did:key:{}",
+            multibase::encode(&[[0xec, 0x01].as_slice(), &[1u8; 32]].concat())
+        );
+        assert!(matches!(
+            resolve(&URL::new(&x25519_did).unwrap()),
+            Err(DIDKeyError::UnsupportedKeyType(_))
+        ));
+    }
+
+    #[test]
+    fn rejects_invalid_public_keys() {
+        let mut off_curve_key = vec![0x02];
+        // x = 7 is not the x coordinate of a point on either curve.
+        off_curve_key.extend([0u8; 31]);
+        off_curve_key.push(7);
+        for (key_type, public_key) in [
+            (KeyType::Secp256k1, off_curve_key.clone()),
+            (KeyType::P256, off_curve_key.clone()),
+            (KeyType::P256, [[0x02].as_slice(), &[0xff; 32]].concat()),
+            (KeyType::P256, [[0x05].as_slice(), &[1; 32]].concat()),
+            (KeyType::P256, [0x02; 32].to_vec()),
+            (KeyType::Ed25519, [1u8; 33].to_vec()),
+        ] {
+            let did = URL::new(&did_from_public_key(key_type, &public_key)).unwrap();
+            assert!(
+                matches!(resolve(&did), Err(DIDKeyError::InvalidPublicKey(_))),
+                "{}",
+                did
+            );
+        }
+    }
+}
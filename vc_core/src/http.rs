@@ -0,0 +1,108 @@
+use std::io;
+use std::net::{IpAddr, SocketAddr, ToSocketAddrs};
+use std::time::Duration;
+
+pub const CONNECT_TIMEOUT: Duration = Duration::from_secs(5);
+pub const READ_TIMEOUT: Duration = Duration::from_secs(10);
+
+// Builds an agent for fetching documents from hosts named in credentials and DIDs. Those hosts
+// are chosen by whoever wrote the credential, so unless private hosts are allowed, every address
+// a host resolves to (including on redirects) must be publicly routable.
+pub fn new_agent(allow_private_hosts: bool) -> ureq::Agent {
+    let builder = ureq::AgentBuilder::new()
+        .timeout_connect(CONNECT_TIMEOUT)
+        .timeout_read(READ_TIMEOUT);
+    if allow_private_hosts {
+        return builder.build();
+    }
+    builder
+        .resolver(|netloc: &str| -> io::Result<Vec<SocketAddr>> {
+            let addresses: Vec<SocketAddr> = netloc.to_socket_addrs()?.collect();
+            if let Some(address) = addresses
+                .iter()
+                .find(|address| !is_public_address(&address.ip()))
+            {
+                return Err(io::Error::new(
+                    io::ErrorKind::PermissionDenied,
+                    format!("{} resolves to non-public address {}", netloc, address.ip()),
+                ));
+            }
+            Ok(addresses)
+        })
+        .build()
+}
+
+pub fn is_public_address(address: &IpAddr) -> bool {
+    match address {
+        IpAddr::V4(address) => {
+            let octets = address.octets();
+            !(address.is_unspecified()
+                || address.is_loopback()
+                || address.is_private()
+                || address.is_link_local()
+                || address.is_broadcast()
+                || address.is_documentation()
+                || address.is_multicast()
+                || octets[0] == 0
+                // Shared address space, 100.64.0.0/10.
+                || (octets[0] == 100 && octets[1] & 0xc0 == 64))
+        }
+        IpAddr::V6(address) => {
+            if let Some(address) = address.to_ipv4_mapped() {
+                return is_public_address(&IpAddr::V4(address));
+            }
+            let first_segment = address.segments()[0];
+            !(address.is_unspecified()
+                || address.is_loopback()
+                || address.is_multicast()
+                // Unique local, fc00::/7, and link-local, fe80::/10, addresses.
+                || first_segment & 0xfe00 == 0xfc00
+                || first_segment & 0xffc0 == 0xfe80)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::thread;
+    use tiny_http::{Response, Server};
+
+    #[test]
+    fn classifies_addresses() {
+        for address in ["93.184.215.14", "2606:2800:21f:cb07:6820:80da:af6b:8b2c"] {
+            assert!(is_public_address(&address.parse().unwrap()), "{}", address);
+        }
+        for address in [
+            "127.0.0.1",
+            "10.1.2.3",
+            "172.16.0.1",
+            "192.168.1.1",
+            "169.254.169.254",
+            "100.64.0.1",
+            "0.0.0.0",
+            "::1",
+            "::",
+            "fd00::1",
+            "fe80::1",
+            "::ffff:127.0.0.1",
+        ] {
+            assert!(!is_public_address(&address.parse().unwrap()), "{}", address);
+        }
+    }
+
+    #[test]
+    fn rejects_private_hosts_unless_allowed() {
+        let server = Server::http("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/", server.server_addr().to_ip().unwrap());
+        let handle = thread::spawn(move || {
+            let request = server.recv().unwrap();
+            request.respond(Response::from_string("ok")).unwrap();
+        });
+
+        assert!(new_agent(false).get(&url).call().is_err());
+        let response = new_agent(true).get(&url).call().unwrap();
+        assert_eq!(response.into_string().unwrap(), "ok");
+        handle.join().unwrap();
+    }
+}
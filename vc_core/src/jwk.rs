@@ -0,0 +1,279 @@
+use super::*;
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine as _};
+use k256::elliptic_curve::sec1::ToEncodedPoint;
+use multibase::KeyType;
+
+pub const JSON_WEB_KEY_2020_TYPE: &str = "JsonWebKey2020";
+
+#[derive(Debug)]
+pub enum JWKError {
+    UnsupportedKeyType(String),
+    InvalidEncoding(String),
+    InvalidPublicKey(String),
+    InvalidPrivateKey(String),
+}
+
+impl Error for JWKError {}
+
+impl fmt::Display for JWKError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnsupportedKeyType(e) => write!(f, "Unsupported key type: {}", e),
+            Self::InvalidEncoding(e) => write!(f, "Invalid encoding: {}", e),
+            Self::InvalidPublicKey(e) => write!(f, "Invalid public key: {}", e),
+            Self::InvalidPrivateKey(e) => write!(f, "Invalid private key: {}", e),
+        }
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize, PartialEq)]
+pub struct JWK {
+    kty: String,
+    crv: String,
+    x: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    y: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    d: Option<String>,
+}
+
+fn decode_member(name: &str, value: &str) -> Result<Vec<u8>, JWKError> {
+    URL_SAFE_NO_PAD
+        .decode(value)
+        .map_err(|e| JWKError::InvalidEncoding(format!("{}: {}", name, e)))
+}
+
+// Returns the uncompressed SEC1 point for an EC key, or the raw key for an OKP key.
+fn uncompressed_public_key(key_type: KeyType, public_key: &[u8]) -> Result<Vec<u8>, JWKError> {
+    let invalid = |_e| JWKError::InvalidPublicKey(format!("Not a valid {:?} key.", key_type));
+    match key_type {
+        KeyType::Secp256k1 => Ok(k256::PublicKey::from_sec1_bytes(public_key)
+            .map_err(invalid)?
+            .to_encoded_point(false)
+            .as_bytes()
+            .to_vec()),
+        KeyType::P256 => Ok(p256::PublicKey::from_sec1_bytes(public_key)
+            .map_err(invalid)?
+            .to_encoded_point(false)
+            .as_bytes()
+            .to_vec()),
+        KeyType::Ed25519 => {
+            if public_key.len() != 32 {
+                return Err(JWKError::InvalidPublicKey(
+                    "Ed25519 keys must be 32 bytes.".to_string(),
+                ));
+            }
+            Ok(public_key.to_vec())
+        }
+    }
+}
+
+fn compressed_public_key(key_type: KeyType, public_key: &[u8]) -> Result<Vec<u8>, JWKError> {
+    let invalid = |_e| JWKError::InvalidPublicKey(format!("Not a valid {:?} key.", key_type));
+    match key_type {
+        KeyType::Secp256k1 => Ok(k256::PublicKey::from_sec1_bytes(public_key)
+            .map_err(invalid)?
+            .to_encoded_point(true)
+            .as_bytes()
+            .to_vec()),
+        KeyType::P256 => Ok(p256::PublicKey::from_sec1_bytes(public_key)
+            .map_err(invalid)?
+            .to_encoded_point(true)
+            .as_bytes()
+            .to_vec()),
+        KeyType::Ed25519 => uncompressed_public_key(key_type, public_key),
+    }
+}
+
+// Ed25519 private keys are the 32 byte seed the key pair is derived from.
+fn ed25519_public_key(private_key: &[u8]) -> Result<Vec<u8>, JWKError> {
+    let seed: [u8; 32] = private_key.try_into().map_err(|_e| {
+        JWKError::InvalidPrivateKey("Ed25519 private keys must be 32 bytes.".to_string())
+    })?;
+    Ok(ed25519_dalek::SigningKey::from_bytes(&seed)
+        .verifying_key()
+        .to_bytes()
+        .to_vec())
+}
+
+impl JWK {
+    pub fn from_public_key(key_type: KeyType, public_key: &[u8]) -> Result<Self, JWKError> {
+        let public_key = uncompressed_public_key(key_type, public_key)?;
+        let (kty, crv, x, y) = match key_type {
+            KeyType::Secp256k1 | KeyType::P256 => {
+                let crv = if key_type == KeyType::Secp256k1 {
+                    "secp256k1"
+                } else {
+                    "P-256"
+                };
+                // Skip the 0x04 tag of the uncompressed point, then split into coordinates.
+                let (x, y) = public_key[1..].split_at(32);
+                ("EC", crv, x, Some(y))
+            }
+            KeyType::Ed25519 => ("OKP", "Ed25519", public_key.as_slice(), None),
+        };
+        Ok(Self {
+            kty: kty.to_string(),
+            crv: crv.to_string(),
+            x: URL_SAFE_NO_PAD.encode(x),
+            y: y.map(|y| URL_SAFE_NO_PAD.encode(y)),
+            d: None,
+        })
+    }
+
+    pub fn from_private_key(key_type: KeyType, private_key: &[u8]) -> Result<Self, JWKError> {
+        let invalid = |_e| JWKError::InvalidPrivateKey(format!("Not a valid {:?} key.", key_type));
+        let public_key = match key_type {
+            KeyType::Secp256k1 => k256::SecretKey::from_slice(private_key)
+                .map_err(invalid)?
+                .public_key()
+                .to_encoded_point(true)
+                .as_bytes()
+                .to_vec(),
+            KeyType::P256 => p256::SecretKey::from_slice(private_key)
+                .map_err(invalid)?
+                .public_key()
+                .to_encoded_point(true)
+                .as_bytes()
+                .to_vec(),
+            KeyType::Ed25519 => ed25519_public_key(private_key)?,
+        };
+        Ok(Self {
+            d: Some(URL_SAFE_NO_PAD.encode(private_key)),
+            ..Self::from_public_key(key_type, &public_key)?
+        })
+    }
+
+    pub fn get_kty(&self) -> &String {
+        &self.kty
+    }
+
+    pub fn get_crv(&self) -> &String {
+        &self.crv
+    }
+
+    pub fn get_key_type(&self) -> Result<KeyType, JWKError> {
+        match (self.kty.as_str(), self.crv.as_str()) {
+            ("EC", "secp256k1") => Ok(KeyType::Secp256k1),
+            ("EC", "P-256") => Ok(KeyType::P256),
+            ("OKP", "Ed25519") => Ok(KeyType::Ed25519),
+            (kty, crv) => Err(JWKError::UnsupportedKeyType(format!("{} {}", kty, crv))),
+        }
+    }
+
+    pub fn is_private(&self) -> bool {
+        self.d.is_some()
+    }
+
+    pub fn to_public(&self) -> Self {
+        Self {
+            d: None,
+            ..self.clone()
+        }
+    }
+
+    // Returns the public key in the same form as a decoded Multikey: compressed SEC1 for EC keys.
+    pub fn get_public_key(&self) -> Result<(KeyType, Vec<u8>), JWKError> {
+        let key_type = self.get_key_type()?;
+        let x = decode_member("x", &self.x)?;
+        let public_key = match key_type {
+            KeyType::Secp256k1 | KeyType::P256 => {
+                let y = self.y.as_ref().ok_or_else(|| {
+                    JWKError::InvalidPublicKey("EC keys must have a y coordinate.".to_string())
+                })?;
+                let y = decode_member("y", y)?;
+                if x.len() != 32 || y.len() != 32 {
+                    return Err(JWKError::InvalidPublicKey(
+                        "EC coordinates must be 32 bytes.".to_string(),
+                    ));
+                }
+                let mut uncompressed = vec![0x04];
+                uncompressed.extend_from_slice(&x);
+                uncompressed.extend_from_slice(&y);
+                compressed_public_key(key_type, &uncompressed)?
+            }
+            KeyType::Ed25519 => compressed_public_key(key_type, &x)?,
+        };
+        Ok((key_type, public_key))
+    }
+
+    // Returns the private key bytes, checking that they match the public key.
+    pub fn get_private_key(&self) -> Result<(KeyType, Vec<u8>), JWKError> {
+        let (key_type, public_key) = self.get_public_key()?;
+        let d = self
+            .d
+            .as_ref()
+            .ok_or_else(|| JWKError::InvalidPrivateKey("Missing d parameter.".to_string()))?;
+        let private_key = decode_member("d", d)?;
+        let invalid = |_e| JWKError::InvalidPrivateKey(format!("Not a valid {:?} key.", key_type));
+        let derived_public_key = match key_type {
+            KeyType::Secp256k1 => k256::SecretKey::from_slice(&private_key)
+                .map_err(invalid)?
+                .public_key()
+                .to_encoded_point(true)
+                .as_bytes()
+                .to_vec(),
+            KeyType::P256 => p256::SecretKey::from_slice(&private_key)
+                .map_err(invalid)?
+                .public_key()
+                .to_encoded_point(true)
+                .as_bytes()
+                .to_vec(),
+            KeyType::Ed25519 => ed25519_public_key(&private_key)?,
+        };
+        if derived_public_key != public_key {
+            return Err(JWKError::InvalidPrivateKey(
+                "Private key does not match public key.".to_string(),
+            ));
+        }
+        Ok((key_type, private_key))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // The Ed25519 private key of RFC 8037, appendix A.1.
+    const RFC_8037_PRIVATE_JWK: &str = r#"{
+        "kty": "OKP",
+        "crv": "Ed25519",
+        "d": "nWGxne_9WmC6hEr0kuwsxERJxWl7MmkZcDusAxyuf2A",
+        "x": "11qYAYKxCrfVS_7TyWQHOg7hcvPapiMlrwIaaPcHURo"
+    }"#;
+
+    #[test]
+    fn imports_and_exports_ed25519_private_keys() {
+        let jwk: JWK = serde_json::from_str(RFC_8037_PRIVATE_JWK).unwrap();
+        let (key_type, private_key) = jwk.get_private_key().unwrap();
+        assert_eq!(key_type, KeyType::Ed25519);
+        assert_eq!(
+            private_key,
+            decode_member("d", "nWGxne_9WmC6hEr0kuwsxERJxWl7MmkZcDusAxyuf2A").unwrap()
+        );
+        assert!(JWK::from_private_key(key_type, &private_key).unwrap() == jwk);
+    }
+
+    #[test]
+    fn rejects_mismatched_ed25519_private_keys() {
+        let mut jwk: JWK = serde_json::from_str(RFC_8037_PRIVATE_JWK).unwrap();
+        jwk.d = Some(URL_SAFE_NO_PAD.encode([7u8; 32]));
+        assert!(matches!(
+            jwk.get_private_key(),
+            Err(JWKError::InvalidPrivateKey(_))
+        ));
+        jwk.d = Some(URL_SAFE_NO_PAD.encode([7u8; 31]));
+        assert!(matches!(
+            jwk.get_private_key(),
+            Err(JWKError::InvalidPrivateKey(_))
+        ));
+    }
+
+    #[test]
+    fn round_trips_ec_private_keys() {
+        for key_type in [KeyType::Secp256k1, KeyType::P256] {
+            let jwk = JWK::from_private_key(key_type, &[1u8; 32]).unwrap();
+            assert_eq!(jwk.get_private_key().unwrap(), (key_type, vec![1u8; 32]));
+        }
+    }
+}
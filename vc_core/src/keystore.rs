@@ -0,0 +1,190 @@
+use super::*;
+use proof::{InMemoryKeyHandle, KeyHandle, ProofGenerationError};
+use std::fs::{self, DirBuilder, OpenOptions};
+use std::io::{ErrorKind, Write};
+#[cfg(unix)]
+use std::os::unix::fs::{DirBuilderExt, OpenOptionsExt, PermissionsExt};
+use std::path::PathBuf;
+
+#[derive(Debug)]
+pub enum KeyStoreError {
+    NotFound(String),
+    StorageError(String),
+    InvalidKeyError(String),
+}
+
+impl Error for KeyStoreError {}
+
+impl fmt::Display for KeyStoreError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NotFound(e) => write!(f, "Signing key not found: {}", e),
+            Self::StorageError(e) => write!(f, "Key storage error: {}", e),
+            Self::InvalidKeyError(e) => write!(f, "Invalid signing key: {}", e),
+        }
+    }
+}
+
+// Holds issuer private keys by verification method id and hands out handles that sign with them.
+// The key type is supplied by the caller, since it is known from the verification method.
+pub trait KeyStore {
+    fn store_key(&self, key_id: &URL, secret_key: &[u8]) -> Result<(), KeyStoreError>;
+
+    fn get_key_handle(
+        &self,
+        key_id: &URL,
+        key_type: KeyType,
+    ) -> Result<Box<dyn KeyHandle>, KeyStoreError>;
+
+    fn delete_key(&self, key_id: &URL) -> Result<(), KeyStoreError>;
+}
+
+// Holds opaque key material by key id. Key stores that protect keys at rest, for example by
+// encrypting them, are layered over a storage backend.
+pub trait KeyStorage {
+    fn put_key_data(&self, key_id: &URL, data: &[u8]) -> Result<(), KeyStoreError>;
+
+    fn get_key_data(&self, key_id: &URL) -> Result<Vec<u8>, KeyStoreError>;
+
+    fn delete_key_data(&self, key_id: &URL) -> Result<(), KeyStoreError>;
+}
+
+// Keeps each private key in its own file, named after the digest of its key id. On unix the
+// directory and key files are only accessible to the owner.
+#[derive(Clone)]
+pub struct FileKeyStore {
+    directory: PathBuf,
+}
+
+impl FileKeyStore {
+    pub fn new(directory: PathBuf) -> Result<Self, KeyStoreError> {
+        let mut dir_builder = DirBuilder::new();
+        dir_builder.recursive(true);
+        #[cfg(unix)]
+        dir_builder.mode(0o700);
+        dir_builder.create(&directory).map_err(|e| {
+            KeyStoreError::StorageError(format!("could not create {}: {}", directory.display(), e))
+        })?;
+        Ok(Self { directory })
+    }
+
+    pub fn get_directory(&self) -> &PathBuf {
+        &self.directory
+    }
+
+    fn get_key_path(&self, key_id: &URL) -> PathBuf {
+        self.directory
+            .join(multibase::encode(&Sha256::digest(key_id.get_str())))
+    }
+}
+
+impl KeyStorage for FileKeyStore {
+    fn put_key_data(&self, key_id: &URL, data: &[u8]) -> Result<(), KeyStoreError> {
+        let mut open_options = OpenOptions::new();
+        open_options.write(true).create(true).truncate(true);
+        #[cfg(unix)]
+        open_options.mode(0o600);
+        let mut file = open_options.open(self.get_key_path(key_id)).map_err(|e| {
+            KeyStoreError::StorageError(format!("could not create key {}: {}", key_id, e))
+        })?;
+        // The mode only applies to new files, so tighten a key file that already existed too.
+        #[cfg(unix)]
+        file.set_permissions(fs::Permissions::from_mode(0o600))
+            .map_err(|e| {
+                KeyStoreError::StorageError(format!("could not restrict key {}: {}", key_id, e))
+            })?;
+        file.write_all(data).map_err(|e| {
+            KeyStoreError::StorageError(format!("could not write key {}: {}", key_id, e))
+        })
+    }
+
+    fn get_key_data(&self, key_id: &URL) -> Result<Vec<u8>, KeyStoreError> {
+        fs::read(self.get_key_path(key_id)).map_err(|e| match e.kind() {
+            ErrorKind::NotFound => KeyStoreError::NotFound(key_id.to_string()),
+            _ => KeyStoreError::StorageError(format!("could not read key {}: {}", key_id, e)),
+        })
+    }
+
+    fn delete_key_data(&self, key_id: &URL) -> Result<(), KeyStoreError> {
+        match fs::remove_file(self.get_key_path(key_id)) {
+            Err(e) if e.kind() != ErrorKind::NotFound => Err(KeyStoreError::StorageError(format!(
+                "could not delete key {}: {}",
+                key_id, e
+            ))),
+            _ => Ok(()),
+        }
+    }
+}
+
+impl KeyStore for FileKeyStore {
+    fn store_key(&self, key_id: &URL, secret_key: &[u8]) -> Result<(), KeyStoreError> {
+        self.put_key_data(key_id, secret_key)
+    }
+
+    fn get_key_handle(
+        &self,
+        key_id: &URL,
+        key_type: KeyType,
+    ) -> Result<Box<dyn KeyHandle>, KeyStoreError> {
+        let path = self.get_key_path(key_id);
+        if !path.is_file() {
+            return Err(KeyStoreError::NotFound(key_id.to_string()));
+        }
+        Ok(Box::new(FileKeyHandle { path, key_type }))
+    }
+
+    fn delete_key(&self, key_id: &URL) -> Result<(), KeyStoreError> {
+        self.delete_key_data(key_id)
+    }
+}
+
+// Reads its key from disk only for the duration of each signature.
+pub struct FileKeyHandle {
+    path: PathBuf,
+    key_type: KeyType,
+}
+
+impl KeyHandle for FileKeyHandle {
+    fn get_key_type(&self) -> KeyType {
+        self.key_type
+    }
+
+    fn sign(&self, data: &[u8]) -> Result<Vec<u8>, ProofGenerationError> {
+        let secret_key = fs::read(&self.path).map_err(|e| {
+            ProofGenerationError::SigningError(format!(
+                "could not read key {}: {}",
+                self.path.display(),
+                e
+            ))
+        })?;
+        InMemoryKeyHandle::new(self.key_type, &secret_key)?.sign(data)
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keeps_keys_private_to_the_owner() {
+        let directory = std::env::temp_dir()
+            .join(format!("vc_core-keystore-{}", std::process::id()))
+            .join("keys");
+        let _ = fs::remove_dir_all(directory.parent().unwrap());
+        let keystore = FileKeyStore::new(directory.clone()).unwrap();
+        let key_id = URL::new("did:example:issuer#key-1").unwrap();
+        keystore.store_key(&key_id, &[1u8; 32]).unwrap();
+
+        let mode = |path: &PathBuf| fs::metadata(path).unwrap().permissions().mode() & 0o777;
+        assert_eq!(mode(&directory), 0o700);
+        let key_path = keystore.get_key_path(&key_id);
+        assert_eq!(mode(&key_path), 0o600);
+
+        fs::set_permissions(&key_path, fs::Permissions::from_mode(0o644)).unwrap();
+        keystore.store_key(&key_id, &[2u8; 32]).unwrap();
+        assert_eq!(mode(&key_path), 0o600);
+        assert_eq!(fs::read(&key_path).unwrap(), [2u8; 32]);
+
+        fs::remove_dir_all(directory.parent().unwrap()).unwrap();
+    }
+}
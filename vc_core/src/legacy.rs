@@ -0,0 +1,178 @@
+use super::*;
+use chrono::serde::ts_seconds;
+use k256::ecdsa::VerifyingKey;
+use multibase::MultibaseError;
+
+#[derive(Deserialize)]
+enum LegacyClaimPropertyValue {
+    Text(String),
+    Number(i32),
+    Boolean(bool),
+}
+
+impl From<LegacyClaimPropertyValue> for ClaimPropertyValue {
+    fn from(value: LegacyClaimPropertyValue) -> Self {
+        match value {
+            LegacyClaimPropertyValue::Text(text) => Self::Text(text),
+            LegacyClaimPropertyValue::Number(number) => Self::Number(number.into()),
+            LegacyClaimPropertyValue::Boolean(boolean) => Self::Boolean(boolean),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+enum LegacyClaimProperty {
+    Value(LegacyClaimPropertyValue),
+    Array(Vec<LegacyClaimProperty>),
+    Map(HashMap<String, LegacyClaimProperty>),
+}
+
+impl From<LegacyClaimProperty> for ClaimProperty {
+    fn from(property: LegacyClaimProperty) -> Self {
+        match property {
+            LegacyClaimProperty::Value(value) => Self::Value(value.into()),
+            LegacyClaimProperty::Array(array) => {
+                Self::Array(array.into_iter().map(Self::from).collect())
+            }
+            LegacyClaimProperty::Map(map) => Self::Map(
+                map.into_iter()
+                    .map(|(key, value)| (key, Self::from(value)))
+                    .collect(),
+            ),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct LegacyCredentialSchemaLink {
+    id: URL,
+    type_: String,
+}
+
+#[derive(Deserialize)]
+struct LegacyProof {
+    type_: String,
+    created: DateTime<Utc>,
+    verification_method: URL,
+    proof_purpose: String,
+    proof_value: Vec<u8>,
+}
+
+#[derive(Deserialize)]
+struct LegacyCredential {
+    context: Vec<URL>,
+    id: URL,
+    type_: Vec<String>,
+    issuer: URL,
+    #[serde(with = "ts_seconds")]
+    valid_from: DateTime<Utc>,
+    #[serde(with = "ts_seconds")]
+    valid_until: DateTime<Utc>,
+    credential_subject: HashMap<String, LegacyClaimProperty>,
+    credential_schema: Vec<LegacyCredentialSchemaLink>,
+}
+
+#[derive(Deserialize)]
+pub(crate) struct LegacyVerifiableCredential {
+    credential: LegacyCredential,
+    proof: Vec<LegacyProof>,
+}
+
+impl From<LegacyVerifiableCredential> for VerifiableCredential {
+    fn from(legacy: LegacyVerifiableCredential) -> Self {
+        let credential = legacy.credential;
+        let credential = Credential::new(
+            credential.context,
+            credential.id,
+            credential.type_,
+            credential.issuer,
+            credential.valid_from,
+            credential.valid_until,
+            credential
+                .credential_subject
+                .into_iter()
+                .map(|(key, value)| (key, value.into()))
+                .collect(),
+            credential
+                .credential_schema
+                .into_iter()
+                .map(|link| CredentialSchemaLink::new(link.id, link.type_))
+                .collect(),
+        );
+        let proof = legacy
+            .proof
+            .into_iter()
+            .map(|proof| {
+                Proof::new(
+                    proof.type_,
+                    None,
+                    proof.created,
+                    proof.verification_method,
+                    proof.proof_purpose,
+                    None,
+                    None,
+                    proof.proof_value,
+                )
+            })
+            .collect();
+        Self::new(credential, proof)
+    }
+}
+
+#[derive(Deserialize)]
+struct LegacyVerificationMethod {
+    id: URL,
+    type_: String,
+    controller_id: URL,
+    public_key_multibase: Vec<u8>,
+}
+
+impl TryFrom<LegacyVerificationMethod> for VerificationMethod {
+    type Error = MultibaseError;
+
+    // Keys were only ever generated as raw SEC1 secp256k1 keys.
+    fn try_from(legacy: LegacyVerificationMethod) -> Result<Self, Self::Error> {
+        let verifying_key =
+            VerifyingKey::from_sec1_bytes(&legacy.public_key_multibase).map_err(|_e| {
+                MultibaseError::UnsupportedKeyType(format!(
+                    "Verification method {} does not hold a secp256k1 key.",
+                    legacy.id
+                ))
+            })?;
+        let public_key_multibase = multibase::encode_public_key(
+            KeyType::Secp256k1,
+            verifying_key.to_encoded_point(true).as_bytes(),
+        );
+        Ok(Self::new(
+            legacy.id,
+            legacy.type_,
+            legacy.controller_id,
+            public_key_multibase,
+        ))
+    }
+}
+
+#[derive(Deserialize)]
+pub(crate) struct LegacyIssuer {
+    id: URL,
+    name: String,
+    verification_methods: Vec<LegacyVerificationMethod>,
+}
+
+impl TryFrom<LegacyIssuer> for Issuer {
+    type Error = Box<dyn Error>;
+
+    fn try_from(legacy: LegacyIssuer) -> Result<Self, Self::Error> {
+        let mut issuer = Self::new(legacy.id, legacy.name);
+        for verification_method in legacy.verification_methods {
+            issuer.new_verification_method(
+                verification_method.try_into()?,
+                &[
+                    VerificationRelationship::Authentication,
+                    VerificationRelationship::AssertionMethod,
+                ],
+            )?;
+        }
+        Ok(issuer)
+    }
+}
@@ -1,27 +1,126 @@
-use chrono::{serde::ts_seconds, DateTime, Utc};
-use serde::{Deserialize, Serialize};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use sha2::{Digest, Sha256};
 use std::collections::HashMap;
 use std::error::Error;
 use std::fmt;
 
+pub mod cryptosuite;
+pub mod did_key;
+#[cfg(feature = "did_web")]
+pub mod http;
+pub mod jwk;
+pub mod keystore;
+mod legacy;
+pub mod multibase;
 pub mod proof;
-
+pub mod resolver;
+pub mod status_list;
+mod url;
+
+use jwk::JWK;
+use multibase::KeyType;
+pub use url::{URLParseError, URL};
+
+pub const DID_CONTEXT: &str = "https://www.w3.org/ns/did/v1";
+pub const CREDENTIALS_CONTEXT: &str = "https://www.w3.org/ns/credentials/v2";
+pub const REFRESH_SERVICE_TYPE: &str = "VerifiableCredentialRefreshService2021";
+pub const ALL_VERIFIERS_ASSIGNEE: &str = "AllVerifiers";
+pub const AUTHENTICATION_PROOF_PURPOSE: &str = "authentication";
+pub const ASSERTION_METHOD_PROOF_PURPOSE: &str = "assertionMethod";
+pub const KEY_AGREEMENT_PROOF_PURPOSE: &str = "keyAgreement";
+pub const CAPABILITY_INVOCATION_PROOF_PURPOSE: &str = "capabilityInvocation";
+pub const DATA_INTEGRITY_PROOF_TYPE: &str = "DataIntegrityProof";
+pub const ED25519_VERIFICATION_KEY_2020_TYPE: &str = "Ed25519VerificationKey2020";
+pub const ECDSA_SECP256R1_VERIFICATION_KEY_2019_TYPE: &str = "EcdsaSecp256r1VerificationKey2019";
+pub const DOCUMENT_VERIFICATION_EVIDENCE_TYPE: &str = "DocumentVerification";
+const SHA2_256_MULTIHASH: [u8; 2] = [0x12, 0x20];
+
+// A property that may hold a single value or an array. Keeping the form it was read in lets
+// documents from other implementations round-trip unchanged.
 #[derive(Clone, Serialize, Deserialize, PartialEq)]
-pub struct URL(String);
+#[serde(untagged)]
+pub enum OneOrMany<T> {
+    Many(Vec<T>),
+    One(T),
+}
+
+impl<T> OneOrMany<T> {
+    pub fn as_slice(&self) -> &[T] {
+        match self {
+            Self::Many(values) => values,
+            Self::One(value) => std::slice::from_ref(value),
+        }
+    }
+}
 
-impl URL {
-    pub fn new(url: &str) -> Result<Self, Box<dyn Error>> {
-        Ok(Self(url.to_string()))
+fn non_empty_one_or_many<'de, D, T>(deserializer: D) -> Result<OneOrMany<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    let values = OneOrMany::deserialize(deserializer)?;
+    if values.as_slice().is_empty() {
+        return Err(serde::de::Error::custom("expected at least one value"));
     }
+    Ok(values)
+}
 
-    pub fn get_str(&self) -> &str {
-        &self.0
+fn one_or_many<'de, D, T>(deserializer: D) -> Result<Vec<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    match OneOrMany::deserialize(deserializer)? {
+        OneOrMany::Many(values) => Ok(values),
+        OneOrMany::One(value) => Ok(vec![value]),
     }
 }
 
-impl fmt::Display for URL {
+// Proof values are written as base58btc multibase strings, as Data Integrity requires. Proofs
+// stored before that hold plain byte arrays, so both forms are read.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum ProofValue {
+    Multibase(String),
+    Bytes(Vec<u8>),
+}
+
+fn serialize_proof_value<S>(proof_value: &[u8], serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    serializer.serialize_str(&multibase::encode(proof_value))
+}
+
+fn deserialize_proof_value<'de, D>(deserializer: D) -> Result<Vec<u8>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    match ProofValue::deserialize(deserializer)? {
+        ProofValue::Multibase(proof_value) => {
+            multibase::decode(&proof_value).map_err(serde::de::Error::custom)
+        }
+        ProofValue::Bytes(proof_value) => Ok(proof_value),
+    }
+}
+
+#[derive(Debug)]
+pub enum IssuerError {
+    DuplicateVerificationMethod(String),
+    UnknownVerificationMethod(String),
+}
+
+impl Error for IssuerError {}
+
+impl fmt::Display for IssuerError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "{}", self.get_str())
+        match self {
+            Self::DuplicateVerificationMethod(e) => {
+                write!(f, "Duplicate verification method: {}", e)
+            }
+            Self::UnknownVerificationMethod(e) => write!(f, "Unknown verification method: {}", e),
+        }
     }
 }
 
@@ -30,6 +129,14 @@ pub struct Issuer {
     id: URL,
     name: String,
     verification_methods: Vec<VerificationMethod>,
+    #[serde(default)]
+    authentication: Vec<URL>,
+    #[serde(default)]
+    assertion_method: Vec<URL>,
+    #[serde(default)]
+    key_agreement: Vec<URL>,
+    #[serde(default)]
+    capability_invocation: Vec<URL>,
 }
 
 impl Issuer {
@@ -38,9 +145,19 @@ impl Issuer {
             id,
             name,
             verification_methods: vec![],
+            authentication: vec![],
+            assertion_method: vec![],
+            key_agreement: vec![],
+            capability_invocation: vec![],
         }
     }
 
+    // Parses issuers stored before verification method keys were multibase encoded.
+    pub fn from_legacy_json(json: &str) -> Result<Self, serde_json::Error> {
+        serde_json::from_str::<legacy::LegacyIssuer>(json)
+            .and_then(|issuer| Self::try_from(issuer).map_err(serde::de::Error::custom))
+    }
+
     pub fn get_id(&self) -> &URL {
         &self.id
     }
@@ -53,26 +170,262 @@ impl Issuer {
         &self.verification_methods
     }
 
-    pub fn new_verification_method(&mut self, verification_method: VerificationMethod) {
+    // Adds a key and authorizes it for the given verification relationships. Keys are looked up
+    // by id, so an id that is already in use is rejected rather than shadowed.
+    pub fn new_verification_method(
+        &mut self,
+        verification_method: VerificationMethod,
+        verification_relationships: &[VerificationRelationship],
+    ) -> Result<(), IssuerError> {
+        self.check_new_verification_method_id(verification_method.get_id())?;
+        for verification_relationship in verification_relationships {
+            self.verification_relationship_mut(*verification_relationship)
+                .push(verification_method.get_id().clone());
+        }
         self.verification_methods.push(verification_method);
+        Ok(())
+    }
+
+    fn check_new_verification_method_id(
+        &self,
+        verification_method_id: &URL,
+    ) -> Result<(), IssuerError> {
+        match self.get_verification_method(verification_method_id) {
+            Some(_) => Err(IssuerError::DuplicateVerificationMethod(format!(
+                "Issuer {} already has a verification method {}.",
+                self.id, verification_method_id
+            ))),
+            None => Ok(()),
+        }
+    }
+
+    pub fn get_verification_relationship(
+        &self,
+        verification_relationship: VerificationRelationship,
+    ) -> &Vec<URL> {
+        match verification_relationship {
+            VerificationRelationship::Authentication => &self.authentication,
+            VerificationRelationship::AssertionMethod => &self.assertion_method,
+            VerificationRelationship::KeyAgreement => &self.key_agreement,
+            VerificationRelationship::CapabilityInvocation => &self.capability_invocation,
+        }
+    }
+
+    fn verification_relationship_mut(
+        &mut self,
+        verification_relationship: VerificationRelationship,
+    ) -> &mut Vec<URL> {
+        match verification_relationship {
+            VerificationRelationship::Authentication => &mut self.authentication,
+            VerificationRelationship::AssertionMethod => &mut self.assertion_method,
+            VerificationRelationship::KeyAgreement => &mut self.key_agreement,
+            VerificationRelationship::CapabilityInvocation => &mut self.capability_invocation,
+        }
+    }
+
+    pub fn is_authorized(
+        &self,
+        verification_method_id: &URL,
+        verification_relationship: VerificationRelationship,
+    ) -> bool {
+        self.get_verification_relationship(verification_relationship)
+            .contains(verification_method_id)
+    }
+
+    // Issuers stored before verification relationships existed used every key for both
+    // authentication and assertions. Returns whether the issuer needed migrating.
+    pub fn migrate_verification_relationships(&mut self) -> bool {
+        let has_verification_relationships = VerificationRelationship::ALL
+            .iter()
+            .any(|relationship| !self.get_verification_relationship(*relationship).is_empty());
+        if has_verification_relationships || self.verification_methods.is_empty() {
+            return false;
+        }
+        let verification_method_ids: Vec<URL> = self
+            .verification_methods
+            .iter()
+            .map(|verification_method| verification_method.get_id().clone())
+            .collect();
+        self.authentication = verification_method_ids.clone();
+        self.assertion_method = verification_method_ids;
+        true
+    }
+
+    pub fn get_verification_method(
+        &self,
+        verification_method_id: &URL,
+    ) -> Option<&VerificationMethod> {
+        self.verification_methods
+            .iter()
+            .find(|verification_method| verification_method.get_id() == verification_method_id)
+    }
+
+    // Verification methods are appended as they are added, so the newest active one is last.
+    pub fn get_newest_active_verification_method(
+        &self,
+        verification_relationship: VerificationRelationship,
+    ) -> Option<&VerificationMethod> {
+        self.verification_methods
+            .iter()
+            .rev()
+            .find(|verification_method| {
+                verification_method.is_active()
+                    && self.is_authorized(verification_method.get_id(), verification_relationship)
+            })
+    }
+
+    // Retires an active key in favour of a new one at the given time. Proofs made with the old key
+    // before then stay valid.
+    pub fn rotate_verification_method(
+        &mut self,
+        verification_method_id: &URL,
+        mut verification_method: VerificationMethod,
+        rotated: DateTime<Utc>,
+    ) -> Result<(), IssuerError> {
+        self.check_new_verification_method_id(verification_method.get_id())?;
+        match self
+            .verification_methods
+            .iter_mut()
+            .find(|verification_method| {
+                verification_method.get_id() == verification_method_id
+                    && verification_method.is_active()
+            }) {
+            Some(old_verification_method) => {
+                old_verification_method.deactivate(VerificationMethodStatus::Rotated, rotated);
+                verification_method.set_created(rotated);
+                // The new key takes over the old key's verification relationships.
+                let verification_relationships: Vec<VerificationRelationship> =
+                    VerificationRelationship::ALL
+                        .into_iter()
+                        .filter(|relationship| {
+                            self.is_authorized(verification_method_id, *relationship)
+                        })
+                        .collect();
+                self.new_verification_method(verification_method, &verification_relationships)
+            }
+            None => Err(IssuerError::UnknownVerificationMethod(format!(
+                "Issuer {} has no active verification method {}.",
+                self.id, verification_method_id
+            ))),
+        }
+    }
+
+    // Revokes a key as of the given time, which may lie in the past when a key was compromised
+    // before it was noticed. Returns false if there is no such key.
+    pub fn revoke_verification_method(
+        &mut self,
+        verification_method_id: &URL,
+        revoked: DateTime<Utc>,
+    ) -> bool {
+        match self
+            .verification_methods
+            .iter_mut()
+            .find(|verification_method| verification_method.get_id() == verification_method_id)
+        {
+            Some(verification_method) => {
+                // A revocation can only shorten a rotated key's validity window.
+                let revoked = match verification_method.get_revoked() {
+                    Some(previous) if *previous < revoked => *previous,
+                    _ => revoked,
+                };
+                verification_method.deactivate(VerificationMethodStatus::Revoked, revoked);
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn get_did_document(&self) -> DIDDocument {
+        DIDDocument::new(
+            vec![URL::new(DID_CONTEXT).expect("The DID context should be a valid URL.")],
+            self.id.clone(),
+            self.verification_methods.clone(),
+            self.authentication.clone(),
+            self.assertion_method.clone(),
+            self.key_agreement.clone(),
+            self.capability_invocation.clone(),
+        )
+    }
+}
+
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "camelCase")]
+pub enum VerificationRelationship {
+    Authentication,
+    AssertionMethod,
+    KeyAgreement,
+    CapabilityInvocation,
+}
+
+impl VerificationRelationship {
+    pub const ALL: [Self; 4] = [
+        Self::Authentication,
+        Self::AssertionMethod,
+        Self::KeyAgreement,
+        Self::CapabilityInvocation,
+    ];
+
+    // Proofs made under a relationship carry its name as their proof purpose.
+    pub fn get_proof_purpose(&self) -> &'static str {
+        match self {
+            Self::Authentication => AUTHENTICATION_PROOF_PURPOSE,
+            Self::AssertionMethod => ASSERTION_METHOD_PROOF_PURPOSE,
+            Self::KeyAgreement => KEY_AGREEMENT_PROOF_PURPOSE,
+            Self::CapabilityInvocation => CAPABILITY_INVOCATION_PROOF_PURPOSE,
+        }
+    }
+}
+
+impl fmt::Display for VerificationRelationship {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.get_proof_purpose())
     }
 }
 
 #[derive(Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
 pub struct VerificationMethod {
     id: URL,
+    #[serde(rename = "type")]
     type_: String,
+    #[serde(rename = "controller")]
     controller_id: URL,
-    public_key_multibase: Vec<u8>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    public_key_multibase: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    public_key_jwk: Option<JWK>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    created: Option<DateTime<Utc>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    revoked: Option<DateTime<Utc>>,
+    #[serde(default, skip_serializing_if = "VerificationMethodStatus::is_active")]
+    status: VerificationMethodStatus,
 }
 
 impl VerificationMethod {
-    pub fn new(id: URL, type_: String, controller_id: URL, public_key_multibase: Vec<u8>) -> Self {
+    pub fn new(id: URL, type_: String, controller_id: URL, public_key_multibase: String) -> Self {
         Self {
             id,
             type_,
             controller_id,
-            public_key_multibase,
+            public_key_multibase: Some(public_key_multibase),
+            public_key_jwk: None,
+            created: None,
+            revoked: None,
+            status: VerificationMethodStatus::Active,
+        }
+    }
+
+    pub fn new_jwk(id: URL, controller_id: URL, public_key_jwk: JWK) -> Self {
+        Self {
+            id,
+            type_: jwk::JSON_WEB_KEY_2020_TYPE.to_string(),
+            controller_id,
+            public_key_multibase: None,
+            public_key_jwk: Some(public_key_jwk.to_public()),
+            created: None,
+            revoked: None,
+            status: VerificationMethodStatus::Active,
         }
     }
 
@@ -88,9 +441,176 @@ impl VerificationMethod {
         &self.controller_id
     }
 
-    pub fn get_public_key_multibase(&self) -> &Vec<u8> {
+    pub fn get_public_key_multibase(&self) -> &Option<String> {
         &self.public_key_multibase
     }
+
+    pub fn get_public_key(&self) -> Result<(KeyType, Vec<u8>), Box<dyn Error>> {
+        match (&self.public_key_multibase, &self.public_key_jwk) {
+            (Some(public_key_multibase), _) => {
+                Ok(multibase::decode_public_key(public_key_multibase)?)
+            }
+            (None, Some(public_key_jwk)) => Ok(public_key_jwk.get_public_key()?),
+            (None, None) => Err(format!("Verification method {} has no key.", self.id).into()),
+        }
+    }
+
+    pub fn get_public_key_jwk(&self) -> Result<JWK, Box<dyn Error>> {
+        if let Some(public_key_jwk) = &self.public_key_jwk {
+            return Ok(public_key_jwk.clone());
+        }
+        let (key_type, public_key) = self.get_public_key()?;
+        Ok(JWK::from_public_key(key_type, &public_key)?)
+    }
+
+    pub fn get_created(&self) -> &Option<DateTime<Utc>> {
+        &self.created
+    }
+
+    pub fn set_created(&mut self, created: DateTime<Utc>) {
+        self.created = Some(created);
+    }
+
+    pub fn get_revoked(&self) -> &Option<DateTime<Utc>> {
+        &self.revoked
+    }
+
+    pub fn get_status(&self) -> &VerificationMethodStatus {
+        &self.status
+    }
+
+    // Only active verification methods may be used to sign new proofs.
+    pub fn is_active(&self) -> bool {
+        self.status.is_active()
+    }
+
+    // Ends the key's validity window. Proofs created from then on are rejected.
+    pub fn deactivate(&mut self, status: VerificationMethodStatus, revoked: DateTime<Utc>) {
+        self.status = status;
+        self.revoked = Some(revoked);
+    }
+
+    // Whether a proof created at the given time falls inside the key's validity window.
+    pub fn is_valid_at(&self, time: &DateTime<Utc>) -> bool {
+        self.created.is_none_or(|created| created <= *time)
+            && self.revoked.is_none_or(|revoked| *time < revoked)
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum VerificationMethodStatus {
+    #[default]
+    Active,
+    Rotated,
+    Revoked,
+}
+
+impl VerificationMethodStatus {
+    pub fn is_active(&self) -> bool {
+        *self == Self::Active
+    }
+}
+
+impl fmt::Display for VerificationMethodStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Active => write!(f, "Active"),
+            Self::Rotated => write!(f, "Rotated"),
+            Self::Revoked => write!(f, "Revoked"),
+        }
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct DIDDocument {
+    #[serde(rename = "@context")]
+    context: Vec<URL>,
+    id: URL,
+    #[serde(default)]
+    verification_method: Vec<VerificationMethod>,
+    #[serde(default)]
+    authentication: Vec<URL>,
+    #[serde(default)]
+    assertion_method: Vec<URL>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    key_agreement: Vec<URL>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    capability_invocation: Vec<URL>,
+}
+
+impl DIDDocument {
+    pub fn new(
+        context: Vec<URL>,
+        id: URL,
+        verification_method: Vec<VerificationMethod>,
+        authentication: Vec<URL>,
+        assertion_method: Vec<URL>,
+        key_agreement: Vec<URL>,
+        capability_invocation: Vec<URL>,
+    ) -> Self {
+        Self {
+            context,
+            id,
+            verification_method,
+            authentication,
+            assertion_method,
+            key_agreement,
+            capability_invocation,
+        }
+    }
+
+    pub fn get_context(&self) -> &Vec<URL> {
+        &self.context
+    }
+
+    pub fn get_id(&self) -> &URL {
+        &self.id
+    }
+
+    pub fn get_verification_methods(&self) -> &Vec<VerificationMethod> {
+        &self.verification_method
+    }
+
+    pub fn get_authentication(&self) -> &Vec<URL> {
+        &self.authentication
+    }
+
+    pub fn get_assertion_method(&self) -> &Vec<URL> {
+        &self.assertion_method
+    }
+
+    pub fn get_key_agreement(&self) -> &Vec<URL> {
+        &self.key_agreement
+    }
+
+    pub fn get_capability_invocation(&self) -> &Vec<URL> {
+        &self.capability_invocation
+    }
+
+    pub fn get_verification_method(
+        &self,
+        verification_method_id: &URL,
+    ) -> Option<&VerificationMethod> {
+        self.verification_method
+            .iter()
+            .find(|verification_method| verification_method.get_id() == verification_method_id)
+    }
+
+    pub fn is_authorized(
+        &self,
+        verification_method_id: &URL,
+        verification_relationship: VerificationRelationship,
+    ) -> bool {
+        match verification_relationship {
+            VerificationRelationship::Authentication => &self.authentication,
+            VerificationRelationship::AssertionMethod => &self.assertion_method,
+            VerificationRelationship::KeyAgreement => &self.key_agreement,
+            VerificationRelationship::CapabilityInvocation => &self.capability_invocation,
+        }
+        .contains(verification_method_id)
+    }
 }
 
 #[derive(Clone, Serialize, Deserialize, PartialEq)]
@@ -177,6 +697,7 @@ impl CredentialSchema {
 #[derive(Clone, Serialize, Deserialize, PartialEq)]
 pub struct CredentialSchemaLink {
     id: URL,
+    #[serde(rename = "type")]
     type_: String,
 }
 
@@ -195,13 +716,15 @@ impl CredentialSchemaLink {
 }
 
 #[derive(Clone, Serialize, Deserialize, PartialEq)]
+#[serde(untagged)]
 pub enum ClaimPropertyValue {
     Text(String),
-    Number(i32),
+    Number(serde_json::Number),
     Boolean(bool),
 }
 
 #[derive(Clone, Serialize, Deserialize, PartialEq)]
+#[serde(untagged)]
 pub enum ClaimProperty {
     Value(ClaimPropertyValue),
     Array(Vec<ClaimProperty>),
@@ -209,53 +732,375 @@ pub enum ClaimProperty {
 }
 
 #[derive(Clone, Serialize, Deserialize, PartialEq)]
-pub struct CredentialStatus {}
+#[serde(rename_all = "camelCase")]
+pub struct CredentialStatus {
+    id: URL,
+    #[serde(rename = "type")]
+    type_: String,
+    status_purpose: String,
+    status_list_index: String,
+    status_list_credential: URL,
+}
 
 impl CredentialStatus {
-    pub fn new() -> Self {
-        Self {}
+    pub fn new(
+        id: URL,
+        status_purpose: String,
+        status_list_index: usize,
+        status_list_credential: URL,
+    ) -> Self {
+        Self {
+            id,
+            type_: status_list::BITSTRING_STATUS_LIST_ENTRY_TYPE.to_string(),
+            status_purpose,
+            status_list_index: status_list_index.to_string(),
+            status_list_credential,
+        }
+    }
+
+    pub fn get_id(&self) -> &URL {
+        &self.id
+    }
+
+    pub fn get_type(&self) -> &String {
+        &self.type_
+    }
+
+    pub fn get_status_purpose(&self) -> &String {
+        &self.status_purpose
+    }
+
+    pub fn get_status_list_index(&self) -> Result<usize, status_list::StatusListError> {
+        self.status_list_index.parse::<usize>().map_err(|_e| {
+            status_list::StatusListError::InvalidIndex(self.status_list_index.clone())
+        })
+    }
+
+    pub fn get_status_list_credential(&self) -> &URL {
+        &self.status_list_credential
     }
 }
 
 #[derive(Clone, Serialize, Deserialize, PartialEq)]
-pub struct RefreshService {}
+pub struct RefreshService {
+    #[serde(rename = "type")]
+    type_: String,
+    url: URL,
+}
+
+impl RefreshService {
+    pub fn new(url: URL) -> Self {
+        Self {
+            type_: REFRESH_SERVICE_TYPE.to_string(),
+            url,
+        }
+    }
+
+    pub fn get_type(&self) -> &String {
+        &self.type_
+    }
+
+    pub fn get_url(&self) -> &URL {
+        &self.url
+    }
+}
 
+// An ODRL-style rule: the assignee is prohibited from, or obliged to perform, the listed actions.
 #[derive(Clone, Serialize, Deserialize, PartialEq)]
-pub struct TermsOfUse {}
+pub struct PolicyRule {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    assigner: Option<URL>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    assignee: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    target: Option<URL>,
+    #[serde(deserialize_with = "one_or_many")]
+    action: Vec<String>,
+}
+
+impl PolicyRule {
+    pub fn new(
+        assigner: Option<URL>,
+        assignee: Option<String>,
+        target: Option<URL>,
+        action: Vec<String>,
+    ) -> Self {
+        Self {
+            assigner,
+            assignee,
+            target,
+            action,
+        }
+    }
+
+    pub fn get_assigner(&self) -> &Option<URL> {
+        &self.assigner
+    }
+
+    pub fn get_assignee(&self) -> &Option<String> {
+        &self.assignee
+    }
+
+    pub fn get_target(&self) -> &Option<URL> {
+        &self.target
+    }
+
+    pub fn get_action(&self) -> &Vec<String> {
+        &self.action
+    }
+
+    // A rule without an assignee, or assigned to all verifiers, applies to every verifier.
+    pub fn applies_to(&self, verifier_id: &URL) -> bool {
+        match &self.assignee {
+            Some(assignee) => {
+                assignee == ALL_VERIFIERS_ASSIGNEE || assignee == verifier_id.get_str()
+            }
+            None => true,
+        }
+    }
+}
 
 #[derive(Clone, Serialize, Deserialize, PartialEq)]
-pub struct Evidence {}
+pub struct TermsOfUse {
+    #[serde(rename = "type")]
+    type_: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    id: Option<URL>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    prohibition: Vec<PolicyRule>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    obligation: Vec<PolicyRule>,
+}
+
+impl TermsOfUse {
+    pub fn new(
+        type_: String,
+        id: Option<URL>,
+        prohibition: Vec<PolicyRule>,
+        obligation: Vec<PolicyRule>,
+    ) -> Self {
+        Self {
+            type_,
+            id,
+            prohibition,
+            obligation,
+        }
+    }
+
+    pub fn get_type(&self) -> &String {
+        &self.type_
+    }
+
+    pub fn get_id(&self) -> &Option<URL> {
+        &self.id
+    }
+
+    pub fn get_prohibition(&self) -> &Vec<PolicyRule> {
+        &self.prohibition
+    }
+
+    pub fn get_obligation(&self) -> &Vec<PolicyRule> {
+        &self.obligation
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize, PartialEq)]
+pub enum Presence {
+    Physical,
+    Digital,
+}
+
+impl fmt::Display for Presence {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Physical => write!(f, "In person"),
+            Self::Digital => write!(f, "Remote"),
+        }
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct DocumentDigest {
+    name: String,
+    digest_multibase: String,
+}
+
+impl DocumentDigest {
+    pub fn new(name: String, digest_multibase: String) -> Self {
+        Self {
+            name,
+            digest_multibase,
+        }
+    }
+
+    // Digests the document as a base58btc encoded sha2-256 multihash.
+    pub fn from_document(name: String, document: &[u8]) -> Self {
+        let mut multihash = SHA2_256_MULTIHASH.to_vec();
+        multihash.extend_from_slice(&Sha256::digest(document));
+        Self::new(name, multibase::encode(&multihash))
+    }
+
+    pub fn get_name(&self) -> &String {
+        &self.name
+    }
+
+    pub fn get_digest_multibase(&self) -> &String {
+        &self.digest_multibase
+    }
+
+    pub fn is_valid_digest(&self) -> bool {
+        multibase::decode(&self.digest_multibase).is_ok_and(|multihash| {
+            multihash.len() == SHA2_256_MULTIHASH.len() + 32
+                && multihash.starts_with(&SHA2_256_MULTIHASH)
+        })
+    }
+
+    pub fn matches_document(&self, document: &[u8]) -> bool {
+        let name = self.name.clone();
+        *self == Self::from_document(name, document)
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Evidence {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    id: Option<URL>,
+    #[serde(rename = "type", deserialize_with = "one_or_many")]
+    type_: Vec<String>,
+    verifier: String,
+    evidence_document: String,
+    subject_presence: Presence,
+    document_presence: Presence,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    document_digests: Vec<DocumentDigest>,
+}
+
+impl Evidence {
+    pub fn new(
+        id: Option<URL>,
+        verifier: String,
+        evidence_document: String,
+        subject_presence: Presence,
+        document_presence: Presence,
+        document_digests: Vec<DocumentDigest>,
+    ) -> Self {
+        Self {
+            id,
+            type_: vec![
+                "Evidence".to_string(),
+                DOCUMENT_VERIFICATION_EVIDENCE_TYPE.to_string(),
+            ],
+            verifier,
+            evidence_document,
+            subject_presence,
+            document_presence,
+            document_digests,
+        }
+    }
+
+    pub fn get_id(&self) -> &Option<URL> {
+        &self.id
+    }
+
+    pub fn get_type(&self) -> &Vec<String> {
+        &self.type_
+    }
+
+    pub fn get_verifier(&self) -> &String {
+        &self.verifier
+    }
+
+    pub fn get_evidence_document(&self) -> &String {
+        &self.evidence_document
+    }
+
+    pub fn get_subject_presence(&self) -> &Presence {
+        &self.subject_presence
+    }
+
+    pub fn get_document_presence(&self) -> &Presence {
+        &self.document_presence
+    }
+
+    pub fn get_document_digests(&self) -> &Vec<DocumentDigest> {
+        &self.document_digests
+    }
+}
 
 #[derive(Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
 pub struct Proof {
+    // The secured document's @context, recorded by the JCS cryptosuites.
+    #[serde(rename = "@context", default, skip_serializing_if = "Option::is_none")]
+    context: Option<Vec<URL>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    id: Option<URL>,
+    #[serde(rename = "type")]
     type_: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    cryptosuite: Option<String>,
     created: DateTime<Utc>,
     verification_method: URL,
     proof_purpose: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    challenge: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    domain: Option<String>,
+    // Links a chained proof to the proof it endorses.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    previous_proof: Option<URL>,
+    #[serde(
+        serialize_with = "serialize_proof_value",
+        deserialize_with = "deserialize_proof_value"
+    )]
     proof_value: Vec<u8>,
 }
 
 impl Proof {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         type_: String,
+        cryptosuite: Option<String>,
         created: DateTime<Utc>,
         verification_method: URL,
         proof_purpose: String,
+        challenge: Option<String>,
+        domain: Option<String>,
         proof_value: Vec<u8>,
     ) -> Self {
         Self {
+            context: None,
+            id: None,
             type_,
+            cryptosuite,
             created,
             verification_method,
             proof_purpose,
+            challenge,
+            domain,
+            previous_proof: None,
             proof_value,
         }
     }
 
+    pub fn get_context(&self) -> &Option<Vec<URL>> {
+        &self.context
+    }
+
+    pub fn get_id(&self) -> &Option<URL> {
+        &self.id
+    }
+
     pub fn get_type(&self) -> &String {
         &self.type_
     }
 
+    pub fn get_cryptosuite(&self) -> &Option<String> {
+        &self.cryptosuite
+    }
+
     pub fn get_created(&self) -> &DateTime<Utc> {
         &self.created
     }
@@ -268,41 +1113,87 @@ impl Proof {
         &self.proof_purpose
     }
 
+    pub fn get_challenge(&self) -> &Option<String> {
+        &self.challenge
+    }
+
+    pub fn get_domain(&self) -> &Option<String> {
+        &self.domain
+    }
+
+    pub fn get_previous_proof(&self) -> &Option<URL> {
+        &self.previous_proof
+    }
+
     pub fn get_proof_value(&self) -> &Vec<u8> {
         &self.proof_value
     }
 }
 
 #[derive(Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
 pub struct Credential {
+    #[serde(rename = "@context")]
     context: Vec<URL>,
     id: URL,
-    type_: Vec<URL>,
-    issuer: URL,
-    #[serde(with = "ts_seconds")]
-    valid_from: DateTime<Utc>,
-    #[serde(with = "ts_seconds")]
-    valid_until: DateTime<Utc>,
-    credential_subject: HashMap<String, ClaimProperty>,
+    #[serde(rename = "type", deserialize_with = "one_or_many")]
+    type_: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    description: Option<String>,
+    issuer: CredentialIssuer,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    valid_from: Option<DateTime<Utc>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    valid_until: Option<DateTime<Utc>>,
+    #[serde(deserialize_with = "non_empty_one_or_many")]
+    credential_subject: OneOrMany<HashMap<String, ClaimProperty>>,
+    #[serde(
+        default,
+        skip_serializing_if = "Vec::is_empty",
+        deserialize_with = "one_or_many"
+    )]
     credential_schema: Vec<CredentialSchemaLink>,
-    credential_status: CredentialStatus,
+    #[serde(
+        default,
+        skip_serializing_if = "Vec::is_empty",
+        deserialize_with = "one_or_many"
+    )]
+    credential_status: Vec<CredentialStatus>,
+    #[serde(
+        default,
+        skip_serializing_if = "Vec::is_empty",
+        deserialize_with = "one_or_many"
+    )]
     refresh_service: Vec<RefreshService>,
+    #[serde(
+        default,
+        skip_serializing_if = "Vec::is_empty",
+        deserialize_with = "one_or_many"
+    )]
     terms_of_use: Vec<TermsOfUse>,
+    #[serde(
+        default,
+        skip_serializing_if = "Vec::is_empty",
+        deserialize_with = "one_or_many"
+    )]
     evidence: Vec<Evidence>,
 }
 
 impl Credential {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         context: Vec<URL>,
         id: URL,
-        type_: Vec<URL>,
+        type_: Vec<String>,
         issuer: URL,
         valid_from: DateTime<Utc>,
         valid_until: DateTime<Utc>,
         credential_subject: HashMap<String, ClaimProperty>,
         credential_schema: Vec<CredentialSchemaLink>,
     ) -> Self {
-        let credential_status = CredentialStatus::new();
+        let credential_status = Vec::new();
         let refresh_service = Vec::new();
         let terms_of_use = Vec::new();
         let evidence = Vec::new();
@@ -310,10 +1201,12 @@ impl Credential {
             context,
             id,
             type_,
-            issuer,
-            valid_from,
-            valid_until,
-            credential_subject,
+            name: None,
+            description: None,
+            issuer: CredentialIssuer::Id(issuer),
+            valid_from: Some(valid_from),
+            valid_until: Some(valid_until),
+            credential_subject: OneOrMany::One(credential_subject),
             credential_schema,
             credential_status,
             refresh_service,
@@ -330,50 +1223,119 @@ impl Credential {
         &self.id
     }
 
-    pub fn get_type(&self) -> &Vec<URL> {
+    pub fn get_type(&self) -> &Vec<String> {
         &self.type_
     }
 
+    pub fn get_name(&self) -> &Option<String> {
+        &self.name
+    }
+
+    pub fn get_description(&self) -> &Option<String> {
+        &self.description
+    }
+
     pub fn get_issuer(&self) -> &URL {
+        self.issuer.get_id()
+    }
+
+    pub fn get_issuer_details(&self) -> &CredentialIssuer {
         &self.issuer
     }
 
-    pub fn get_valid_from(&self) -> &DateTime<Utc> {
+    pub fn get_valid_from(&self) -> &Option<DateTime<Utc>> {
         &self.valid_from
     }
 
-    pub fn get_valid_until(&self) -> &DateTime<Utc> {
+    pub fn get_valid_until(&self) -> &Option<DateTime<Utc>> {
         &self.valid_until
     }
 
+    pub fn set_validity_period(&mut self, valid_from: DateTime<Utc>, valid_until: DateTime<Utc>) {
+        self.valid_from = Some(valid_from);
+        self.valid_until = Some(valid_until);
+    }
+
+    // The first credential subject. Credentials issued here always have exactly one.
     pub fn get_credential_subject(&self) -> &HashMap<String, ClaimProperty> {
-        &self.credential_subject
+        &self.credential_subject.as_slice()[0]
+    }
+
+    pub fn get_credential_subjects(&self) -> &[HashMap<String, ClaimProperty>] {
+        self.credential_subject.as_slice()
     }
 
     pub fn get_credential_schema(&self) -> &Vec<CredentialSchemaLink> {
         &self.credential_schema
     }
 
-    pub fn get_credential_status(&self) -> &CredentialStatus {
+    pub fn get_credential_status(&self) -> &Vec<CredentialStatus> {
         &self.credential_status
     }
 
+    pub fn add_credential_status(&mut self, credential_status: CredentialStatus) {
+        self.credential_status.push(credential_status);
+    }
+
     pub fn get_refresh_service(&self) -> &Vec<RefreshService> {
         &self.refresh_service
     }
 
+    pub fn add_refresh_service(&mut self, refresh_service: RefreshService) {
+        self.refresh_service.push(refresh_service);
+    }
+
     pub fn get_terms_of_use(&self) -> &Vec<TermsOfUse> {
         &self.terms_of_use
     }
 
+    pub fn add_terms_of_use(&mut self, terms_of_use: TermsOfUse) {
+        self.terms_of_use.push(terms_of_use);
+    }
+
     pub fn get_evidence(&self) -> &Vec<Evidence> {
         &self.evidence
     }
+
+    pub fn add_evidence(&mut self, evidence: Evidence) {
+        self.evidence.push(evidence);
+    }
+}
+
+// The issuer of a credential, either as a bare URL or as an object with an id and further
+// properties such as a name.
+#[derive(Clone, Serialize, Deserialize, PartialEq)]
+#[serde(untagged)]
+pub enum CredentialIssuer {
+    Id(URL),
+    Object {
+        id: URL,
+        #[serde(flatten)]
+        properties: serde_json::Map<String, serde_json::Value>,
+    },
+}
+
+impl CredentialIssuer {
+    pub fn get_id(&self) -> &URL {
+        match self {
+            Self::Id(id) => id,
+            Self::Object { id, .. } => id,
+        }
+    }
+
+    pub fn get_name(&self) -> Option<&str> {
+        match self {
+            Self::Id(_) => None,
+            Self::Object { properties, .. } => properties.get("name")?.as_str(),
+        }
+    }
 }
 
 #[derive(Clone, Serialize, Deserialize, PartialEq)]
 pub struct VerifiableCredential {
+    #[serde(flatten)]
     credential: Credential,
+    #[serde(deserialize_with = "one_or_many")]
     proof: Vec<Proof>,
 }
 
@@ -382,6 +1344,15 @@ impl VerifiableCredential {
         Self { credential, proof }
     }
 
+    // Parses the VC Data Model 2.0 form, falling back to the legacy form written by earlier versions.
+    pub fn from_json(json: &str) -> Result<Self, serde_json::Error> {
+        serde_json::from_str::<Self>(json).or_else(|e| Self::from_legacy_json(json).map_err(|_| e))
+    }
+
+    pub fn from_legacy_json(json: &str) -> Result<Self, serde_json::Error> {
+        serde_json::from_str::<legacy::LegacyVerifiableCredential>(json).map(Self::from)
+    }
+
     pub fn get_credential(&self) -> &Credential {
         &self.credential
     }
@@ -389,14 +1360,40 @@ impl VerifiableCredential {
     pub fn get_proof(&self) -> &Vec<Proof> {
         &self.proof
     }
+
+    pub fn get_proof_by_id(&self, id: &URL) -> Option<&Proof> {
+        self.proof
+            .iter()
+            .find(|proof| proof.get_id().as_ref() == Some(id))
+    }
+
+    // Adds a proof to an already signed credential, forming a proof set.
+    pub fn add_proof(&mut self, proof: Proof) {
+        self.proof.push(proof);
+    }
 }
 
+#[derive(Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
 pub struct VerifiablePresentation {
+    #[serde(rename = "@context")]
     context: Vec<URL>,
     id: URL,
-    type_: Vec<URL>,
+    #[serde(rename = "type", deserialize_with = "one_or_many")]
+    type_: Vec<String>,
+    #[serde(
+        default,
+        skip_serializing_if = "Vec::is_empty",
+        deserialize_with = "one_or_many"
+    )]
     verifiable_credential: Vec<VerifiableCredential>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     holder: Option<URL>,
+    #[serde(
+        default,
+        skip_serializing_if = "Vec::is_empty",
+        deserialize_with = "one_or_many"
+    )]
     proof: Vec<Proof>,
 }
 
@@ -404,7 +1401,7 @@ impl VerifiablePresentation {
     pub fn new(
         context: Vec<URL>,
         id: URL,
-        type_: Vec<URL>,
+        type_: Vec<String>,
         verifiable_credential: Vec<VerifiableCredential>,
         holder: Option<URL>,
         proof: Vec<Proof>,
@@ -427,7 +1424,7 @@ impl VerifiablePresentation {
         &self.id
     }
 
-    pub fn get_type(&self) -> &Vec<URL> {
+    pub fn get_type(&self) -> &Vec<String> {
         &self.type_
     }
 
@@ -442,6 +1439,10 @@ impl VerifiablePresentation {
     pub fn get_proof(&self) -> &Vec<Proof> {
         &self.proof
     }
+
+    pub fn add_proof(&mut self, proof: Proof) {
+        self.proof.push(proof);
+    }
 }
 
 #[derive(Clone, Serialize, Deserialize, PartialEq)]
@@ -449,14 +1450,28 @@ pub struct Verifier {
     id: URL,
     name: String,
     schema_id: URL,
+    // The actions the verifier takes with presented credentials, checked against their terms of use.
+    #[serde(default)]
+    actions: Vec<String>,
+    // Whether credentials without a subject id may be presented by any holder.
+    #[serde(default)]
+    allow_bearer_credentials: bool,
 }
 
 impl Verifier {
-    pub fn new(id: URL, name: String, schema_id: URL) -> Self {
+    pub fn new(
+        id: URL,
+        name: String,
+        schema_id: URL,
+        actions: Vec<String>,
+        allow_bearer_credentials: bool,
+    ) -> Self {
         Self {
             id,
             name,
             schema_id,
+            actions,
+            allow_bearer_credentials,
         }
     }
 
@@ -471,4 +1486,214 @@ impl Verifier {
     pub fn get_schema_id(&self) -> &URL {
         &self.schema_id
     }
+
+    pub fn get_actions(&self) -> &Vec<String> {
+        &self.actions
+    }
+
+    pub fn allows_bearer_credentials(&self) -> bool {
+        self.allow_bearer_credentials
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_round_trips(credential_json: serde_json::Value) -> Credential {
+        let credential: Credential = serde_json::from_value(credential_json.clone()).unwrap();
+        assert_eq!(serde_json::to_value(&credential).unwrap(), credential_json);
+        credential
+    }
+
+    #[test]
+    fn round_trips_vc_2_0_example() {
+        // Example 2 of the VC Data Model 2.0 specification, which has no validUntil.
+        let credential = assert_round_trips(serde_json::json!({
+            "@context": [
+                "https://www.w3.org/ns/credentials/v2",
+                "https://www.w3.org/ns/credentials/examples/v2"
+            ],
+            "id": "http://university.example/credentials/3732",
+            "type": ["VerifiableCredential", "ExampleDegreeCredential"],
+            "issuer": "https://university.example/issuers/565049",
+            "validFrom": "2010-01-01T00:00:00Z",
+            "credentialSubject": {
+                "id": "did:example:ebfeb1f712ebc6f1c276e12ec21",
+                "degree": {
+                    "type": "ExampleBachelorDegree",
+                    "name": "Bachelor of Science and Arts"
+                }
+            }
+        }));
+        assert_eq!(
+            credential.get_issuer().get_str(),
+            "https://university.example/issuers/565049"
+        );
+        assert!(credential.get_valid_until().is_none());
+    }
+
+    #[test]
+    fn round_trips_issuer_object() {
+        let credential = assert_round_trips(serde_json::json!({
+            "@context": ["https://www.w3.org/ns/credentials/v2"],
+            "id": "http://university.example/credentials/1872",
+            "type": ["VerifiableCredential", "ExampleAlumniCredential"],
+            "issuer": {
+                "id": "did:example:76e12ec712ebc6f1c221ebfeb1f",
+                "name": "Example University"
+            },
+            "validFrom": "2010-01-01T19:23:24Z",
+            "validUntil": "2020-01-01T19:23:24Z",
+            "credentialSubject": {
+                "id": "did:example:ebfeb1f712ebc6f1c276e12ec21",
+                "alumniOf": "Example University"
+            }
+        }));
+        assert_eq!(
+            credential.get_issuer().get_str(),
+            "did:example:76e12ec712ebc6f1c221ebfeb1f"
+        );
+        assert_eq!(
+            credential.get_issuer_details().get_name(),
+            Some("Example University")
+        );
+    }
+
+    #[test]
+    fn round_trips_credential_subject_array() {
+        // The multiple subjects example of the VC Data Model 2.0 specification.
+        let credential = assert_round_trips(serde_json::json!({
+            "@context": [
+                "https://www.w3.org/ns/credentials/v2",
+                "https://www.w3.org/ns/credentials/examples/v2"
+            ],
+            "id": "http://university.example/credentials/3732",
+            "type": ["VerifiableCredential", "RelationshipCredential"],
+            "issuer": "https://issuer.example/issuer/123",
+            "validFrom": "2010-01-01T00:00:00Z",
+            "credentialSubject": [{
+                "id": "did:example:ebfeb1f712ebc6f1c276e12ec21",
+                "name": "Jayden Doe",
+                "spouse": "did:example:c276e12ec21ebfeb1f712ebc6f1"
+            }, {
+                "id": "did:example:c276e12ec21ebfeb1f712ebc6f1",
+                "name": "Morgan Doe",
+                "spouse": "did:example:ebfeb1f712ebc6f1c276e12ec21"
+            }]
+        }));
+        assert_eq!(credential.get_credential_subjects().len(), 2);
+    }
+
+    #[test]
+    fn round_trips_numeric_claims() {
+        assert_round_trips(serde_json::json!({
+            "@context": ["https://www.w3.org/ns/credentials/v2"],
+            "id": "urn:uuid:58172aac-d8ba-11ed-83dd-0b3aef56cc33",
+            "type": ["VerifiableCredential"],
+            "issuer": "https://vc.example/issuers/5678",
+            "credentialSubject": {
+                "gpa": 3.75,
+                "credits": 4294967296u64,
+                "balance": -12
+            }
+        }));
+    }
+
+    #[test]
+    fn rejects_empty_credential_subject() {
+        let credential_json = serde_json::json!({
+            "@context": ["https://www.w3.org/ns/credentials/v2"],
+            "id": "urn:uuid:58172aac-d8ba-11ed-83dd-0b3aef56cc33",
+            "type": ["VerifiableCredential"],
+            "issuer": "https://vc.example/issuers/5678",
+            "credentialSubject": []
+        });
+        assert!(serde_json::from_value::<Credential>(credential_json).is_err());
+    }
+
+    fn new_test_verification_method(issuer: &Issuer, fragment: &str) -> VerificationMethod {
+        VerificationMethod::new(
+            URL::new(&format!("{}#{}", issuer.get_id(), fragment)).unwrap(),
+            "Multikey".to_string(),
+            issuer.get_id().clone(),
+            "zDnaepBuvsQ8cpsWrVKw8fbpGpvPeNSjVPTWoq6cRqaYzBKVP".to_string(),
+        )
+    }
+
+    #[test]
+    fn rejects_duplicate_verification_method_ids() {
+        let mut issuer = Issuer::new(
+            URL::new("did:example:issuer").unwrap(),
+            "Test Issuer".to_string(),
+        );
+        let relationships = [VerificationRelationship::AssertionMethod];
+        let key_1 = new_test_verification_method(&issuer, "key-1");
+        let key_2 = new_test_verification_method(&issuer, "key-2");
+        issuer
+            .new_verification_method(key_1.clone(), &relationships)
+            .unwrap();
+        issuer
+            .new_verification_method(key_2.clone(), &relationships)
+            .unwrap();
+        assert!(matches!(
+            issuer.new_verification_method(key_1.clone(), &relationships),
+            Err(IssuerError::DuplicateVerificationMethod(_))
+        ));
+        assert!(matches!(
+            issuer.rotate_verification_method(key_1.get_id(), key_2.clone(), Utc::now()),
+            Err(IssuerError::DuplicateVerificationMethod(_))
+        ));
+        // A rejected rotation leaves the old key active.
+        assert!(issuer
+            .get_verification_method(key_1.get_id())
+            .unwrap()
+            .is_active());
+        assert_eq!(issuer.get_verification_methods().len(), 2);
+        assert_eq!(
+            issuer
+                .get_verification_relationship(VerificationRelationship::AssertionMethod)
+                .len(),
+            2
+        );
+    }
+
+    #[test]
+    fn checks_proofs_against_the_key_validity_window() {
+        let mut issuer = Issuer::new(
+            URL::new("did:example:issuer").unwrap(),
+            "Test Issuer".to_string(),
+        );
+        let relationships = [VerificationRelationship::AssertionMethod];
+        let mut key_1 = new_test_verification_method(&issuer, "key-1");
+        let created: DateTime<Utc> = "2024-01-01T00:00:00Z".parse().unwrap();
+        key_1.set_created(created);
+        issuer
+            .new_verification_method(key_1.clone(), &relationships)
+            .unwrap();
+        let rotated: DateTime<Utc> = "2024-06-01T00:00:00Z".parse().unwrap();
+        issuer
+            .rotate_verification_method(
+                key_1.get_id(),
+                new_test_verification_method(&issuer, "key-2"),
+                rotated,
+            )
+            .unwrap();
+        let before_rotation: DateTime<Utc> = "2024-03-01T00:00:00Z".parse().unwrap();
+        let after_rotation: DateTime<Utc> = "2024-09-01T00:00:00Z".parse().unwrap();
+
+        // Proofs made before a rotation stay valid.
+        let key_1 = issuer.get_verification_method(key_1.get_id()).unwrap();
+        assert!(key_1.is_valid_at(&before_rotation));
+        assert!(!key_1.is_valid_at(&after_rotation));
+        assert!(!key_1.is_valid_at(&"2023-01-01T00:00:00Z".parse().unwrap()));
+
+        // Proofs made before a revocation stay valid too; only later ones are rejected.
+        let key_1_id = key_1.get_id().clone();
+        let revoked: DateTime<Utc> = "2024-04-01T00:00:00Z".parse().unwrap();
+        issuer.revoke_verification_method(&key_1_id, revoked);
+        let key_1 = issuer.get_verification_method(&key_1_id).unwrap();
+        assert!(key_1.is_valid_at(&before_rotation));
+        assert!(!key_1.is_valid_at(&"2024-05-01T00:00:00Z".parse().unwrap()));
+    }
 }
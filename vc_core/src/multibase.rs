@@ -0,0 +1,205 @@
+use super::*;
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine as _};
+
+const BASE58_BTC_PREFIX: char = 'z';
+const BASE64_URL_PREFIX: char = 'u';
+const SECP256K1_PUB_MULTICODEC: [u8; 2] = [0xe7, 0x01];
+const ED25519_PUB_MULTICODEC: [u8; 2] = [0xed, 0x01];
+const P256_PUB_MULTICODEC: [u8; 2] = [0x80, 0x24];
+
+#[derive(Debug)]
+pub enum MultibaseError {
+    UnsupportedEncoding(String),
+    InvalidEncoding(String),
+    UnsupportedKeyType(String),
+}
+
+impl Error for MultibaseError {}
+
+impl fmt::Display for MultibaseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnsupportedEncoding(e) => write!(f, "Unsupported multibase encoding: {}", e),
+            Self::InvalidEncoding(e) => write!(f, "Invalid multibase encoding: {}", e),
+            Self::UnsupportedKeyType(e) => write!(f, "Unsupported key type: {}", e),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum KeyType {
+    Secp256k1,
+    Ed25519,
+    P256,
+}
+
+impl KeyType {
+    fn get_multicodec(&self) -> &'static [u8] {
+        match self {
+            Self::Secp256k1 => &SECP256K1_PUB_MULTICODEC,
+            Self::Ed25519 => &ED25519_PUB_MULTICODEC,
+            Self::P256 => &P256_PUB_MULTICODEC,
+        }
+    }
+}
+
+pub fn encode(data: &[u8]) -> String {
+    format!("{}{}", BASE58_BTC_PREFIX, bs58::encode(data).into_string())
+}
+
+pub fn encode_base64url(data: &[u8]) -> String {
+    format!("{}{}", BASE64_URL_PREFIX, URL_SAFE_NO_PAD.encode(data))
+}
+
+pub fn decode(multibase: &str) -> Result<Vec<u8>, MultibaseError> {
+    let mut chars = multibase.chars();
+    match chars.next() {
+        Some(BASE58_BTC_PREFIX) => bs58::decode(chars.as_str())
+            .into_vec()
+            .map_err(|e| MultibaseError::InvalidEncoding(e.to_string())),
+        Some(BASE64_URL_PREFIX) => URL_SAFE_NO_PAD
+            .decode(chars.as_str())
+            .map_err(|e| MultibaseError::InvalidEncoding(e.to_string())),
+        Some(prefix) => Err(MultibaseError::UnsupportedEncoding(prefix.to_string())),
+        None => Err(MultibaseError::InvalidEncoding(
+            "Empty multibase string.".to_string(),
+        )),
+    }
+}
+
+// Encodes a public key as a Multikey: a multicodec key type prefix followed by the key bytes.
+pub fn encode_public_key(key_type: KeyType, public_key: &[u8]) -> String {
+    let mut multicodec_key = key_type.get_multicodec().to_vec();
+    multicodec_key.extend_from_slice(public_key);
+    encode(&multicodec_key)
+}
+
+// Multikeys are always base58btc encoded.
+pub fn decode_public_key(multibase: &str) -> Result<(KeyType, Vec<u8>), MultibaseError> {
+    if !multibase.starts_with(BASE58_BTC_PREFIX) {
+        return Err(MultibaseError::UnsupportedEncoding(format!(
+            "{} is not a base58btc Multikey.",
+            multibase
+        )));
+    }
+    let multicodec_key = decode(multibase)?;
+    for key_type in [KeyType::Secp256k1, KeyType::Ed25519, KeyType::P256] {
+        if let Some(public_key) = multicodec_key.strip_prefix(key_type.get_multicodec()) {
+            return Ok((key_type, public_key.to_vec()));
+        }
+    }
+    Err(MultibaseError::UnsupportedKeyType(format!(
+        "Unknown multicodec prefix in {}.",
+        multibase
+    )))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trips_multibase_data() {
+        for data in [
+            vec![],
+            vec![0],
+            vec![0, 0, 1],
+            b"Multibase is awesome! \\o/".to_vec(),
+            (0..=255).collect(),
+        ] {
+            assert_eq!(decode(&encode(&data)).unwrap(), data);
+            assert_eq!(decode(&encode_base64url(&data)).unwrap(), data);
+        }
+        // The examples from the multibase specification.
+        assert_eq!(
+            encode(b"Multibase is awesome! \\o/"),
+            "zYAjKoNbau5KiqmHPmSxYCvn66dA1vLmwbt"
+        );
+        assert_eq!(
+            encode_base64url(b"Multibase is awesome! \\o/"),
+            "uTXVsdGliYXNlIGlzIGF3ZXNvbWUhIFxvLw"
+        );
+    }
+
+    #[test]
+    fn rejects_unsupported_or_invalid_multibase() {
+        for multibase in ["", "z0OIl", "u+/==", "uTQ=x"] {
+            assert!(
+                matches!(decode(multibase), Err(MultibaseError::InvalidEncoding(_))),
+                "{}",
+                multibase
+            );
+        }
+        // Base16, base32 and base64 with padding are valid multibase, but not supported here.
+        for multibase in ["f4d756c74", "bjv2wy5dj", "mTXVsdA", "MTXVsdA=="] {
+            assert!(
+                matches!(
+                    decode(multibase),
+                    Err(MultibaseError::UnsupportedEncoding(_))
+                ),
+                "{}",
+                multibase
+            );
+        }
+    }
+
+    #[test]
+    fn round_trips_public_keys_with_their_multicodec() {
+        for (key_type, multicodec, public_key, prefix) in [
+            (
+                KeyType::Secp256k1,
+                SECP256K1_PUB_MULTICODEC,
+                [2u8; 33].to_vec(),
+                "zQ3s",
+            ),
+            (
+                KeyType::Ed25519,
+                ED25519_PUB_MULTICODEC,
+                [1u8; 32].to_vec(),
+                "z6Mk",
+            ),
+            (
+                KeyType::P256,
+                P256_PUB_MULTICODEC,
+                [3u8; 33].to_vec(),
+                "zDn",
+            ),
+        ] {
+            let multibase = encode_public_key(key_type, &public_key);
+            assert!(multibase.starts_with(prefix), "{}", multibase);
+            assert_eq!(
+                decode(&multibase).unwrap(),
+                [multicodec.as_slice(), &public_key].concat()
+            );
+            let (decoded_key_type, decoded_key) = decode_public_key(&multibase).unwrap();
+            assert_eq!(decoded_key_type, key_type);
+            assert_eq!(decoded_key, public_key);
+        }
+    }
+
+    #[test]
+    fn rejects_unknown_multicodecs_and_non_base58btc_keys() {
+        for multicodec_key in [
+            // x25519-pub, bls12_381-g2-pub and p384-pub.
+            [[0xec, 0x01].as_slice(), &[1u8; 32]].concat(),
+            [[0xeb, 0x01].as_slice(), &[1u8; 96]].concat(),
+            [[0x81, 0x24].as_slice(), &[2u8; 49]].concat(),
+            vec![0xe7],
+            vec![],
+        ] {
+            assert!(matches!(
+                decode_public_key(&encode(&multicodec_key)),
+                Err(MultibaseError::UnsupportedKeyType(_))
+            ));
+        }
+        let multicodec_key = [ED25519_PUB_MULTICODEC.as_slice(), &[1u8; 32]].concat();
+        assert!(matches!(
+            decode_public_key(&encode_base64url(&multicodec_key)),
+            Err(MultibaseError::UnsupportedEncoding(_))
+        ));
+        assert!(matches!(
+            decode_public_key(""),
+            Err(MultibaseError::UnsupportedEncoding(_))
+        ));
+    }
+}
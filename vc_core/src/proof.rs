@@ -5,14 +5,17 @@ use k256::ecdsa::{
     Signature, SigningKey, VerifyingKey,
 };
 
+pub const EDDSA_JCS_2022_CRYPTOSUITE: &str = "eddsa-jcs-2022";
+pub const ECDSA_JCS_2019_CRYPTOSUITE: &str = "ecdsa-jcs-2019";
+
 pub struct ProofOptions {
     verification_method: VerificationMethod,
     proof_purpose: String,
     created: DateTime<Utc>,
-    #[allow(dead_code)]
-    domain: String,
-    #[allow(dead_code)]
-    challenge: String,
+    domain: Option<String>,
+    challenge: Option<String>,
+    id: Option<URL>,
+    previous_proof: Option<Proof>,
 }
 
 impl ProofOptions {
@@ -20,8 +23,8 @@ impl ProofOptions {
         verification_method: VerificationMethod,
         proof_purpose: String,
         created: DateTime<Utc>,
-        domain: String,
-        challenge: String,
+        domain: Option<String>,
+        challenge: Option<String>,
     ) -> Self {
         Self {
             verification_method,
@@ -29,50 +32,294 @@ impl ProofOptions {
             created,
             domain,
             challenge,
+            id: None,
+            previous_proof: None,
         }
     }
+
+    pub fn set_id(&mut self, id: URL) {
+        self.id = Some(id);
+    }
+
+    // The previous proof must have an id, which the new proof records as its previousProof.
+    pub fn set_previous_proof(&mut self, previous_proof: Proof) {
+        self.previous_proof = Some(previous_proof);
+    }
 }
 
 #[derive(Debug)]
 pub enum ProofGenerationError {
-    Error,
+    SerializationError(String),
+    InvalidSigningKeyError(String),
+    SigningError(String),
 }
 
+impl Error for ProofGenerationError {}
+
 impl fmt::Display for ProofGenerationError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "{:?}", self)
+        match self {
+            Self::SerializationError(e) => write!(f, "Serialization error: {}", e),
+            Self::InvalidSigningKeyError(e) => write!(f, "Invalid signing key: {}", e),
+            Self::SigningError(e) => write!(f, "Signing error: {}", e),
+        }
     }
 }
 
 #[derive(Debug)]
 pub enum ProofVerificationError {
-    MismatchedProofPurposeError,
-    BadTransformationError,
-    BadHashingError,
-    MalformedProofError,
-    InvalidPublicKeyError,
+    MismatchedProofPurposeError(String),
+    BadTransformationError(ProofGenerationError),
+    BadHashingError(ProofGenerationError),
+    MalformedProofError(String),
+    InvalidPublicKeyError(String),
+    MismatchedChallengeError(String),
+    MismatchedDomainError(String),
+    MismatchedPreviousProofError(String),
+    MismatchedContextError(String),
+}
+
+impl Error for ProofVerificationError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::BadTransformationError(e) | Self::BadHashingError(e) => Some(e),
+            _ => None,
+        }
+    }
 }
 
 impl fmt::Display for ProofVerificationError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
-            Self::MismatchedProofPurposeError => {
-                write!(f, "Mismatched proof purpose.")
+            Self::MismatchedProofPurposeError(e) => write!(f, "Mismatched proof purpose: {}", e),
+            Self::BadTransformationError(e) => write!(f, "Bad transformation: {}", e),
+            Self::BadHashingError(e) => write!(f, "Bad hashing: {}", e),
+            Self::MalformedProofError(e) => write!(f, "Malformed proof: {}", e),
+            Self::InvalidPublicKeyError(e) => write!(f, "Invalid public key: {}", e),
+            Self::MismatchedChallengeError(e) => write!(f, "Mismatched challenge: {}", e),
+            Self::MismatchedDomainError(e) => write!(f, "Mismatched domain: {}", e),
+            Self::MismatchedPreviousProofError(e) => {
+                write!(f, "Mismatched previous proof: {}", e)
             }
-            Self::BadTransformationError => {
-                write!(f, "Bad transformation.")
+            Self::MismatchedContextError(e) => write!(f, "Mismatched context: {}", e),
+        }
+    }
+}
+
+pub fn canonicalize<T: Serialize>(data: &T) -> Result<String, serde_json::Error> {
+    let value = serde_json::to_value(data)?;
+    let mut canonical = String::new();
+    write_canonical_value(&value, &mut canonical);
+    Ok(canonical)
+}
+
+// Implements the JSON Canonicalization Scheme (RFC 8785).
+fn write_canonical_value(value: &serde_json::Value, out: &mut String) {
+    match value {
+        serde_json::Value::Null => out.push_str("null"),
+        serde_json::Value::Bool(boolean) => out.push_str(if *boolean { "true" } else { "false" }),
+        serde_json::Value::Number(number) => write_canonical_number(number, out),
+        serde_json::Value::String(string) => write_canonical_string(string, out),
+        serde_json::Value::Array(array) => {
+            out.push('[');
+            for (i, element) in array.iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                write_canonical_value(element, out);
             }
-            Self::BadHashingError => {
-                write!(f, "Bad hashing.")
+            out.push(']');
+        }
+        serde_json::Value::Object(object) => {
+            // Properties are sorted by the UTF-16 code units of their names.
+            let mut entries: Vec<(&String, &serde_json::Value)> = object.iter().collect();
+            entries.sort_by(|(a, _), (b, _)| a.encode_utf16().cmp(b.encode_utf16()));
+            out.push('{');
+            for (i, (key, element)) in entries.into_iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                write_canonical_string(key, out);
+                out.push(':');
+                write_canonical_value(element, out);
             }
-            Self::MalformedProofError => {
-                write!(f, "Malformed proof.")
+            out.push('}');
+        }
+    }
+}
+
+// Numbers are serialized as IEEE 754 doubles using the ECMAScript Number.prototype.toString rules.
+fn write_canonical_number(number: &serde_json::Number, out: &mut String) {
+    let value = number
+        .as_f64()
+        .expect("JSON numbers should be representable as f64.");
+    if value == 0.0 {
+        out.push('0');
+        return;
+    }
+    if value < 0.0 {
+        out.push('-');
+    }
+    let scientific = format!("{:e}", value.abs());
+    let (mantissa, exponent) = scientific
+        .split_once('e')
+        .expect("Scientific notation should contain an exponent.");
+    let mut digits: String = mantissa.chars().filter(|c| *c != '.').collect();
+    if let Some(even_digits) = get_even_tied_digits(value.abs(), &digits, exponent) {
+        digits = even_digits;
+    }
+    let k = digits.len() as i32;
+    let n = exponent
+        .parse::<i32>()
+        .expect("Scientific notation exponent should be an integer.")
+        + 1;
+    if k <= n && n <= 21 {
+        out.push_str(&digits);
+        out.push_str(&"0".repeat((n - k) as usize));
+    } else if 0 < n && n <= 21 {
+        out.push_str(&digits[..n as usize]);
+        out.push('.');
+        out.push_str(&digits[n as usize..]);
+    } else if -6 < n && n <= 0 {
+        out.push_str("0.");
+        out.push_str(&"0".repeat(-n as usize));
+        out.push_str(&digits);
+    } else {
+        out.push_str(&digits[..1]);
+        if k > 1 {
+            out.push('.');
+            out.push_str(&digits[1..]);
+        }
+        out.push('e');
+        out.push(if n - 1 > 0 { '+' } else { '-' });
+        out.push_str(&(n - 1).abs().to_string());
+    }
+}
+
+// When the value lies exactly halfway between two shortest round-tripping digit strings,
+// ECMAScript picks the even one while Rust's formatter may pick the other.
+fn get_even_tied_digits(value: f64, digits: &str, exponent: &str) -> Option<String> {
+    // Every double has an exact decimal expansion of at most 767 significant digits.
+    let exact = format!("{:.800e}", value);
+    let (exact_mantissa, exact_exponent) = exact.split_once('e')?;
+    if exact_exponent != exponent {
+        return None;
+    }
+    let exact_digits: String = exact_mantissa.chars().filter(|c| *c != '.').collect();
+    let (lower, rest) = exact_digits.split_at(digits.len());
+    let is_tie = rest.starts_with('5') && rest[1..].chars().all(|c| c == '0');
+    let is_even = lower
+        .chars()
+        .last()
+        .and_then(|c| c.to_digit(10))
+        .is_some_and(|digit| digit % 2 == 0);
+    let lower_exponent = exponent.parse::<i32>().ok()? - (lower.len() as i32 - 1);
+    let round_trips = format!("{}e{}", lower, lower_exponent)
+        .parse::<f64>()
+        .is_ok_and(|parsed| parsed == value);
+    (is_tie && is_even && round_trips && lower != digits).then(|| lower.to_string())
+}
+
+fn write_canonical_string(string: &str, out: &mut String) {
+    out.push('"');
+    for c in string.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\u{08}' => out.push_str("\\b"),
+            '\u{0C}' => out.push_str("\\f"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if c < '\u{20}' => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+}
+
+// Signs data on behalf of a suite, so suites never see private key material and keys can be held
+// outside the process.
+pub trait KeyHandle {
+    fn get_key_type(&self) -> KeyType;
+
+    fn sign(&self, data: &[u8]) -> Result<Vec<u8>, ProofGenerationError>;
+}
+
+enum SigningKeyMaterial {
+    Secp256k1(SigningKey),
+    P256(p256::ecdsa::SigningKey),
+    Ed25519(ed25519_dalek::SigningKey),
+}
+
+// Holds a parsed private key in process memory.
+pub struct InMemoryKeyHandle {
+    signing_key: SigningKeyMaterial,
+}
+
+impl InMemoryKeyHandle {
+    pub fn new(key_type: KeyType, secret_key: &[u8]) -> Result<Self, ProofGenerationError> {
+        let signing_key = match key_type {
+            KeyType::Secp256k1 => {
+                SigningKeyMaterial::Secp256k1(SigningKey::from_slice(secret_key).map_err(|_| {
+                    ProofGenerationError::InvalidSigningKeyError(format!(
+                        "{} bytes do not hold a secp256k1 scalar",
+                        secret_key.len()
+                    ))
+                })?)
             }
-            Self::InvalidPublicKeyError => {
-                write!(f, "Invalid public key.")
+            KeyType::P256 => SigningKeyMaterial::P256(
+                p256::ecdsa::SigningKey::from_slice(secret_key).map_err(|_| {
+                    ProofGenerationError::InvalidSigningKeyError(format!(
+                        "{} bytes do not hold a P-256 scalar",
+                        secret_key.len()
+                    ))
+                })?,
+            ),
+            KeyType::Ed25519 => {
+                let secret_key: [u8; 32] = secret_key.try_into().map_err(|_| {
+                    ProofGenerationError::InvalidSigningKeyError(format!(
+                        "expected a 32 byte Ed25519 key, found {} bytes",
+                        secret_key.len()
+                    ))
+                })?;
+                SigningKeyMaterial::Ed25519(ed25519_dalek::SigningKey::from_bytes(&secret_key))
             }
+        };
+        Ok(Self { signing_key })
+    }
+}
+
+impl KeyHandle for InMemoryKeyHandle {
+    fn get_key_type(&self) -> KeyType {
+        match self.signing_key {
+            SigningKeyMaterial::Secp256k1(_) => KeyType::Secp256k1,
+            SigningKeyMaterial::P256(_) => KeyType::P256,
+            SigningKeyMaterial::Ed25519(_) => KeyType::Ed25519,
         }
     }
+
+    fn sign(&self, data: &[u8]) -> Result<Vec<u8>, ProofGenerationError> {
+        let signature = match &self.signing_key {
+            SigningKeyMaterial::Secp256k1(signing_key) => {
+                let signature: Signature = signing_key
+                    .try_sign(data)
+                    .map_err(|e| ProofGenerationError::SigningError(e.to_string()))?;
+                signature.to_vec()
+            }
+            SigningKeyMaterial::P256(signing_key) => {
+                let signature: p256::ecdsa::Signature = signing_key
+                    .try_sign(data)
+                    .map_err(|e| ProofGenerationError::SigningError(e.to_string()))?;
+                signature.to_vec()
+            }
+            SigningKeyMaterial::Ed25519(signing_key) => signing_key
+                .try_sign(data)
+                .map_err(|e| ProofGenerationError::SigningError(e.to_string()))?
+                .to_vec(),
+        };
+        Ok(signature)
+    }
 }
 
 pub trait CryptographicSuite {
@@ -83,34 +330,47 @@ pub trait CryptographicSuite {
 
     fn get_type(&self) -> &String;
 
+    // Data Integrity suites share a proof type and are told apart by their cryptosuite name.
+    fn get_cryptosuite(&self) -> Option<&str> {
+        None
+    }
+
     fn transform(
         &self,
         data: &Self::DataDocument,
         options: &ProofOptions,
     ) -> Result<Vec<u8>, ProofGenerationError>;
 
+    // Builds the unsigned proof whose metadata is signed alongside the document.
+    fn configure_proof(
+        &self,
+        data: &Self::DataDocument,
+        options: &ProofOptions,
+    ) -> Result<Self::OutputProof, ProofGenerationError>;
+
     fn hash(
         &self,
         transformed_data: &[u8],
-        options: &ProofOptions,
+        proof_config: &Self::OutputProof,
     ) -> Result<Vec<u8>, ProofGenerationError>;
 
     fn prove(
         &self,
         hash_data: &[u8],
-        proving_key: &[u8],
-        options: &ProofOptions,
+        proving_key: &dyn KeyHandle,
+        proof_config: Self::OutputProof,
     ) -> Result<Self::OutputProof, ProofGenerationError>;
 
     fn generate_proof(
         &self,
         data: &Self::DataDocument,
-        proving_key: &[u8],
+        proving_key: &dyn KeyHandle,
         options: &ProofOptions,
     ) -> Result<Self::OutputProof, ProofGenerationError> {
         let transformed_data = self.transform(data, options)?;
-        let hash_data = self.hash(&transformed_data, options)?;
-        let proof = self.prove(&hash_data, proving_key, options)?;
+        let proof_config = self.configure_proof(data, options)?;
+        let hash_data = self.hash(&transformed_data, &proof_config)?;
+        let proof = self.prove(&hash_data, proving_key, proof_config)?;
         Ok(proof)
     }
 
@@ -137,6 +397,12 @@ impl MyEcdsaSecp256k1 {
     }
 }
 
+impl Default for MyEcdsaSecp256k1 {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl CryptographicSuite for MyEcdsaSecp256k1 {
     type DataDocument = Credential;
     type OutputProof = Proof;
@@ -152,44 +418,242 @@ impl CryptographicSuite for MyEcdsaSecp256k1 {
     fn transform(
         &self,
         data: &Credential,
-        _options: &ProofOptions,
+        options: &ProofOptions,
     ) -> Result<Vec<u8>, ProofGenerationError> {
-        let credential_string =
-            serde_json::to_string(data).map_err(|_| ProofGenerationError::Error)?;
-        Ok(credential_string.as_bytes().to_vec())
+        transform_credential(data, options)
+    }
+
+    fn configure_proof(
+        &self,
+        _data: &Credential,
+        options: &ProofOptions,
+    ) -> Result<Proof, ProofGenerationError> {
+        Ok(new_proof_configuration(
+            self.get_type(),
+            None,
+            None,
+            options,
+        ))
     }
 
     fn hash(
         &self,
         transformed_data: &[u8],
+        proof_config: &Proof,
+    ) -> Result<Vec<u8>, ProofGenerationError> {
+        hash_data_integrity(transformed_data, proof_config, blake3_hash)
+    }
+
+    fn prove(
+        &self,
+        hash_data: &[u8],
+        proving_key: &dyn KeyHandle,
+        mut proof_config: Proof,
+    ) -> Result<Proof, ProofGenerationError> {
+        check_key_type(proving_key, KeyType::Secp256k1)?;
+        proof_config.proof_value = proving_key.sign(hash_data)?;
+        Ok(proof_config)
+    }
+
+    fn verify_proof(
+        &self,
+        data: &Credential,
+        proof: &Proof,
+        verifying_key: &[u8],
+        options: &ProofOptions,
+    ) -> Result<bool, ProofVerificationError> {
+        check_proof_purpose(proof, options)?;
+        check_challenge_and_domain(proof, options)?;
+        check_previous_proof(proof, options)?;
+        let transformed_data = self
+            .transform(data, options)
+            .map_err(ProofVerificationError::BadTransformationError)?;
+        let hash_data = self
+            .hash(&transformed_data, proof)
+            .map_err(ProofVerificationError::BadHashingError)?;
+
+        verify_ecdsa_secp256k1(&hash_data, proof.get_proof_value(), verifying_key)
+    }
+}
+
+// Signs presentations for the authentication proof purpose, binding them to a verifier's challenge and domain.
+pub struct MyEcdsaSecp256k1Presentation {
+    id: URL,
+    type_: String,
+}
+
+impl MyEcdsaSecp256k1Presentation {
+    pub fn new() -> Self {
+        Self {
+            id: URL::new("https://w3id.org/security#proof-ecdsa-secp256k1-2021").unwrap(),
+            type_: "MyEcdsaSecp256k1Signature".to_string(),
+        }
+    }
+}
+
+impl Default for MyEcdsaSecp256k1Presentation {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CryptographicSuite for MyEcdsaSecp256k1Presentation {
+    type DataDocument = VerifiablePresentation;
+    type OutputProof = Proof;
+
+    fn get_id(&self) -> &URL {
+        &self.id
+    }
+
+    fn get_type(&self) -> &String {
+        &self.type_
+    }
+
+    fn transform(
+        &self,
+        data: &VerifiablePresentation,
         _options: &ProofOptions,
     ) -> Result<Vec<u8>, ProofGenerationError> {
-        Ok(blake3::hash(transformed_data).as_bytes().to_vec())
+        let mut unsigned_presentation = data.clone();
+        unsigned_presentation.proof = Vec::new();
+        let presentation_string = canonicalize(&unsigned_presentation).map_err(|e| {
+            ProofGenerationError::SerializationError(format!(
+                "could not canonicalize presentation: {}",
+                e
+            ))
+        })?;
+        Ok(presentation_string.as_bytes().to_vec())
+    }
+
+    fn configure_proof(
+        &self,
+        _data: &VerifiablePresentation,
+        options: &ProofOptions,
+    ) -> Result<Proof, ProofGenerationError> {
+        Ok(new_proof_configuration(
+            self.get_type(),
+            None,
+            None,
+            options,
+        ))
+    }
+
+    fn hash(
+        &self,
+        transformed_data: &[u8],
+        proof_config: &Proof,
+    ) -> Result<Vec<u8>, ProofGenerationError> {
+        hash_data_integrity(transformed_data, proof_config, blake3_hash)
     }
 
     fn prove(
         &self,
         hash_data: &[u8],
-        proving_key: &[u8],
+        proving_key: &dyn KeyHandle,
+        mut proof_config: Proof,
+    ) -> Result<Proof, ProofGenerationError> {
+        check_key_type(proving_key, KeyType::Secp256k1)?;
+        proof_config.proof_value = proving_key.sign(hash_data)?;
+        Ok(proof_config)
+    }
+
+    fn verify_proof(
+        &self,
+        data: &VerifiablePresentation,
+        proof: &Proof,
+        verifying_key: &[u8],
+        options: &ProofOptions,
+    ) -> Result<bool, ProofVerificationError> {
+        check_proof_purpose(proof, options)?;
+        check_challenge_and_domain(proof, options)?;
+        let transformed_data = self
+            .transform(data, options)
+            .map_err(ProofVerificationError::BadTransformationError)?;
+        let hash_data = self
+            .hash(&transformed_data, proof)
+            .map_err(ProofVerificationError::BadHashingError)?;
+
+        verify_ecdsa_secp256k1(&hash_data, proof.get_proof_value(), verifying_key)
+    }
+}
+
+// Implements the eddsa-jcs-2022 Data Integrity cryptosuite: JCS canonicalization, SHA-256 and Ed25519.
+pub struct EddsaJcs2022 {
+    id: URL,
+    type_: String,
+}
+
+impl EddsaJcs2022 {
+    pub fn new() -> Self {
+        Self {
+            id: URL::new("https://www.w3.org/TR/vc-di-eddsa/#eddsa-jcs-2022").unwrap(),
+            type_: DATA_INTEGRITY_PROOF_TYPE.to_string(),
+        }
+    }
+}
+
+impl Default for EddsaJcs2022 {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CryptographicSuite for EddsaJcs2022 {
+    type DataDocument = Credential;
+    type OutputProof = Proof;
+
+    fn get_id(&self) -> &URL {
+        &self.id
+    }
+
+    fn get_type(&self) -> &String {
+        &self.type_
+    }
+
+    fn get_cryptosuite(&self) -> Option<&str> {
+        Some(EDDSA_JCS_2022_CRYPTOSUITE)
+    }
+
+    fn transform(
+        &self,
+        data: &Credential,
+        options: &ProofOptions,
+    ) -> Result<Vec<u8>, ProofGenerationError> {
+        transform_credential(data, options)
+    }
+
+    fn configure_proof(
+        &self,
+        data: &Credential,
         options: &ProofOptions,
     ) -> Result<Proof, ProofGenerationError> {
-        let type_ = self.get_type().clone();
-        let created = options.created;
-        let verification_method = options.verification_method.get_id().clone();
-        let proof_purpose = options.proof_purpose.clone();
-        let signing_key =
-            SigningKey::from_slice(proving_key).map_err(|_| ProofGenerationError::Error)?;
-        let signature: Signature = signing_key.sign(hash_data);
-        let proof_value = signature.to_vec();
-        Ok(Proof::new(
-            type_,
-            created,
-            verification_method,
-            proof_purpose,
-            proof_value,
+        Ok(new_proof_configuration(
+            self.get_type(),
+            Some(EDDSA_JCS_2022_CRYPTOSUITE),
+            Some(data.get_context()),
+            options,
         ))
     }
 
+    fn hash(
+        &self,
+        transformed_data: &[u8],
+        proof_config: &Proof,
+    ) -> Result<Vec<u8>, ProofGenerationError> {
+        hash_data_integrity(transformed_data, proof_config, sha256_hash)
+    }
+
+    fn prove(
+        &self,
+        hash_data: &[u8],
+        proving_key: &dyn KeyHandle,
+        mut proof_config: Proof,
+    ) -> Result<Proof, ProofGenerationError> {
+        check_key_type(proving_key, KeyType::Ed25519)?;
+        proof_config.proof_value = proving_key.sign(hash_data)?;
+        Ok(proof_config)
+    }
+
     fn verify_proof(
         &self,
         data: &Credential,
@@ -197,20 +661,786 @@ impl CryptographicSuite for MyEcdsaSecp256k1 {
         verifying_key: &[u8],
         options: &ProofOptions,
     ) -> Result<bool, ProofVerificationError> {
-        if *proof.get_proof_purpose() != options.proof_purpose {
-            return Err(ProofVerificationError::MismatchedProofPurposeError);
+        check_data_integrity_proof(proof, EDDSA_JCS_2022_CRYPTOSUITE)?;
+        check_proof_context(proof, data.get_context())?;
+        check_proof_purpose(proof, options)?;
+        check_challenge_and_domain(proof, options)?;
+        check_previous_proof(proof, options)?;
+        let transformed_data = self
+            .transform(data, options)
+            .map_err(ProofVerificationError::BadTransformationError)?;
+        let hash_data = self
+            .hash(&transformed_data, proof)
+            .map_err(ProofVerificationError::BadHashingError)?;
+
+        let signature =
+            ed25519_dalek::Signature::from_slice(proof.get_proof_value()).map_err(|_| {
+                ProofVerificationError::MalformedProofError(format!(
+                    "expected a 64 byte Ed25519 signature, found {} bytes",
+                    proof.get_proof_value().len()
+                ))
+            })?;
+        let public_key: [u8; 32] = verifying_key.try_into().map_err(|_| {
+            ProofVerificationError::InvalidPublicKeyError(format!(
+                "expected a 32 byte Ed25519 key, found {} bytes",
+                verifying_key.len()
+            ))
+        })?;
+        let public_key = ed25519_dalek::VerifyingKey::from_bytes(&public_key).map_err(|e| {
+            ProofVerificationError::InvalidPublicKeyError(format!(
+                "not an Ed25519 curve point: {}",
+                e
+            ))
+        })?;
+
+        Ok(public_key.verify(&hash_data, &signature).is_ok())
+    }
+}
+
+// Implements the ecdsa-jcs-2019 Data Integrity cryptosuite with P-256 keys: JCS canonicalization,
+// SHA-256 and ECDSA signatures.
+pub struct EcdsaJcs2019 {
+    id: URL,
+    type_: String,
+}
+
+impl EcdsaJcs2019 {
+    pub fn new() -> Self {
+        Self {
+            id: URL::new("https://www.w3.org/TR/vc-di-ecdsa/#ecdsa-jcs-2019").unwrap(),
+            type_: DATA_INTEGRITY_PROOF_TYPE.to_string(),
         }
+    }
+}
+
+impl Default for EcdsaJcs2019 {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CryptographicSuite for EcdsaJcs2019 {
+    type DataDocument = Credential;
+    type OutputProof = Proof;
+
+    fn get_id(&self) -> &URL {
+        &self.id
+    }
+
+    fn get_type(&self) -> &String {
+        &self.type_
+    }
+
+    fn get_cryptosuite(&self) -> Option<&str> {
+        Some(ECDSA_JCS_2019_CRYPTOSUITE)
+    }
+
+    fn transform(
+        &self,
+        data: &Credential,
+        options: &ProofOptions,
+    ) -> Result<Vec<u8>, ProofGenerationError> {
+        transform_credential(data, options)
+    }
+
+    fn configure_proof(
+        &self,
+        data: &Credential,
+        options: &ProofOptions,
+    ) -> Result<Proof, ProofGenerationError> {
+        Ok(new_proof_configuration(
+            self.get_type(),
+            Some(ECDSA_JCS_2019_CRYPTOSUITE),
+            Some(data.get_context()),
+            options,
+        ))
+    }
+
+    fn hash(
+        &self,
+        transformed_data: &[u8],
+        proof_config: &Proof,
+    ) -> Result<Vec<u8>, ProofGenerationError> {
+        hash_data_integrity(transformed_data, proof_config, sha256_hash)
+    }
+
+    fn prove(
+        &self,
+        hash_data: &[u8],
+        proving_key: &dyn KeyHandle,
+        mut proof_config: Proof,
+    ) -> Result<Proof, ProofGenerationError> {
+        check_key_type(proving_key, KeyType::P256)?;
+        proof_config.proof_value = proving_key.sign(hash_data)?;
+        Ok(proof_config)
+    }
+
+    fn verify_proof(
+        &self,
+        data: &Credential,
+        proof: &Proof,
+        verifying_key: &[u8],
+        options: &ProofOptions,
+    ) -> Result<bool, ProofVerificationError> {
+        check_data_integrity_proof(proof, ECDSA_JCS_2019_CRYPTOSUITE)?;
+        check_proof_context(proof, data.get_context())?;
+        check_proof_purpose(proof, options)?;
+        check_challenge_and_domain(proof, options)?;
+        check_previous_proof(proof, options)?;
         let transformed_data = self
             .transform(data, options)
-            .map_err(|_| ProofVerificationError::BadTransformationError)?;
+            .map_err(ProofVerificationError::BadTransformationError)?;
         let hash_data = self
-            .hash(&transformed_data, options)
-            .map_err(|_| ProofVerificationError::BadHashingError)?;
-        let signature = Signature::from_slice(proof.get_proof_value())
-            .map_err(|_| ProofVerificationError::MalformedProofError)?;
-        let public_key = VerifyingKey::from_sec1_bytes(verifying_key)
-            .map_err(|_| ProofVerificationError::InvalidPublicKeyError)?;
+            .hash(&transformed_data, proof)
+            .map_err(ProofVerificationError::BadHashingError)?;
+
+        let signature =
+            p256::ecdsa::Signature::from_slice(proof.get_proof_value()).map_err(|_| {
+                ProofVerificationError::MalformedProofError(format!(
+                    "{} bytes do not hold a P-256 signature",
+                    proof.get_proof_value().len()
+                ))
+            })?;
+        let public_key =
+            p256::ecdsa::VerifyingKey::from_sec1_bytes(verifying_key).map_err(|_| {
+                ProofVerificationError::InvalidPublicKeyError(format!(
+                    "{} bytes do not hold a SEC1 encoded P-256 point",
+                    verifying_key.len()
+                ))
+            })?;
 
         Ok(public_key.verify(&hash_data, &signature).is_ok())
     }
 }
+
+// The JCS cryptosuites copy the document's @context into the proof configuration, so the
+// context is covered by the signature.
+fn new_proof_configuration(
+    type_: &str,
+    cryptosuite: Option<&str>,
+    context: Option<&Vec<URL>>,
+    options: &ProofOptions,
+) -> Proof {
+    let mut proof = Proof::new(
+        type_.to_string(),
+        cryptosuite.map(|cryptosuite| cryptosuite.to_string()),
+        options.created,
+        options.verification_method.get_id().clone(),
+        options.proof_purpose.clone(),
+        options.challenge.clone(),
+        options.domain.clone(),
+        Vec::new(),
+    );
+    proof.context = context.cloned();
+    proof.id = options.id.clone();
+    proof.previous_proof = options
+        .previous_proof
+        .as_ref()
+        .and_then(|previous_proof| previous_proof.id.clone());
+    proof
+}
+
+// A chained proof signs the credential together with the proof it follows, as the Data Integrity
+// proof chain algorithm requires, so that proof cannot be removed or swapped.
+fn transform_credential(
+    data: &Credential,
+    options: &ProofOptions,
+) -> Result<Vec<u8>, ProofGenerationError> {
+    let credential_string = match &options.previous_proof {
+        Some(previous_proof) => canonicalize(&VerifiableCredential::new(
+            data.clone(),
+            vec![previous_proof.clone()],
+        )),
+        None => canonicalize(data),
+    }
+    .map_err(|e| {
+        ProofGenerationError::SerializationError(format!(
+            "could not canonicalize credential {}: {}",
+            data.get_id(),
+            e
+        ))
+    })?;
+    Ok(credential_string.as_bytes().to_vec())
+}
+
+// Follows the Data Integrity hashing algorithm: the canonical proof configuration (every proof
+// property except the proof value) and the transformed document are hashed separately and
+// concatenated, so tampering with the proof metadata invalidates the signature.
+fn hash_data_integrity(
+    transformed_data: &[u8],
+    proof_config: &Proof,
+    hash: fn(&[u8]) -> Vec<u8>,
+) -> Result<Vec<u8>, ProofGenerationError> {
+    let mut proof_config = serde_json::to_value(proof_config).map_err(|e| {
+        ProofGenerationError::SerializationError(format!(
+            "could not serialize proof configuration: {}",
+            e
+        ))
+    })?;
+    if let Some(proof_config) = proof_config.as_object_mut() {
+        proof_config.remove("proofValue");
+    }
+    let canonical_proof_config = canonicalize(&proof_config).map_err(|e| {
+        ProofGenerationError::SerializationError(format!(
+            "could not canonicalize proof configuration: {}",
+            e
+        ))
+    })?;
+    let mut hash_data = hash(canonical_proof_config.as_bytes());
+    hash_data.extend_from_slice(&hash(transformed_data));
+    Ok(hash_data)
+}
+
+fn blake3_hash(data: &[u8]) -> Vec<u8> {
+    blake3::hash(data).as_bytes().to_vec()
+}
+
+fn sha256_hash(data: &[u8]) -> Vec<u8> {
+    Sha256::digest(data).to_vec()
+}
+
+// Describes an expected and a found value for mismatch errors.
+fn describe_mismatch(expected: Option<&str>, found: Option<&str>) -> String {
+    format!(
+        "expected {}, found {}",
+        expected.unwrap_or("none"),
+        found.unwrap_or("none")
+    )
+}
+
+fn check_data_integrity_proof(
+    proof: &Proof,
+    cryptosuite: &str,
+) -> Result<(), ProofVerificationError> {
+    if proof.get_type() != DATA_INTEGRITY_PROOF_TYPE
+        || proof.get_cryptosuite().as_deref() != Some(cryptosuite)
+    {
+        return Err(ProofVerificationError::MalformedProofError(format!(
+            "expected a {} with cryptosuite {}, found a {} with cryptosuite {}",
+            DATA_INTEGRITY_PROOF_TYPE,
+            cryptosuite,
+            proof.get_type(),
+            proof.get_cryptosuite().as_deref().unwrap_or("none")
+        )));
+    }
+    Ok(())
+}
+
+fn check_proof_purpose(
+    proof: &Proof,
+    options: &ProofOptions,
+) -> Result<(), ProofVerificationError> {
+    if *proof.get_proof_purpose() != options.proof_purpose {
+        return Err(ProofVerificationError::MismatchedProofPurposeError(
+            describe_mismatch(
+                Some(&options.proof_purpose),
+                Some(proof.get_proof_purpose()),
+            ),
+        ));
+    }
+    Ok(())
+}
+
+// A proof that records a @context only verifies a document whose @context starts with it.
+fn check_proof_context(proof: &Proof, context: &[URL]) -> Result<(), ProofVerificationError> {
+    match &proof.context {
+        Some(proof_context) if !context.starts_with(proof_context) => {
+            Err(ProofVerificationError::MismatchedContextError(format!(
+                "document @context does not start with the proof's {} values",
+                proof_context.len()
+            )))
+        }
+        _ => Ok(()),
+    }
+}
+
+// A challenge or domain is only required when the verifier asked for one, as is the case for
+// presentations. Assertion proofs on credentials are not bound to either.
+fn check_challenge_and_domain(
+    proof: &Proof,
+    options: &ProofOptions,
+) -> Result<(), ProofVerificationError> {
+    if let Some(challenge) = &options.challenge {
+        if proof.get_challenge().as_ref() != Some(challenge) {
+            return Err(ProofVerificationError::MismatchedChallengeError(
+                describe_mismatch(Some(challenge), proof.get_challenge().as_deref()),
+            ));
+        }
+    }
+    if let Some(domain) = &options.domain {
+        if proof.get_domain().as_ref() != Some(domain) {
+            return Err(ProofVerificationError::MismatchedDomainError(
+                describe_mismatch(Some(domain), proof.get_domain().as_deref()),
+            ));
+        }
+    }
+    Ok(())
+}
+
+fn check_previous_proof(
+    proof: &Proof,
+    options: &ProofOptions,
+) -> Result<(), ProofVerificationError> {
+    let previous_proof_id = options
+        .previous_proof
+        .as_ref()
+        .and_then(|previous_proof| previous_proof.id.as_ref());
+    if proof.get_previous_proof().as_ref() != previous_proof_id {
+        return Err(ProofVerificationError::MismatchedPreviousProofError(
+            describe_mismatch(
+                previous_proof_id.map(|id| id.get_str()),
+                proof.get_previous_proof().as_ref().map(|id| id.get_str()),
+            ),
+        ));
+    }
+    Ok(())
+}
+
+fn check_key_type(key: &dyn KeyHandle, key_type: KeyType) -> Result<(), ProofGenerationError> {
+    if key.get_key_type() != key_type {
+        return Err(ProofGenerationError::InvalidSigningKeyError(format!(
+            "expected a {:?} key, found a {:?} key",
+            key_type,
+            key.get_key_type()
+        )));
+    }
+    Ok(())
+}
+
+fn verify_ecdsa_secp256k1(
+    hash_data: &[u8],
+    proof_value: &[u8],
+    verifying_key: &[u8],
+) -> Result<bool, ProofVerificationError> {
+    let signature = Signature::from_slice(proof_value).map_err(|_| {
+        ProofVerificationError::MalformedProofError(format!(
+            "{} bytes do not hold a secp256k1 signature",
+            proof_value.len()
+        ))
+    })?;
+    let public_key = VerifyingKey::from_sec1_bytes(verifying_key).map_err(|_| {
+        ProofVerificationError::InvalidPublicKeyError(format!(
+            "{} bytes do not hold a SEC1 encoded secp256k1 point",
+            verifying_key.len()
+        ))
+    })?;
+
+    Ok(public_key.verify(hash_data, &signature).is_ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn canonicalize_json(json: &str) -> String {
+        let value: serde_json::Value = serde_json::from_str(json).unwrap();
+        canonicalize(&value).unwrap()
+    }
+
+    #[test]
+    fn canonicalizes_rfc_8785_number_samples() {
+        // The IEEE 754 sample values from RFC 8785 appendix B.
+        let samples: [(u64, &str); 23] = [
+            (0x0000000000000000, "0"),
+            (0x8000000000000000, "0"),
+            (0x0000000000000001, "5e-324"),
+            (0x8000000000000001, "-5e-324"),
+            (0x7fefffffffffffff, "1.7976931348623157e+308"),
+            (0xffefffffffffffff, "-1.7976931348623157e+308"),
+            (0x4340000000000000, "9007199254740992"),
+            (0xc340000000000000, "-9007199254740992"),
+            (0x4430000000000000, "295147905179352830000"),
+            (0x44b52d02c7e14af5, "9.999999999999997e+22"),
+            (0x44b52d02c7e14af6, "1e+23"),
+            (0x44b52d02c7e14af7, "1.0000000000000001e+23"),
+            (0x444b1ae4d6e2ef4e, "999999999999999700000"),
+            (0x444b1ae4d6e2ef4f, "999999999999999900000"),
+            (0x444b1ae4d6e2ef50, "1e+21"),
+            (0x3eb0c6f7a0b5ed8c, "9.999999999999997e-7"),
+            (0x3eb0c6f7a0b5ed8d, "0.000001"),
+            (0x41b3de4355555553, "333333333.3333332"),
+            (0x41b3de4355555554, "333333333.33333325"),
+            (0x41b3de4355555555, "333333333.3333333"),
+            (0x41b3de4355555556, "333333333.3333334"),
+            (0x41b3de4355555557, "333333333.33333343"),
+            (0xbecbf647612f3696, "-0.0000033333333333333333"),
+        ];
+        for (bits, expected) in samples {
+            let number = serde_json::Number::from_f64(f64::from_bits(bits)).unwrap();
+            let mut canonical = String::new();
+            write_canonical_number(&number, &mut canonical);
+            assert_eq!(canonical, expected, "{:#018x}", bits);
+        }
+        assert_eq!(canonicalize_json("1e21"), "1e+21");
+        assert_eq!(canonicalize_json("-0"), "0");
+        assert_eq!(
+            canonicalize_json("1424953923781206.2"),
+            "1424953923781206.2"
+        );
+    }
+
+    #[test]
+    fn sorts_properties_by_utf16_code_units() {
+        // The sorting example from RFC 8785 section 3.2.3. U+1F600 sorts before U+FB33 because
+        // its UTF-16 high surrogate is lower.
+        let json = r#"{
+            "\u20ac": "Euro Sign",
+            "\r": "Carriage Return",
+            "\ufb33": "Hebrew Letter Dalet With Dagesh",
+            "1": "One",
+            "\ud83d\ude00": "Emoji: Grinning Face",
+            "\u0080": "Control",
+            "\u00f6": "Latin Small Letter O With Diaeresis"
+        }"#;
+        let expected = concat!(
+            "{\"\\r\":\"Carriage Return\",\"1\":\"One\",\"\u{80}\":\"Control\",",
+            "\"\u{f6}\":\"Latin Small Letter O With Diaeresis\",\"\u{20ac}\":\"Euro Sign\",",
+            "\"\u{1f600}\":\"Emoji: Grinning Face\",",
+            "\"\u{fb33}\":\"Hebrew Letter Dalet With Dagesh\"}"
+        );
+        assert_eq!(canonicalize_json(json), expected);
+    }
+
+    #[test]
+    fn canonicalizes_rfc_8785_example() {
+        // The example from RFC 8785 section 3.2.2, covering number formatting and string escaping.
+        let json = r#"{
+            "numbers": [333333333.33333329, 1E30, 4.50, 2e-3, 0.000000000000000000000000001],
+            "string": "\u20ac$\u000F\u000aA'\u0042\u0022\u005c\\\"\/",
+            "literals": [null, true, false]
+        }"#;
+        let expected = concat!(
+            "{\"literals\":[null,true,false],",
+            "\"numbers\":[333333333.3333333,1e+30,4.5,0.002,1e-27],",
+            "\"string\":\"\u{20ac}$\\u000f\\nA'B\\\"\\\\\\\\\\\"/\"}"
+        );
+        assert_eq!(canonicalize_json(json), expected);
+    }
+
+    #[test]
+    fn escapes_control_characters() {
+        let value = serde_json::json!("\u{1}\u{8}\u{9}\u{a}\u{c}\u{d}\u{1f} \u{7f}");
+        assert_eq!(
+            canonicalize(&value).unwrap(),
+            "\"\\u0001\\b\\t\\n\\f\\r\\u001f \u{7f}\""
+        );
+    }
+
+    #[test]
+    fn checks_challenge_and_domain_only_when_requested() {
+        let signing_key = SigningKey::from_slice(&[1u8; 32]).unwrap();
+        let public_key = VerifyingKey::from(&signing_key).to_sec1_bytes();
+        let holder = did_key::did_from_secp256k1_public_key(&public_key).unwrap();
+        let verification_method = did_key::resolve_verification_method(&holder).unwrap();
+        let options = |domain: Option<&str>, challenge: Option<&str>| {
+            ProofOptions::new(
+                verification_method.clone(),
+                AUTHENTICATION_PROOF_PURPOSE.to_string(),
+                Utc::now(),
+                domain.map(|domain| domain.to_string()),
+                challenge.map(|challenge| challenge.to_string()),
+            )
+        };
+        let proof = |domain: Option<&str>, challenge: Option<&str>| {
+            new_proof_configuration(
+                "DataIntegrityProof",
+                None,
+                None,
+                &options(domain, challenge),
+            )
+        };
+
+        assert!(check_challenge_and_domain(&proof(None, None), &options(None, None)).is_ok());
+        assert!(check_challenge_and_domain(
+            &proof(Some("did:example:verifier"), Some("1234")),
+            &options(None, None)
+        )
+        .is_ok());
+        assert!(check_challenge_and_domain(
+            &proof(Some("did:example:verifier"), Some("1234")),
+            &options(Some("did:example:verifier"), Some("1234"))
+        )
+        .is_ok());
+        assert!(matches!(
+            check_challenge_and_domain(
+                &proof(Some("did:example:verifier"), None),
+                &options(Some("did:example:verifier"), Some("1234"))
+            ),
+            Err(ProofVerificationError::MismatchedChallengeError(_))
+        ));
+        assert!(matches!(
+            check_challenge_and_domain(
+                &proof(Some("did:example:other"), Some("1234")),
+                &options(Some("did:example:verifier"), Some("1234"))
+            ),
+            Err(ProofVerificationError::MismatchedDomainError(_))
+        ));
+    }
+
+    // The unsecured credential from the test vectors in the W3C vc-di-eddsa and vc-di-ecdsa
+    // specifications.
+    fn new_specification_credential() -> serde_json::Value {
+        serde_json::json!({
+            "@context": [
+                "https://www.w3.org/ns/credentials/v2",
+                "https://www.w3.org/ns/credentials/examples/v2"
+            ],
+            "id": "urn:uuid:58172aac-d8ba-11ed-83dd-0b3aef56cc33",
+            "type": ["VerifiableCredential", "AlumniCredential"],
+            "name": "Alumni Credential",
+            "description": "A minimum viable example of an Alumni Credential.",
+            "issuer": "https://vc.example/issuers/5678",
+            "validFrom": "2023-01-01T00:00:00Z",
+            "credentialSubject": {
+                "id": "did:example:abcdefgh",
+                "alumniOf": "The School of Examples"
+            }
+        })
+    }
+
+    fn new_specification_proof_options(public_key_multibase: &str) -> ProofOptions {
+        let did = URL::new(&format!("did:key:{}", public_key_multibase)).unwrap();
+        ProofOptions::new(
+            did_key::resolve_verification_method(&did).unwrap(),
+            ASSERTION_METHOD_PROOF_PURPOSE.to_string(),
+            "2023-02-24T23:36:38Z".parse().unwrap(),
+            None,
+            None,
+        )
+    }
+
+    // Adds a proof from the specification to its unsecured credential.
+    fn new_specification_verifiable_credential(
+        cryptosuite: &str,
+        public_key_multibase: &str,
+        proof_value: &str,
+    ) -> VerifiableCredential {
+        let mut verifiable_credential = new_specification_credential();
+        verifiable_credential["proof"] = serde_json::json!({
+            "type": "DataIntegrityProof",
+            "cryptosuite": cryptosuite,
+            "created": "2023-02-24T23:36:38Z",
+            "verificationMethod": format!(
+                "did:key:{}#{}",
+                public_key_multibase, public_key_multibase
+            ),
+            "proofPurpose": "assertionMethod",
+            "@context": [
+                "https://www.w3.org/ns/credentials/v2",
+                "https://www.w3.org/ns/credentials/examples/v2"
+            ],
+            "proofValue": proof_value
+        });
+        serde_json::from_value(verifiable_credential).unwrap()
+    }
+
+    const EDDSA_SPECIFICATION_SECRET_KEY: &str = "z3u2en7t5LR2WtQH5PfFqMqwVHBeXouLzo6haApm8XHqvjxq";
+    const EDDSA_SPECIFICATION_PUBLIC_KEY: &str = "z6MkrJVnaZkeFzdQyMZu1cgjg7k1pZZ6pvBQ7XJPt4swbTQ2";
+    const EDDSA_SPECIFICATION_PROOF_VALUE: &str =
+        "z2HnFSSPPBzR36zdDgK8PbEHeXbR56YF24jwMpt3R1eHXQzJDMWS93FCzpvJpwTWd3GAVFuUfjoJdcnTMuVor51aX";
+
+    #[test]
+    fn reproduces_eddsa_jcs_2022_specification_proof() {
+        let credential: Credential =
+            serde_json::from_value(new_specification_credential()).unwrap();
+        let secret_key = multibase::decode(EDDSA_SPECIFICATION_SECRET_KEY).unwrap();
+        // The secret key is prefixed with the ed25519-priv multicodec.
+        let secret_key = secret_key.strip_prefix(&[0x80, 0x26]).unwrap();
+        let key_handle = InMemoryKeyHandle::new(KeyType::Ed25519, secret_key).unwrap();
+        let proof = EddsaJcs2022::new()
+            .generate_proof(
+                &credential,
+                &key_handle,
+                &new_specification_proof_options(EDDSA_SPECIFICATION_PUBLIC_KEY),
+            )
+            .unwrap();
+
+        let expected = new_specification_verifiable_credential(
+            EDDSA_JCS_2022_CRYPTOSUITE,
+            EDDSA_SPECIFICATION_PUBLIC_KEY,
+            EDDSA_SPECIFICATION_PROOF_VALUE,
+        );
+        assert_eq!(
+            serde_json::to_string(&proof).unwrap(),
+            serde_json::to_string(&expected.get_proof()[0]).unwrap()
+        );
+    }
+
+    #[test]
+    fn verifies_eddsa_jcs_2022_specification_example() {
+        let suite = EddsaJcs2022::new();
+        let options = new_specification_proof_options(EDDSA_SPECIFICATION_PUBLIC_KEY);
+        let (_key_type, public_key) =
+            multibase::decode_public_key(EDDSA_SPECIFICATION_PUBLIC_KEY).unwrap();
+        let verifiable_credential = new_specification_verifiable_credential(
+            EDDSA_JCS_2022_CRYPTOSUITE,
+            EDDSA_SPECIFICATION_PUBLIC_KEY,
+            EDDSA_SPECIFICATION_PROOF_VALUE,
+        );
+        let credential = verifiable_credential.get_credential();
+        let proof = &verifiable_credential.get_proof()[0];
+        assert!(suite
+            .verify_proof(credential, proof, &public_key, &options)
+            .unwrap());
+
+        let mut tampered_credential = new_specification_credential();
+        tampered_credential["credentialSubject"]["alumniOf"] = "Another School".into();
+        let tampered_credential: Credential = serde_json::from_value(tampered_credential).unwrap();
+        assert!(!suite
+            .verify_proof(&tampered_credential, proof, &public_key, &options)
+            .unwrap());
+
+        let mut tampered_proof = serde_json::to_value(proof).unwrap();
+        tampered_proof["@context"] = serde_json::json!(["https://www.w3.org/ns/credentials/v2"]);
+        let tampered_proof: Proof = serde_json::from_value(tampered_proof).unwrap();
+        assert!(!suite
+            .verify_proof(credential, &tampered_proof, &public_key, &options)
+            .unwrap());
+    }
+
+    #[test]
+    fn reads_proof_values_stored_as_byte_arrays() {
+        let verifiable_credential = new_specification_verifiable_credential(
+            EDDSA_JCS_2022_CRYPTOSUITE,
+            EDDSA_SPECIFICATION_PUBLIC_KEY,
+            EDDSA_SPECIFICATION_PROOF_VALUE,
+        );
+        let proof = &verifiable_credential.get_proof()[0];
+        let mut legacy_proof = serde_json::to_value(proof).unwrap();
+        legacy_proof["proofValue"] = serde_json::json!(proof.get_proof_value());
+        let legacy_proof: Proof = serde_json::from_value(legacy_proof).unwrap();
+        assert!(legacy_proof == *proof);
+        assert_eq!(
+            serde_json::to_value(&legacy_proof).unwrap()["proofValue"],
+            EDDSA_SPECIFICATION_PROOF_VALUE
+        );
+    }
+
+    fn decode_hex(data: &str) -> Vec<u8> {
+        (0..data.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&data[i..i + 2], 16).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn reproduces_rfc_6979_p256_signatures() {
+        // The private key and (message, r || s) pairs from RFC 6979 appendix A.2.5 with SHA-256.
+        let secret_key =
+            decode_hex("c9afa9d845ba75166b5c215767b1d6934e50c3db36e89b127b8a622b120f6721");
+        let vectors = [
+            (
+                "sample",
+                "efd48b2aacb6a8fd1140dd9cd45e81d69d2c877b56aaf991c34d0ea84eaf3716f7cb1c942d657c41d436c7a1b6e29f65f3e900dbb9aff4064dc4ab2f843acda8",
+            ),
+            (
+                "test",
+                "f1abb023518351cd71d881567b1ea663ed3efcf6c5132b354f28d3b0b7d38367019f4113742a2b14bd25926b49c649155f267e60d3814b4c0cc84250e46f0083",
+            ),
+        ];
+        let public_key = p256::ecdsa::SigningKey::from_slice(&secret_key)
+            .unwrap()
+            .verifying_key()
+            .to_encoded_point(false);
+        assert_eq!(
+            public_key.as_bytes(),
+            decode_hex("0460fed4ba255a9d31c961eb74c6356d68c049b8923b61fa6ce669622e60f29fb67903fe1008b8bc99a41ae9e95628bc64f2f1b20c2d7e9f5177a3c294d4462299")
+        );
+
+        let suite = EcdsaJcs2019::new();
+        let credential: Credential =
+            serde_json::from_value(new_specification_credential()).unwrap();
+        let key_handle = InMemoryKeyHandle::new(KeyType::P256, &secret_key).unwrap();
+        for (message, signature) in vectors {
+            let proof_config = suite
+                .configure_proof(
+                    &credential,
+                    &new_specification_proof_options(ECDSA_SPECIFICATION_PUBLIC_KEY),
+                )
+                .unwrap();
+            let proof = suite
+                .prove(message.as_bytes(), &key_handle, proof_config)
+                .unwrap();
+            assert_eq!(
+                *proof.get_proof_value(),
+                decode_hex(signature),
+                "{}",
+                message
+            );
+        }
+    }
+
+    const ECDSA_SPECIFICATION_SECRET_KEY: &str = "z42twTcNeSYcnqg1FLuSFs2bsGH3ZqbRHFmvS9XMsYhjxvHN";
+    const ECDSA_SPECIFICATION_PUBLIC_KEY: &str =
+        "zDnaepBuvsQ8cpsWrVKw8fbpGpvPeNSjVPTWoq6cRqaYzBKVP";
+    const ECDSA_SPECIFICATION_PROOF_VALUE: &str =
+        "z5ptCet75SaEgzG4v4zJhbJtfNi74Wv7Fq15hhKouJQQjEPQvPZKaYxcMXAMLPQS2FXrkCWokNJkFVkwxNzZfD5oT";
+
+    #[test]
+    fn reproduces_ecdsa_jcs_2019_specification_proof() {
+        let credential: Credential =
+            serde_json::from_value(new_specification_credential()).unwrap();
+        let secret_key = multibase::decode(ECDSA_SPECIFICATION_SECRET_KEY).unwrap();
+        // The secret key is prefixed with the p256-priv multicodec.
+        let secret_key = secret_key.strip_prefix(&[0x86, 0x26]).unwrap();
+        let public_key = p256::ecdsa::SigningKey::from_slice(secret_key)
+            .unwrap()
+            .verifying_key()
+            .to_encoded_point(true);
+        assert_eq!(
+            multibase::encode_public_key(KeyType::P256, public_key.as_bytes()),
+            ECDSA_SPECIFICATION_PUBLIC_KEY
+        );
+
+        let key_handle = InMemoryKeyHandle::new(KeyType::P256, secret_key).unwrap();
+        let proof = EcdsaJcs2019::new()
+            .generate_proof(
+                &credential,
+                &key_handle,
+                &new_specification_proof_options(ECDSA_SPECIFICATION_PUBLIC_KEY),
+            )
+            .unwrap();
+
+        let expected = new_specification_verifiable_credential(
+            ECDSA_JCS_2019_CRYPTOSUITE,
+            ECDSA_SPECIFICATION_PUBLIC_KEY,
+            ECDSA_SPECIFICATION_PROOF_VALUE,
+        );
+        assert_eq!(
+            serde_json::to_string(&proof).unwrap(),
+            serde_json::to_string(&expected.get_proof()[0]).unwrap()
+        );
+    }
+
+    #[test]
+    fn verifies_ecdsa_jcs_2019_specification_example() {
+        let suite = EcdsaJcs2019::new();
+        let options = new_specification_proof_options(ECDSA_SPECIFICATION_PUBLIC_KEY);
+        let (_key_type, public_key) =
+            multibase::decode_public_key(ECDSA_SPECIFICATION_PUBLIC_KEY).unwrap();
+        let verifiable_credential = new_specification_verifiable_credential(
+            ECDSA_JCS_2019_CRYPTOSUITE,
+            ECDSA_SPECIFICATION_PUBLIC_KEY,
+            ECDSA_SPECIFICATION_PROOF_VALUE,
+        );
+        let credential = verifiable_credential.get_credential();
+        let proof = &verifiable_credential.get_proof()[0];
+        assert!(suite
+            .verify_proof(credential, proof, &public_key, &options)
+            .unwrap());
+
+        let mut tampered_credential = new_specification_credential();
+        tampered_credential["credentialSubject"]["alumniOf"] = "Another School".into();
+        let tampered_credential: Credential = serde_json::from_value(tampered_credential).unwrap();
+        assert!(!suite
+            .verify_proof(&tampered_credential, proof, &public_key, &options)
+            .unwrap());
+
+        let mut tampered_proof = serde_json::to_value(proof).unwrap();
+        tampered_proof["created"] = serde_json::json!("2024-01-01T00:00:00Z");
+        let tampered_proof: Proof = serde_json::from_value(tampered_proof).unwrap();
+        assert!(!suite
+            .verify_proof(credential, &tampered_proof, &public_key, &options)
+            .unwrap_or(false));
+    }
+}
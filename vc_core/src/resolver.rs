@@ -0,0 +1,318 @@
+use super::*;
+
+#[derive(Debug)]
+pub enum DIDResolutionError {
+    UnsupportedMethod(String),
+    InvalidDID(String),
+    NotFound(String),
+    InvalidDIDDocument(String),
+    ResolutionError(String),
+}
+
+impl Error for DIDResolutionError {}
+
+impl fmt::Display for DIDResolutionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnsupportedMethod(e) => write!(f, "Unsupported DID method: {}", e),
+            Self::InvalidDID(e) => write!(f, "Invalid DID: {}", e),
+            Self::NotFound(e) => write!(f, "DID not found: {}", e),
+            Self::InvalidDIDDocument(e) => write!(f, "Invalid DID document: {}", e),
+            Self::ResolutionError(e) => write!(f, "Resolution error: {}", e),
+        }
+    }
+}
+
+pub trait DIDResolver {
+    fn resolve(&self, did: &URL) -> Result<DIDDocument, DIDResolutionError>;
+
+    fn resolve_verification_method(
+        &self,
+        verification_method_id: &URL,
+    ) -> Result<VerificationMethod, DIDResolutionError> {
+        let did = match verification_method_id.did() {
+            Some(did) => {
+                URL::new(did).map_err(|e| DIDResolutionError::InvalidDID(e.to_string()))?
+            }
+            None => verification_method_id.clone(),
+        };
+        let did_document = self.resolve(&did)?;
+        did_document
+            .get_verification_methods()
+            .iter()
+            .find(|verification_method| verification_method.get_id() == verification_method_id)
+            .cloned()
+            .ok_or_else(|| {
+                DIDResolutionError::NotFound(format!(
+                    "Verification method {} not found in {}.",
+                    verification_method_id, did
+                ))
+            })
+    }
+}
+
+pub struct KeyDIDResolver;
+
+impl DIDResolver for KeyDIDResolver {
+    fn resolve(&self, did: &URL) -> Result<DIDDocument, DIDResolutionError> {
+        if did.did_method() != Some("key") {
+            return Err(DIDResolutionError::UnsupportedMethod(did.to_string()));
+        }
+        did_key::resolve(did).map_err(|e| DIDResolutionError::InvalidDID(e.to_string()))
+    }
+}
+
+#[cfg(feature = "did_web")]
+pub struct WebDIDResolver {
+    scheme: String,
+    agent: ureq::Agent,
+}
+
+#[cfg(feature = "did_web")]
+impl WebDIDResolver {
+    pub fn new() -> Self {
+        Self {
+            scheme: "https".to_string(),
+            agent: http::new_agent(false),
+        }
+    }
+
+    // did:web mandates HTTPS on public hosts; plain HTTP and private hosts are only for local
+    // stand-in servers.
+    pub fn insecure() -> Self {
+        Self {
+            scheme: "http".to_string(),
+            agent: http::new_agent(true),
+        }
+    }
+
+    pub fn get_document_url(&self, did: &URL) -> Result<String, DIDResolutionError> {
+        if did.did_method() != Some("web") {
+            return Err(DIDResolutionError::UnsupportedMethod(did.to_string()));
+        }
+        let method_specific_id = did
+            .did_method_specific_id()
+            .expect("A did:web should have a method-specific id.");
+        let mut segments = method_specific_id.split(':');
+        let domain = percent_decode(
+            segments
+                .next()
+                .expect("Split should yield at least one segment."),
+        )
+        .ok_or_else(|| DIDResolutionError::InvalidDID(did.to_string()))?;
+        // The decoded domain must stay a host and optional port, not smuggle in a path or userinfo.
+        if domain.is_empty()
+            || !domain
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | ':' | '[' | ']'))
+        {
+            return Err(DIDResolutionError::InvalidDID(did.to_string()));
+        }
+        let path: Vec<&str> = segments.collect();
+        if path.is_empty() {
+            Ok(format!("{}://{}/.well-known/did.json", self.scheme, domain))
+        } else {
+            Ok(format!(
+                "{}://{}/{}/did.json",
+                self.scheme,
+                domain,
+                path.join("/")
+            ))
+        }
+    }
+}
+
+// Decodes every percent-encoded octet, failing on malformed escapes or invalid UTF-8.
+#[cfg(feature = "did_web")]
+fn percent_decode(value: &str) -> Option<String> {
+    let bytes = value.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' {
+            let hex = std::str::from_utf8(bytes.get(i + 1..i + 3)?).ok()?;
+            decoded.push(u8::from_str_radix(hex, 16).ok()?);
+            i += 3;
+        } else {
+            decoded.push(bytes[i]);
+            i += 1;
+        }
+    }
+    String::from_utf8(decoded).ok()
+}
+
+#[cfg(feature = "did_web")]
+impl Default for WebDIDResolver {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(feature = "did_web")]
+impl DIDResolver for WebDIDResolver {
+    fn resolve(&self, did: &URL) -> Result<DIDDocument, DIDResolutionError> {
+        let document_url = self.get_document_url(did)?;
+        let response = self.agent.get(&document_url).call().map_err(|e| match e {
+            ureq::Error::Status(404, _) => DIDResolutionError::NotFound(did.to_string()),
+            e => DIDResolutionError::ResolutionError(e.to_string()),
+        })?;
+        let document_json = response
+            .into_string()
+            .map_err(|e| DIDResolutionError::ResolutionError(e.to_string()))?;
+        let did_document = serde_json::from_str::<DIDDocument>(&document_json)
+            .map_err(|e| DIDResolutionError::InvalidDIDDocument(e.to_string()))?;
+        if did_document.get_id() != did {
+            return Err(DIDResolutionError::InvalidDIDDocument(format!(
+                "Document id {} does not match {}.",
+                did_document.get_id(),
+                did
+            )));
+        }
+
+        Ok(did_document)
+    }
+}
+
+// Tries each resolver in order, skipping those that do not support the DID's method.
+pub struct ChainedDIDResolver<'a> {
+    resolvers: Vec<&'a dyn DIDResolver>,
+}
+
+impl<'a> ChainedDIDResolver<'a> {
+    pub fn new(resolvers: Vec<&'a dyn DIDResolver>) -> Self {
+        Self { resolvers }
+    }
+}
+
+impl<'a> DIDResolver for ChainedDIDResolver<'a> {
+    fn resolve(&self, did: &URL) -> Result<DIDDocument, DIDResolutionError> {
+        let mut last_error = DIDResolutionError::UnsupportedMethod(did.to_string());
+        for resolver in &self.resolvers {
+            match resolver.resolve(did) {
+                Ok(did_document) => return Ok(did_document),
+                Err(DIDResolutionError::UnsupportedMethod(_)) => continue,
+                Err(e) => last_error = e,
+            }
+        }
+        Err(last_error)
+    }
+}
+
+#[cfg(all(test, feature = "did_web"))]
+mod tests {
+    use super::*;
+    use std::thread;
+    use tiny_http::{Header, Response, Server};
+
+    // Serves a DID document for each of the given DID paths (such as "" or ":user:alice") under
+    // did:web:127.0.0.1%3A<port>, with `id_suffix` appended to each document's id, until
+    // `requests` requests have been answered. Returns the domain DID.
+    fn serve_did_documents(
+        did_paths: &[&str],
+        id_suffix: &str,
+        requests: usize,
+    ) -> (String, thread::JoinHandle<()>) {
+        let server = Server::http("127.0.0.1:0").unwrap();
+        let port = server.server_addr().to_ip().unwrap().port();
+        let domain_did = format!("did:web:127.0.0.1%3A{}", port);
+        let documents: Vec<(String, String)> = did_paths
+            .iter()
+            .map(|did_path| {
+                let url_path = match did_path.replace(':', "/") {
+                    path if path.is_empty() => "/.well-known/did.json".to_string(),
+                    path => format!("{}/did.json", path),
+                };
+                let document = serde_json::json!({
+                    "@context": ["https://www.w3.org/ns/did/v1"],
+                    "id": format!("{}{}{}", domain_did, did_path, id_suffix),
+                });
+                (url_path, document.to_string())
+            })
+            .collect();
+        let handle = thread::spawn(move || {
+            for request in server.incoming_requests().take(requests) {
+                let document = documents
+                    .iter()
+                    .find(|(url_path, _)| url_path == request.url())
+                    .map(|(_, document)| document.clone());
+                let result = match document {
+                    Some(document) => {
+                        let content_type =
+                            Header::from_bytes("Content-Type", "application/did+json").unwrap();
+                        request.respond(Response::from_string(document).with_header(content_type))
+                    }
+                    None => request.respond(Response::empty(404)),
+                };
+                result.unwrap();
+            }
+        });
+        (domain_did, handle)
+    }
+
+    #[test]
+    fn gets_document_urls() {
+        let resolver = WebDIDResolver::new();
+        let url = |did: &str| resolver.get_document_url(&URL::new(did).unwrap());
+        assert_eq!(
+            url("did:web:w3c-ccg.github.io").unwrap(),
+            "https://w3c-ccg.github.io/.well-known/did.json"
+        );
+        assert_eq!(
+            url("did:web:w3c-ccg.github.io:user:alice").unwrap(),
+            "https://w3c-ccg.github.io/user/alice/did.json"
+        );
+        assert_eq!(
+            url("did:web:example.com%3A3000").unwrap(),
+            "https://example.com:3000/.well-known/did.json"
+        );
+        assert_eq!(
+            url("did:web:%65xample.com").unwrap(),
+            "https://example.com/.well-known/did.json"
+        );
+        for did in [
+            "did:web:example.com%2Fpath",
+            "did:web:evil.example%40example.com",
+            "did:web:example.com%ff",
+        ] {
+            assert!(url(did).is_err(), "{}", did);
+        }
+    }
+
+    #[test]
+    fn resolves_documents_from_a_web_server() {
+        let (domain_did, handle) = serve_did_documents(&["", ":user:alice"], "", 3);
+        let resolver = WebDIDResolver::insecure();
+        for did in [domain_did.clone(), format!("{}:user:alice", domain_did)] {
+            let did = URL::new(&did).unwrap();
+            assert!(resolver.resolve(&did).unwrap().get_id() == &did);
+        }
+        let unknown_did = URL::new(&format!("{}:user:bob", domain_did)).unwrap();
+        assert!(matches!(
+            resolver.resolve(&unknown_did),
+            Err(DIDResolutionError::NotFound(_))
+        ));
+        handle.join().unwrap();
+    }
+
+    #[test]
+    fn rejects_documents_for_another_did() {
+        let (domain_did, handle) = serve_did_documents(&[""], ":other", 1);
+        let did = URL::new(&domain_did).unwrap();
+        assert!(matches!(
+            WebDIDResolver::insecure().resolve(&did),
+            Err(DIDResolutionError::InvalidDIDDocument(_))
+        ));
+        handle.join().unwrap();
+    }
+
+    #[test]
+    fn rejects_private_hosts() {
+        let did = URL::new("did:web:localhost%3A8080").unwrap();
+        match WebDIDResolver::new().resolve(&did) {
+            Err(DIDResolutionError::ResolutionError(e)) => {
+                assert!(e.contains("non-public address"), "{}", e)
+            }
+            _ => panic!("{} should not resolve", did),
+        }
+    }
+}
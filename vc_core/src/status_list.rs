@@ -0,0 +1,271 @@
+use super::*;
+use flate2::{read::GzDecoder, write::GzEncoder, Compression};
+use std::io::{Read, Write};
+
+pub const BITSTRING_STATUS_LIST_CREDENTIAL_TYPE: &str = "BitstringStatusListCredential";
+pub const BITSTRING_STATUS_LIST_TYPE: &str = "BitstringStatusList";
+pub const BITSTRING_STATUS_LIST_ENTRY_TYPE: &str = "BitstringStatusListEntry";
+pub const REVOCATION_STATUS_PURPOSE: &str = "revocation";
+pub const SUSPENSION_STATUS_PURPOSE: &str = "suspension";
+pub const MESSAGE_STATUS_PURPOSE: &str = "message";
+// The specification requires at least 16KB of entries so that a single status reveals little.
+pub const MIN_STATUS_LIST_LENGTH: usize = 131_072;
+// Status lists come from remote servers, so decoding stops at a 16MB bitstring rather than
+// inflating whatever a compressed list expands to.
+pub const MAX_STATUS_LIST_LENGTH: usize = 134_217_728;
+
+#[derive(Debug)]
+pub enum StatusListError {
+    InvalidIndex(String),
+    InvalidEncoding(String),
+    InvalidStatusListCredential(String),
+}
+
+impl Error for StatusListError {}
+
+impl fmt::Display for StatusListError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidIndex(e) => write!(f, "Invalid status list index: {}", e),
+            Self::InvalidEncoding(e) => write!(f, "Invalid status list encoding: {}", e),
+            Self::InvalidStatusListCredential(e) => {
+                write!(f, "Invalid status list credential: {}", e)
+            }
+        }
+    }
+}
+
+#[derive(Clone, PartialEq)]
+pub struct StatusList {
+    bitstring: Vec<u8>,
+}
+
+impl StatusList {
+    pub fn new(length: usize) -> Self {
+        Self {
+            bitstring: vec![0; length.div_ceil(8)],
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.bitstring.len() * 8
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.bitstring.is_empty()
+    }
+
+    // Index 0 is the left-most bit of the bitstring, i.e. the most significant bit of the first byte.
+    fn locate(&self, index: usize) -> Result<(usize, u8), StatusListError> {
+        if index >= self.len() {
+            return Err(StatusListError::InvalidIndex(format!(
+                "{} is out of range for a list of length {}.",
+                index,
+                self.len()
+            )));
+        }
+        Ok((index / 8, 0x80 >> (index % 8)))
+    }
+
+    pub fn get(&self, index: usize) -> Result<bool, StatusListError> {
+        let (byte, mask) = self.locate(index)?;
+        Ok(self.bitstring[byte] & mask != 0)
+    }
+
+    pub fn set(&mut self, index: usize, value: bool) -> Result<(), StatusListError> {
+        let (byte, mask) = self.locate(index)?;
+        if value {
+            self.bitstring[byte] |= mask;
+        } else {
+            self.bitstring[byte] &= !mask;
+        }
+        Ok(())
+    }
+
+    // Encodes the bitstring as a GZIP-compressed, multibase base64url string.
+    pub fn encode(&self) -> Result<String, StatusListError> {
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder
+            .write_all(&self.bitstring)
+            .map_err(|e| StatusListError::InvalidEncoding(e.to_string()))?;
+        let compressed = encoder
+            .finish()
+            .map_err(|e| StatusListError::InvalidEncoding(e.to_string()))?;
+        Ok(multibase::encode_base64url(&compressed))
+    }
+
+    pub fn decode(encoded_list: &str) -> Result<Self, StatusListError> {
+        let compressed = multibase::decode(encoded_list)
+            .map_err(|e| StatusListError::InvalidEncoding(e.to_string()))?;
+        let max_bytes = MAX_STATUS_LIST_LENGTH / 8;
+        let mut bitstring = Vec::new();
+        GzDecoder::new(compressed.as_slice())
+            .take(max_bytes as u64 + 1)
+            .read_to_end(&mut bitstring)
+            .map_err(|e| StatusListError::InvalidEncoding(e.to_string()))?;
+        if bitstring.len() > max_bytes {
+            return Err(StatusListError::InvalidEncoding(format!(
+                "Status lists cannot be longer than {} entries.",
+                MAX_STATUS_LIST_LENGTH
+            )));
+        }
+        Ok(Self { bitstring })
+    }
+}
+
+pub fn new_status_list_credential(
+    id: URL,
+    issuer: URL,
+    valid_from: DateTime<Utc>,
+    valid_until: DateTime<Utc>,
+    status_purpose: &str,
+    status_list: &StatusList,
+) -> Result<Credential, StatusListError> {
+    let context = URL::new(CREDENTIALS_CONTEXT)
+        .map_err(|e| StatusListError::InvalidStatusListCredential(e.to_string()))?;
+    let text = |value: String| ClaimProperty::Value(ClaimPropertyValue::Text(value));
+    let mut credential_subject = HashMap::new();
+    credential_subject.insert("id".to_string(), text(format!("{}#list", id)));
+    credential_subject.insert(
+        "type".to_string(),
+        text(BITSTRING_STATUS_LIST_TYPE.to_string()),
+    );
+    credential_subject.insert(
+        "statusPurpose".to_string(),
+        text(status_purpose.to_string()),
+    );
+    credential_subject.insert("encodedList".to_string(), text(status_list.encode()?));
+    Ok(Credential::new(
+        vec![context],
+        id,
+        vec![
+            "VerifiableCredential".to_string(),
+            BITSTRING_STATUS_LIST_CREDENTIAL_TYPE.to_string(),
+        ],
+        issuer,
+        valid_from,
+        valid_until,
+        credential_subject,
+        Vec::new(),
+    ))
+}
+
+fn get_subject_text<'a>(credential: &'a Credential, key: &str) -> Result<&'a str, StatusListError> {
+    match credential.get_credential_subject().get(key) {
+        Some(ClaimProperty::Value(ClaimPropertyValue::Text(value))) => Ok(value),
+        _ => Err(StatusListError::InvalidStatusListCredential(format!(
+            "Missing {} in credential subject.",
+            key
+        ))),
+    }
+}
+
+// Returns whether the bit referenced by a status entry is set in the given status list credential.
+pub fn get_status(
+    credential_status: &CredentialStatus,
+    status_list_credential: &Credential,
+) -> Result<bool, StatusListError> {
+    if status_list_credential.get_id() != credential_status.get_status_list_credential() {
+        return Err(StatusListError::InvalidStatusListCredential(format!(
+            "Expected {} but got {}.",
+            credential_status.get_status_list_credential(),
+            status_list_credential.get_id()
+        )));
+    }
+    if !status_list_credential
+        .get_type()
+        .iter()
+        .any(|type_| type_ == BITSTRING_STATUS_LIST_CREDENTIAL_TYPE)
+    {
+        return Err(StatusListError::InvalidStatusListCredential(
+            "Not a status list credential.".to_string(),
+        ));
+    }
+    let status_purpose = get_subject_text(status_list_credential, "statusPurpose")?;
+    if status_purpose != credential_status.get_status_purpose() {
+        return Err(StatusListError::InvalidStatusListCredential(format!(
+            "Expected status purpose {} but got {}.",
+            credential_status.get_status_purpose(),
+            status_purpose
+        )));
+    }
+    let status_list = StatusList::decode(get_subject_text(status_list_credential, "encodedList")?)?;
+    status_list.get(credential_status.get_status_list_index()?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trips_encoded_status_lists() {
+        let mut status_list = StatusList::new(MIN_STATUS_LIST_LENGTH);
+        assert_eq!(status_list.len(), MIN_STATUS_LIST_LENGTH);
+        for index in [0, 7, 8, 1234, MIN_STATUS_LIST_LENGTH - 1] {
+            status_list.set(index, true).unwrap();
+        }
+        status_list.set(1234, false).unwrap();
+
+        let encoded_list = status_list.encode().unwrap();
+        assert!(encoded_list.starts_with('u'));
+        let decoded_list = StatusList::decode(&encoded_list).unwrap();
+        assert!(decoded_list == status_list);
+        for (index, value) in [
+            (0, true),
+            (1, false),
+            (7, true),
+            (8, true),
+            (1234, false),
+            (MIN_STATUS_LIST_LENGTH - 1, true),
+        ] {
+            assert_eq!(decoded_list.get(index).unwrap(), value, "{}", index);
+        }
+        // The left-most bit is the first entry.
+        assert_eq!(decoded_list.bitstring[0], 0x81);
+        assert!(matches!(
+            decoded_list.get(MIN_STATUS_LIST_LENGTH),
+            Err(StatusListError::InvalidIndex(_))
+        ));
+    }
+
+    #[test]
+    fn decodes_specification_example() {
+        // The empty 16KB list from the Bitstring Status List specification's examples.
+        let status_list = StatusList::decode(
+            "uH4sIAAAAAAAAA-3BMQEAAADCoPVPbQwfoAAAAAAAAAAAAAAAAAAAAIC3AYbSVKsAQAAA",
+        )
+        .unwrap();
+        assert_eq!(status_list.len(), MIN_STATUS_LIST_LENGTH);
+        assert!(status_list.bitstring.iter().all(|byte| *byte == 0));
+    }
+
+    #[test]
+    fn rejects_oversized_and_malformed_status_lists() {
+        let largest_list = StatusList::new(MAX_STATUS_LIST_LENGTH);
+        assert_eq!(
+            StatusList::decode(&largest_list.encode().unwrap())
+                .unwrap()
+                .len(),
+            MAX_STATUS_LIST_LENGTH
+        );
+        // Some twenty kilobytes of compressed zeros inflate past the limit.
+        let oversized_list = StatusList::new(MAX_STATUS_LIST_LENGTH + 8);
+        let encoded_list = oversized_list.encode().unwrap();
+        assert!(encoded_list.len() < 100_000);
+        assert!(matches!(
+            StatusList::decode(&encoded_list),
+            Err(StatusListError::InvalidEncoding(_))
+        ));
+
+        for encoded_list in ["", "uH4sI", "zH4sI", "unot-gzip"] {
+            assert!(
+                matches!(
+                    StatusList::decode(encoded_list),
+                    Err(StatusListError::InvalidEncoding(_))
+                ),
+                "{}",
+                encoded_list
+            );
+        }
+    }
+}